@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
     use ark_std::{end_timer, start_timer};
-    use halo2_proofs::plonk::{create_proof, keygen_pk, keygen_vk, verify_proof};
+    use halo2_proofs::plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, Circuit};
     use halo2_proofs::poly::kzg::commitment::{KZGCommitmentScheme, ParamsKZG, ParamsVerifierKZG};
     use halo2_proofs::poly::kzg::multiopen::{ProverSHPLONK, VerifierSHPLONK};
     use halo2_proofs::poly::kzg::strategy::SingleStrategy;
@@ -14,7 +14,8 @@ mod tests {
     };
     use rand::SeedableRng;
     use rand_xorshift::XorShiftRng;
-    use sha2_256_circuit::dev::{Sha2TestCircuit, INPUTS_OUTPUTS};
+    use sha2_256_circuit::dev::{digest_instances, Sha2TestCircuit, INPUTS_OUTPUTS, MAX_BLOCKS};
+    use sha2_256_circuit::Sha2Layout;
     use std::{env::var, marker::PhantomData};
 
     use crate::constants::{PROOFGEN_PREFIX, PROOFVER_PREFIX, SETUP_PREFIX};
@@ -31,6 +32,10 @@ mod tests {
 
         // Create SHA2-256 circuit with some test vectors.
         let (inputs, outputs) = INPUTS_OUTPUTS.clone();
+        Sha2Layout::new::<Fr>(MAX_BLOCKS)
+            .check(&inputs, degree)
+            .expect("DEGREE is too small for the SHA2-256 circuit");
+        let instances: Vec<Fr> = digest_instances(&inputs, &outputs, MAX_BLOCKS);
         let circuit: Sha2TestCircuit<Fr> = Sha2TestCircuit {
             inputs,
             outputs,
@@ -50,9 +55,10 @@ mod tests {
         let verifier_params: ParamsVerifierKZG<Bn256> = general_params.verifier_params().clone();
         end_timer!(start1);
 
-        // Initialize the proving/verifying key.
-        let vk = keygen_vk(&general_params, &circuit).expect("keygen_vk should not fail");
-        let pk = keygen_pk(&general_params, vk, &circuit).expect("keygen_pk should not fail");
+        // Initialize the proving/verifying key, without the witness.
+        let keygen_circuit = circuit.without_witnesses();
+        let vk = keygen_vk(&general_params, &keygen_circuit).expect("keygen_vk should not fail");
+        let pk = keygen_pk(&general_params, vk, &keygen_circuit).expect("keygen_pk should not fail");
         let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
 
         // Bench proof generation time.
@@ -72,7 +78,7 @@ mod tests {
             &general_params,
             &pk,
             &[circuit],
-            &[&[]],
+            &[&[&instances[..]]],
             rng,
             &mut transcript,
         )
@@ -94,7 +100,7 @@ mod tests {
             &verifier_params,
             pk.get_vk(),
             strategy,
-            &[&[]],
+            &[&[&instances[..]]],
             &mut verifier_transcript,
         )
         .expect("failed to verify bench circuit");
@@ -0,0 +1,391 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::Error,
+};
+
+use crate::Sha2Config;
+use crate::regions::table::TABLE_ROWS_PER_BLOCK;
+use crate::utils::pad_message;
+use crate::word::{AssignedWord, Word32};
+
+/// The SHA-256 compression function over already assigned words, for circuits
+/// using SHA-256 as a building block (Merkle trees, HMAC, ...).
+///
+/// The gadget runs on the columns, gates and lookups of a `Sha2Config`, whose
+/// tables must be loaded with `Sha2Config::load_tables`. Every call to
+/// `compress` lays out its own region with the message schedule and the
+/// compression of one block, in the same layout as a block slot of the
+/// `Sha2Chip`.
+#[derive(Clone, Debug)]
+pub struct Sha256CompressionGadget<F: FieldExt> {
+    config: Sha2Config<F>,
+}
+
+impl<F: FieldExt> Sha256CompressionGadget<F> {
+    pub fn construct(config: Sha2Config<F>) -> Self {
+        Self { config }
+    }
+
+    /// Assigns the IV of the mode of the config, the SHA-256 one by default,
+    /// constrained to its constant value.
+    pub fn iv(&self, layouter: &mut impl Layouter<F>) -> Result<[AssignedWord<F>; 8], Error> {
+        let iv = self.constant_words(layouter, &self.config.mode.iv())?;
+        iv.try_into().map_err(|_| Error::Synthesis)
+    }
+
+    /// Assigns `words` constrained to their constant values, such as the IV
+    /// or the padding words of a block.
+    pub fn constant_words(&self, layouter: &mut impl Layouter<F>, words: &[u32]) -> Result<Vec<AssignedWord<F>>, Error> {
+        layouter.assign_region(
+            || "SHA256 constant words",
+            |mut region| {
+                let mut assigned = vec![];
+                for (i, word) in words.iter().enumerate() {
+                    let word = Word32::from(*word);
+                    let lo = region.assign_advice_from_constant(
+                        || "constant lo", self.config.cols[3], 2 * i, F::from(word.lo as u64)
+                    )?;
+                    let hi = region.assign_advice_from_constant(
+                        || "constant hi", self.config.cols[3], 2 * i + 1, F::from(word.hi as u64)
+                    )?;
+                    assigned.push(AssignedWord::new(lo, hi, Value::known(word)));
+                }
+                Ok(assigned)
+            }
+        )
+    }
+
+    /// Compresses the 16 words of `block` into `state` and returns the new
+    /// state.
+    ///
+    /// The words of `block` are range checked by the message schedule and the
+    /// limbs of `state` are range checked to 16 bits on the rows following the
+    /// compression, so that `state` may come from any chip. The limbs of the
+    /// returned state are range checked by the digest.
+    pub fn compress(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        state: [AssignedWord<F>; 8],
+        block: [AssignedWord<F>; 16],
+    ) -> Result<[AssignedWord<F>; 8], Error> {
+        let layout = self.config.layout.block;
+        let state = layouter.assign_region(
+            || "SHA256 compression",
+            |mut region| {
+                let w = block.iter().map(|word| word.value).collect();
+                let w = self.config.message_schedule_chip.load(&mut region, w, layout.schedule)?;
+                for (w, word) in w.iter().zip(block.iter()) {
+                    region.constrain_equal(w.lo.cell(), word.lo.cell())?;
+                    region.constrain_equal(w.hi.cell(), word.hi.cell())?;
+                }
+                self.config.compression_chip.assign_range(&mut region, &state, layout.init)?;
+                self.config.compression_chip.load_steady(
+                    &mut region, &w, state.to_vec(), layout.compression
+                )
+            }
+        )?;
+        state.try_into().map_err(|_| Error::Synthesis)
+    }
+
+    /// Compresses whole blocks of bytes assigned by another chip into `state`,
+    /// without padding, and returns the new state.
+    ///
+    /// Every cell of `bytes`, whose number must be a multiple of the block
+    /// size, is copied into the range checked bytes of a block.
+    pub fn compress_bytes(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        state: [AssignedWord<F>; 8],
+        bytes: &[AssignedCell<F, F>],
+    ) -> Result<[AssignedWord<F>; 8], Error> {
+        if bytes.len() % TABLE_ROWS_PER_BLOCK != 0 {
+            return Err(Error::Synthesis);
+        }
+        let mut state = state;
+        for (b, block) in bytes.chunks(TABLE_ROWS_PER_BLOCK).enumerate() {
+            let words = layouter.assign_region(
+                || format!("SHA256 unpadded bytes {}", b),
+                |mut region| {
+                    let block_bytes: Vec<Value<u8>> = block.iter()
+                        .map(|byte| byte.value().map(|byte| byte.get_lower_32() as u8))
+                        .collect();
+                    let (byte_c, words) = self.config.table_chip.assign_bytes(&mut region, &block_bytes, 0)?;
+                    for (byte, byte_c) in block.iter().zip(byte_c.iter()) {
+                        region.constrain_equal(byte.cell(), byte_c.cell())?;
+                    }
+                    Ok(words)
+                }
+            )?;
+            let words = words.try_into().map_err(|_| Error::Synthesis)?;
+            state = self.compress(layouter, state, words)?;
+        }
+        Ok(state)
+    }
+
+    /// Hashes a message whose bytes were assigned by another chip, starting
+    /// from `state`, and returns the final state.
+    ///
+    /// `state` is the state after the first `prefix_len` bytes of the message,
+    /// which must be a multiple of the block size, so that the length in the
+    /// padding covers them too. Every cell of `bytes` is copied into the range checked bytes
+    /// of the padded message, whose padding is constrained to constants.
+    pub fn hash_bytes(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        state: [AssignedWord<F>; 8],
+        bytes: &[AssignedCell<F, F>],
+        prefix_len: usize,
+    ) -> Result<[AssignedWord<F>; 8], Error> {
+        if prefix_len % TABLE_ROWS_PER_BLOCK != 0 {
+            return Err(Error::Synthesis);
+        }
+        let blocks = layouter.assign_region(
+            || "SHA256 prefix blocks",
+            |mut region| region.assign_advice_from_constant(
+                || "prefix blocks", self.config.cols[3], 0, F::from((prefix_len / TABLE_ROWS_PER_BLOCK) as u64)
+            )
+        )?;
+        self.hash_bytes_after(layouter, state, bytes, &blocks)
+    }
+
+    /// Hashes the end of a message whose bytes were assigned by another chip,
+    /// starting from `state`, the state after the first `blocks` blocks of the
+    /// message, and returns the final state.
+    ///
+    /// Unlike `hash_bytes`, the number of blocks before `bytes` is a cell, so
+    /// the same circuit finalizes messages of any prefix length: the length
+    /// field of the padding is constrained to the bit length of `blocks`
+    /// blocks followed by `bytes`, and the rest of the padding to constants.
+    pub fn hash_bytes_after(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        state: [AssignedWord<F>; 8],
+        bytes: &[AssignedCell<F, F>],
+        blocks: &AssignedCell<F, F>,
+    ) -> Result<[AssignedWord<F>; 8], Error> {
+        let padding = pad_message(&vec![0u8; bytes.len()]);
+        let message = bytes.iter().fold(Value::known(vec![]), |acc, byte| {
+            acc.zip(byte.value()).map(|(mut acc, byte)| {
+                acc.push(byte.get_lower_32() as u8);
+                acc
+            })
+        });
+        let prefix_blocks = blocks.value().map(|blocks| blocks.get_lower_128() as u64);
+        let padded = message.zip(prefix_blocks).map(|(message, prefix_blocks)| {
+            let len = prefix_blocks * TABLE_ROWS_PER_BLOCK as u64 + message.len() as u64;
+            let mut padded = pad_message(&message);
+            let n = padded.len();
+            padded[n - 8..].copy_from_slice(&(len * 8).to_be_bytes());
+            padded
+        });
+
+        let num_blocks = padding.len() / TABLE_ROWS_PER_BLOCK;
+        let mut state = state;
+        for (b, block) in padding.chunks(TABLE_ROWS_PER_BLOCK).enumerate() {
+            let offset = b * TABLE_ROWS_PER_BLOCK;
+            let is_last = b == num_blocks - 1;
+            let words = layouter.assign_region(
+                || format!("SHA256 assigned bytes {}", b),
+                |mut region| {
+                    let block_bytes: Vec<Value<u8>> = (0..TABLE_ROWS_PER_BLOCK)
+                        .map(|j| padded.as_ref().map(|padded| padded[offset + j]))
+                        .collect();
+                    let (byte_c, words) = self.config.table_chip.assign_bytes(&mut region, &block_bytes, 0)?;
+                    for (j, byte_c) in byte_c.iter().enumerate() {
+                        match bytes.get(offset + j) {
+                            Some(byte) => region.constrain_equal(byte.cell(), byte_c.cell())?,
+                            None if is_last && j >= TABLE_ROWS_PER_BLOCK - 8 => (),
+                            None => region.constrain_constant(byte_c.cell(), F::from(block[j] as u64))?,
+                        }
+                    }
+                    if is_last {
+                        self.config.midstate.assign_length(
+                            &mut region, &byte_c[TABLE_ROWS_PER_BLOCK - 8..], blocks, bytes.len() as u64, 0
+                        )?;
+                    }
+                    Ok(words)
+                }
+            )?;
+            let words = words.try_into().map_err(|_| Error::Synthesis)?;
+            state = self.compress(layouter, state, words)?;
+        }
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::marker::PhantomData;
+    use crate::{Sha2Table, Sha256Trace};
+    use crate::utils::{message_words, pad_message};
+    use halo2_proofs::circuit::SimpleFloorPlanner;
+    use halo2_proofs::plonk::{Circuit, ConstraintSystem};
+    use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr as F};
+
+    /// Hashes `input` block by block with `compress`. With `forged_state`,
+    /// the IV limbs of `h[3]` are moved by `2^16` without changing its value.
+    #[derive(Default, Clone)]
+    struct CompressionCircuit<F: FieldExt> {
+        input: Vec<u8>,
+        forged_state: bool,
+        _marker: PhantomData<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for CompressionCircuit<F> {
+        type Config = Sha2Config<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = Sha2Table::construct(meta);
+            Sha2Config::configure(meta, table, 1)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            config.load_tables(&mut layouter)?;
+            let gadget = Sha256CompressionGadget::construct(config.clone());
+
+            let mut state = gadget.iv(&mut layouter)?;
+            if self.forged_state {
+                let word = state[3].value;
+                state[3] = layouter.assign_region(
+                    || "forged state word",
+                    |mut region| {
+                        let lo = word.map(|word| F::from(word.lo as u64) + F::from(1 << 16));
+                        let hi = word.map(|word| F::from(word.hi as u64) - F::one());
+                        let lo = region.assign_advice(|| "h lo", config.cols[3], 0, || lo)?;
+                        let hi = region.assign_advice(|| "h hi", config.cols[3], 1, || hi)?;
+                        Ok(AssignedWord::new(lo, hi, word))
+                    }
+                )?;
+            }
+            for block in pad_message(&self.input).chunks(64) {
+                let limbs = message_words(block);
+                let words = layouter.assign_region(
+                    || "block words",
+                    |mut region| {
+                        let mut words = vec![];
+                        for (i, limbs) in limbs.chunks(2).enumerate() {
+                            let lo = region.assign_advice(
+                                || "w lo", config.cols[3], 2 * i, || Value::known(F::from(limbs[0] as u64))
+                            )?;
+                            let hi = region.assign_advice(
+                                || "w hi", config.cols[3], 2 * i + 1, || Value::known(F::from(limbs[1] as u64))
+                            )?;
+                            words.push(AssignedWord::new(lo, hi, Value::known(Word32::new(limbs[0], limbs[1]))));
+                        }
+                        Ok(words)
+                    }
+                )?;
+                let words = words.try_into().map_err(|_| Error::Synthesis)?;
+                state = gadget.compress(&mut layouter, state, words)?;
+            }
+
+            let digest = layouter.assign_region(
+                || "digest words",
+                |mut region| config.compression_chip.assign_words(&mut region, &state, 0)
+            )?;
+            for (i, word) in digest.iter().enumerate() {
+                layouter.constrain_instance(word.cell(), config.instance, i)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Hashes `input` from byte cells with `hash_bytes`, starting from the IV
+    /// as if after `prefix_len` bytes.
+    #[derive(Default, Clone)]
+    struct HashBytesCircuit<F: FieldExt> {
+        input: Vec<u8>,
+        prefix_len: usize,
+        _marker: PhantomData<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for HashBytesCircuit<F> {
+        type Config = Sha2Config<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = Sha2Table::construct(meta);
+            Sha2Config::configure(meta, table, 1)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            config.load_tables(&mut layouter)?;
+            let gadget = Sha256CompressionGadget::construct(config.clone());
+            let bytes = layouter.assign_region(
+                || "message bytes",
+                |mut region| {
+                    self.input.iter().enumerate().map(|(i, byte)| region.assign_advice(
+                        || "byte", config.cols[3], i, || Value::known(F::from(*byte as u64))
+                    )).collect::<Result<Vec<_>, Error>>()
+                }
+            )?;
+            let iv = gadget.iv(&mut layouter)?;
+            let state = gadget.hash_bytes(&mut layouter, iv, &bytes, self.prefix_len)?;
+            let digest = layouter.assign_region(
+                || "digest words",
+                |mut region| config.compression_chip.assign_words(&mut region, &state, 0)
+            )?;
+            for (i, word) in digest.iter().enumerate() {
+                layouter.constrain_instance(word.cell(), config.instance, i)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn digest_words(input: &[u8]) -> Vec<F> {
+        let trace = Sha256Trace::new(input);
+        let h_out = trace.blocks.last().expect("padded message has a block").h_out;
+        h_out.iter().map(|word| F::from(*word as u64)).collect()
+    }
+
+    #[test]
+    fn test_compression_gadget() {
+        for input in [b"abc".to_vec(), vec![b'a'; 100]] {
+            let circuit = CompressionCircuit::<F> { input: input.clone(), forged_state: false, _marker: PhantomData };
+            let prover = MockProver::run(17, &circuit, vec![digest_words(&input)]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_compression_gadget_wrong_digest() {
+        let circuit = CompressionCircuit::<F> { input: b"abc".to_vec(), forged_state: false, _marker: PhantomData };
+        let mut digest = digest_words(b"abc");
+        digest[7] += F::one();
+        let prover = MockProver::run(17, &circuit, vec![digest]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_compression_gadget_out_of_range_state() {
+        let circuit = CompressionCircuit::<F> { input: b"abc".to_vec(), forged_state: true, _marker: PhantomData };
+        let prover = MockProver::run(17, &circuit, vec![digest_words(b"abc")]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_hash_bytes() {
+        let circuit = HashBytesCircuit::<F> { input: b"abc".to_vec(), prefix_len: 0, _marker: PhantomData };
+        let prover = MockProver::run(17, &circuit, vec![digest_words(b"abc")]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_hash_bytes_partial_prefix() {
+        // A prefix that is not made of whole blocks is a synthesis error.
+        let circuit = HashBytesCircuit::<F> { input: b"abc".to_vec(), prefix_len: 3, _marker: PhantomData };
+        assert!(MockProver::run(17, &circuit, vec![digest_words(b"abc")]).is_err());
+    }
+}
@@ -1,10 +1,11 @@
 use std::{marker::PhantomData, vec};
 
 use crate::utils::create_range_2_check;
+use crate::word::{AssignedWord, Word32};
 
 use halo2_proofs::{
     arithmetic::FieldExt,
-    circuit::{Region, Value, AssignedCell},
+    circuit::{Region, Value},
     plonk::{Advice, Column, ConstraintSystem, Expression, Selector, Error},
     poly::Rotation,
 };
@@ -79,30 +80,26 @@ impl<F: FieldExt> ANewConfig<F> {
     pub fn assign(
         &self,
         region: &mut Region<F>,
-        a_lo: u16,
-        a_hi: u16,
-        a_c: u64,
-        h_prime_lo: AssignedCell<F, F>,
-        h_prime_hi: AssignedCell<F, F>,
-        sum_lo: AssignedCell<F, F>,
-        sum_hi: AssignedCell<F, F>,
+        a: Value<Word32>,
+        a_c: Value<u64>,
+        h_prime: &AssignedWord<F>,
+        sum: &AssignedWord<F>,
         offset: usize,
-    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+    ) -> Result<AssignedWord<F>, Error> {
         
         self.s_a_new.enable(region, offset)?;
 
-        h_prime_lo.copy_advice(|| "s_a_new h_prime_lo", region, self.a7, offset-1)?;
-        h_prime_hi.copy_advice(|| "s_a_new h_prime_hi", region, self.a8, offset-1)?;
+        h_prime.lo.copy_advice(|| "s_a_new h_prime_lo", region, self.a7, offset-1)?;
+        h_prime.hi.copy_advice(|| "s_a_new h_prime_hi", region, self.a8, offset-1)?;
 
-        sum_lo.copy_advice(|| "s_a_new sum_zero_lo", region, self.a6, offset)?;
-        let a_lo = region.assign_advice(|| "s_a_new a_new lo", self.a8, offset, || Value::known(F::from(a_lo as u64)))?;
-        region.assign_advice(|| "s_a_new a_new carry", self.a9, offset, || Value::known(F::from(a_c)))?;
+        sum.lo.copy_advice(|| "s_a_new sum_zero_lo", region, self.a6, offset)?;
+        let a_lo = region.assign_advice(|| "s_a_new a_new lo", self.a8, offset, || a.map(|a| F::from(a.lo as u64)))?;
+        region.assign_advice(|| "s_a_new a_new carry", self.a9, offset, || a_c.map(F::from))?;
 
-        sum_hi.copy_advice(|| "s_a_new sum_zero_hi", region, self.a6, offset+1)?;
-        let a_hi = region.assign_advice(|| "s_a_new a_new hi", self.a8, offset+1, || Value::known(F::from(a_hi as u64)))?;
+        sum.hi.copy_advice(|| "s_a_new sum_zero_hi", region, self.a6, offset+1)?;
+        let a_hi = region.assign_advice(|| "s_a_new a_new hi", self.a8, offset+1, || a.map(|a| F::from(a.hi as u64)))?;
 
-        let res = vec![a_lo, a_hi];
-        Ok(res)
+        Ok(AssignedWord::new(a_lo, a_hi, a))
 
 
     }
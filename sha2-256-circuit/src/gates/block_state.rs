@@ -0,0 +1,167 @@
+use std::marker::PhantomData;
+
+use crate::utils::create_value_2_check;
+use crate::word::{AssignedWord, Word32};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Region, Value, AssignedCell},
+    plonk::{Advice, Column, ConstraintSystem, Expression, Selector, Error},
+    poly::Rotation,
+};
+
+#[derive(Debug, Clone)]
+pub struct BlockStateConfig<F: FieldExt> {
+    pub s_init: Selector,
+    pub s_out: Selector,
+    a3: Column<Advice>,
+    a4: Column<Advice>,
+    a5: Column<Advice>,
+    a6: Column<Advice>,
+    a7: Column<Advice>,
+    a8: Column<Advice>,
+    _marker: PhantomData<F>
+}
+
+impl<F: FieldExt> BlockStateConfig<F> {
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a3: Column<Advice>,
+        a4: Column<Advice>,
+        a5: Column<Advice>,
+        a6: Column<Advice>,
+        a7: Column<Advice>,
+        a8: Column<Advice>,
+    ) -> Self {
+        let s_init = meta.selector();
+        let s_out = meta.selector();
+
+        meta.create_gate(
+            "Initial State",
+            |meta| {
+                let s_init = meta.query_selector(s_init);
+                let is_first = meta.query_advice(a3, Rotation::cur());
+                let iv = meta.query_advice(a4, Rotation::cur());
+                let prev = meta.query_advice(a5, Rotation::cur());
+                let h = meta.query_advice(a6, Rotation::cur());
+
+                let select_constraint =
+                    is_first.clone() * iv +
+                    (Expression::Constant(F::one()) - is_first.clone()) * prev -
+                    h;
+
+                vec![
+                    s_init.clone() * select_constraint,
+                    s_init * create_value_2_check(is_first)
+                ]
+            }
+        );
+
+        meta.create_gate(
+            "Final Digest",
+            |meta| {
+                let s_out = meta.query_selector(s_out);
+                let is_final = meta.query_advice(a6, Rotation::cur());
+                let h = meta.query_advice(a7, Rotation::cur());
+                let out = meta.query_advice(a8, Rotation::cur());
+
+                vec![
+                    s_out.clone() * (is_final.clone() * h - out),
+                    s_out * create_value_2_check(is_final)
+                ]
+            }
+        );
+
+        Self {
+            s_init,
+            s_out,
+            a3, a4, a5, a6, a7, a8,
+            _marker: PhantomData
+        }
+    }
+
+    /// Number of rows used by `assign_init`, one per limb of the state.
+    pub fn init_rows() -> usize {
+        16
+    }
+
+    /// Number of rows used by `assign_out`, one per word of the digest.
+    pub fn out_rows() -> usize {
+        8
+    }
+
+    /// Selects the initial state of a block limb by limb: the IV when
+    /// `is_first` is set, the output state of the previous block otherwise.
+    /// The `iv` limbs are constrained to constants, so `iv` must be fixed at
+    /// configure time, as is the IV of a `Sha2Mode`.
+    pub fn assign_init(
+        &self,
+        region: &mut Region<F>,
+        is_first: Value<bool>,
+        iv: Vec<Word32>,
+        prev: Option<Vec<AssignedWord<F>>>,
+        offset: usize,
+    ) -> Result<(AssignedCell<F, F>, Vec<AssignedWord<F>>), Error> {
+        let is_first_c = region.assign_advice(|| "s_init is_first", self.a3, offset, || is_first.map(|is_first| F::from(is_first as u64)))?;
+        let mut limbs = vec![];
+        for i in 0..Self::init_rows() {
+            let limb = |w: &Word32| if i % 2 == 0 { w.lo } else { w.hi };
+            let iv_limb = F::from(limb(&iv[i/2]) as u64);
+            self.s_init.enable(region, offset+i)?;
+            if i > 0 {
+                is_first_c.copy_advice(|| "s_init is_first", region, self.a3, offset+i)?;
+            }
+            let iv_c = region.assign_advice(|| "s_init iv", self.a4, offset+i, || Value::known(iv_limb))?;
+            region.constrain_constant(iv_c.cell(), iv_limb)?;
+            // The first block of the circuit has no predecessor, its `prev`
+            // limbs are free and must be ignored by forcing `is_first`.
+            let prev_c = match &prev {
+                Some(prev) => {
+                    let prev_limb = if i % 2 == 0 { &prev[i/2].lo } else { &prev[i/2].hi };
+                    prev_limb.copy_advice(|| "s_init prev", region, self.a5, offset+i)?
+                }
+                None => region.assign_advice(|| "s_init prev", self.a5, offset+i, || Value::known(iv_limb))?,
+            };
+            let h_val = is_first.zip(prev_c.value()).map(|(is_first, prev)| {
+                if is_first {
+                    iv_limb
+                } else {
+                    *prev
+                }
+            });
+            let h_c = region.assign_advice(|| "s_init h", self.a6, offset+i, || h_val)?;
+            limbs.push(h_c);
+        }
+
+        let mut h = vec![];
+        for (j, limbs) in limbs.chunks(2).enumerate() {
+            let prev_val = prev.as_ref().map_or(Value::known(iv[j]), |prev| prev[j].value);
+            let h_val = is_first.zip(prev_val).map(|(is_first, prev)| if is_first { iv[j] } else { prev });
+            h.push(AssignedWord::new(limbs[0].clone(), limbs[1].clone(), h_val));
+        }
+        Ok((is_first_c, h))
+    }
+
+    /// Masks the digest words of a block with its `is_final` flag, so only
+    /// the final block of a message exposes its state.
+    pub fn assign_out(
+        &self,
+        region: &mut Region<F>,
+        is_final: AssignedCell<F, F>,
+        words: Vec<AssignedCell<F, F>>,
+        offset: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let mut out = vec![];
+        for (i, word) in words.iter().enumerate() {
+            self.s_out.enable(region, offset+i)?;
+            let is_final_c = is_final.copy_advice(|| "s_out is_final", region, self.a6, offset+i)?;
+            let word_c = word.copy_advice(|| "s_out h", region, self.a7, offset+i)?;
+            let out_val = is_final_c.value().zip(word_c.value()).map(|(f, h)| *f * *h);
+            let out_c = region.assign_advice(|| "s_out out", self.a8, offset+i, || out_val)?;
+            out.push(out_c);
+        }
+        Ok(out)
+    }
+
+}
@@ -0,0 +1,117 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, Region, Value, AssignedCell},
+    plonk::{Advice, Column, ConstraintSystem, Expression, Selector, TableColumn, Error},
+    poly::Rotation,
+};
+
+/// Decomposes the 16-bit limbs of message words into bytes.
+///
+/// Bytes are laid out one per row in `byte`, big-endian within a word as in
+/// the padded message, and range checked against an 8-bit table. Every odd row
+/// recomposes the byte pair ending on it into a limb in `limb`, so the bytes
+/// `4i..4i+4` give the limbs `w_hi = (4i, 4i+1)` and `w_lo = (4i+2, 4i+3)` of
+/// word `i`.
+#[derive(Debug, Clone)]
+pub struct ByteDecomposeConfig<F: FieldExt> {
+    pub s_byte: Selector,
+    pub s_limb: Selector,
+    byte: Column<Advice>,
+    limb: Column<Advice>,
+    byte_table: TableColumn,
+    _marker: PhantomData<F>
+}
+
+impl<F: FieldExt> ByteDecomposeConfig<F> {
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        byte: Column<Advice>,
+        limb: Column<Advice>,
+    ) -> Self {
+        let s_byte = meta.complex_selector();
+        let s_limb = meta.selector();
+        let byte_table = meta.lookup_table_column();
+
+        meta.lookup("Byte Range", |meta| {
+            let s_byte = meta.query_selector(s_byte);
+            let byte = meta.query_advice(byte, Rotation::cur());
+
+            vec![
+                (s_byte * byte, byte_table)
+            ]
+        });
+
+        meta.create_gate(
+            "Byte Recompose",
+            |meta| {
+                let s_limb = meta.query_selector(s_limb);
+                let limb = meta.query_advice(limb, Rotation::cur());
+                let lo = meta.query_advice(byte, Rotation::cur());
+                let hi = meta.query_advice(byte, Rotation::prev());
+
+                vec![
+                    s_limb * (hi * Expression::Constant(F::from(1 << 8)) + lo - limb)
+                ]
+            }
+        );
+
+        Self {
+            s_byte,
+            s_limb,
+            byte,
+            limb,
+            byte_table,
+            _marker: PhantomData
+        }
+    }
+
+    pub fn load(
+        &self,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_table(
+            || "load byte table",
+            |mut table| {
+                for i in 0..(1 << 8) {
+                    table.assign_cell(
+                        || "Byte",
+                        self.byte_table,
+                        i,
+                        || Value::known(F::from(i as u64))
+                    )?;
+                }
+                Ok(())
+            }
+        )
+    }
+
+    /// Assigns `bytes` from `offset` on and returns the byte cells together
+    /// with the limbs of the words they form, low limb first.
+    pub fn assign(
+        &self,
+        region: &mut Region<F>,
+        bytes: &[Value<u8>],
+        offset: usize,
+    ) -> Result<(Vec<AssignedCell<F, F>>, Vec<AssignedCell<F, F>>), Error> {
+        let mut byte_c = vec![];
+        let mut limb_c = vec![];
+        for (j, byte) in bytes.iter().enumerate() {
+            self.s_byte.enable(region, offset+j)?;
+            byte_c.push(region.assign_advice(|| "byte", self.byte, offset+j, || byte.map(|byte| F::from(byte as u64)))?);
+            if j % 2 == 1 {
+                self.s_limb.enable(region, offset+j)?;
+                let limb = bytes[j].zip(bytes[j-1]).map(|(lo, hi)| lo as u64 + ((hi as u64) << 8));
+                limb_c.push(region.assign_advice(|| "limb", self.limb, offset+j, || limb.map(F::from))?);
+            }
+        }
+        // Limbs come out as (hi, lo) per word.
+        for word in limb_c.chunks_mut(2) {
+            word.swap(0, 1);
+        }
+        Ok((byte_c, limb_c))
+    }
+
+}
@@ -4,9 +4,14 @@ use crate::utils::{
     even_bit,
     odd_bit,    
     create_interleave_num,
-    create_tag
+    create_tag,
+    spread_not,
 };
 
+use crate::word::{AssignedWord, AssignedWord32};
+
+use crate::spread_table::SpreadLookupConfig;
+
 use halo2_proofs::{
     arithmetic::FieldExt,
     circuit::{Region, Value, AssignedCell},
@@ -24,6 +29,7 @@ pub struct ChoiceConfig<F: FieldExt> {
     a3: Column<Advice>,
     a4: Column<Advice>,
     a5: Column<Advice>,
+    spread: SpreadLookupConfig<F>,
     _marker: PhantomData<F>
 }
 
@@ -37,6 +43,7 @@ impl<F: FieldExt> ChoiceConfig<F> {
         a3: Column<Advice>,
         a4: Column<Advice>,
         a5: Column<Advice>,
+        spread: SpreadLookupConfig<F>,
     ) -> Self {
         let s_ch = meta.selector();
         let s_ch_neg = meta.selector();
@@ -98,6 +105,7 @@ impl<F: FieldExt> ChoiceConfig<F> {
             s_ch,
             s_ch_neg,
             a0, a1, a2, a3, a4, a5,
+            spread,
             _marker: PhantomData
         }
     }
@@ -106,94 +114,95 @@ impl<F: FieldExt> ChoiceConfig<F> {
     pub fn assign_p(
         &self,
         region: &mut Region<F>,
-        p_lo: u32,
-        p_hi: u32,
-        s_e_lo: AssignedCell<F, F>,
-        s_e_hi: AssignedCell<F, F>,
-        s_f_lo: AssignedCell<F, F>,
-        s_f_hi: AssignedCell<F, F>,
+        p_lo: Value<u32>,
+        p_hi: Value<u32>,
+        e: &AssignedWord32<F>,
+        f: &AssignedWord32<F>,
         offset: usize,
-    ) -> Result<Vec<AssignedCell<F, F>>, Error>{
+    ) -> Result<(AssignedCell<F, F>, AssignedWord32<F>, AssignedWord32<F>), Error>{
 
         self.s_ch.enable(region, offset)?;
-        let p_e_0 = even_bit(p_lo);
-        let p_o_0 = odd_bit(p_lo);
-        let p_e_1 = even_bit(p_hi);
-        let p_o_1 = odd_bit(p_hi);
-
-        region.assign_advice(|| "s_ch p_e_0 tag", self.a0, offset-1, || Value::known(F::from(create_tag(p_e_0) as u64)))?;
-        region.assign_advice(|| "s_ch p_e_0", self.a1, offset-1, || Value::known(F::from(p_e_0 as u64)))?;
-        region.assign_advice(|| "s_ch s_p_e_0", self.a2, offset-1, || Value::known(F::from(create_interleave_num(p_e_0 as u32) as u64)))?;
-        let s_e_lo_c = s_e_lo.copy_advice(|| "s_ch s_e_lo", region, self.a3, offset-1)?;
-        let s_e_hi_c = s_e_hi.copy_advice(|| "s_ch s_e_hi", region, self.a4, offset-1)?;
+        let p_e_0 = p_lo.map(even_bit);
+        let p_o_0 = p_lo.map(odd_bit);
+        let p_e_1 = p_hi.map(even_bit);
+        let p_o_1 = p_hi.map(odd_bit);
+
+        self.spread.enable(region, offset-1, p_e_0)?;
+        region.assign_advice(|| "s_ch p_e_0 tag", self.a0, offset-1, || p_e_0.map(|p_e_0| F::from(create_tag(p_e_0) as u64)))?;
+        region.assign_advice(|| "s_ch p_e_0", self.a1, offset-1, || p_e_0.map(|p_e_0| F::from(p_e_0 as u64)))?;
+        region.assign_advice(|| "s_ch s_p_e_0", self.a2, offset-1, || p_e_0.map(|p_e_0| F::from(create_interleave_num(p_e_0 as u32) as u64)))?;
+        let s_e_lo_c = e.spread_lo.copy_advice(|| "s_ch s_e_lo", region, self.a3, offset-1)?;
+        let s_e_hi_c = e.spread_hi.copy_advice(|| "s_ch s_e_hi", region, self.a4, offset-1)?;
         
-        region.assign_advice(|| "s_ch p_o_0 tag", self.a0, offset, || Value::known(F::from(create_tag(p_o_0) as u64)))?;
-        region.assign_advice(|| "s_ch p_o_0", self.a1, offset, || Value::known(F::from(p_o_0 as u64)))?;
-        region.assign_advice(|| "s_ch s_p_o_0", self.a2, offset, || Value::known(F::from(create_interleave_num(p_o_0 as u32) as u64)))?;
-
-        region.assign_advice(|| "s_ch p_e_1 tag", self.a0, offset+1, || Value::known(F::from(create_tag(p_e_1) as u64)))?;
-        region.assign_advice(|| "s_ch p_e_1", self.a1, offset+1, || Value::known(F::from(p_e_1 as u64)))?;
-        region.assign_advice(|| "s_ch s_p_e_1", self.a2, offset+1, || Value::known(F::from(create_interleave_num(p_e_1 as u32) as u64)))?;
-        let s_f_lo_c = s_f_lo.copy_advice(|| "s_ch s_f_lo", region, self.a3, offset+1)?;
-        let s_f_hi_c =  s_f_hi.copy_advice(|| "s_ch s_f_hi", region, self.a4, offset+1)?;
-
-        region.assign_advice(|| "s_ch p_o_1 tag", self.a0, offset+2, || Value::known(F::from(create_tag(p_o_1) as u64)))?;
-        let p_o_1_c = region.assign_advice(|| "s_ch p_o_1", self.a1, offset+2, || Value::known(F::from(p_o_1 as u64)))?;
-        let s_p_o_1 = region.assign_advice(|| "s_ch s_p_o_1", self.a2, offset+2, || Value::known(F::from(create_interleave_num(p_o_1 as u32) as u64)))?;
+        self.spread.enable(region, offset, p_o_0)?;
+        region.assign_advice(|| "s_ch p_o_0 tag", self.a0, offset, || p_o_0.map(|p_o_0| F::from(create_tag(p_o_0) as u64)))?;
+        region.assign_advice(|| "s_ch p_o_0", self.a1, offset, || p_o_0.map(|p_o_0| F::from(p_o_0 as u64)))?;
+        region.assign_advice(|| "s_ch s_p_o_0", self.a2, offset, || p_o_0.map(|p_o_0| F::from(create_interleave_num(p_o_0 as u32) as u64)))?;
+
+        self.spread.enable(region, offset+1, p_e_1)?;
+        region.assign_advice(|| "s_ch p_e_1 tag", self.a0, offset+1, || p_e_1.map(|p_e_1| F::from(create_tag(p_e_1) as u64)))?;
+        region.assign_advice(|| "s_ch p_e_1", self.a1, offset+1, || p_e_1.map(|p_e_1| F::from(p_e_1 as u64)))?;
+        region.assign_advice(|| "s_ch s_p_e_1", self.a2, offset+1, || p_e_1.map(|p_e_1| F::from(create_interleave_num(p_e_1 as u32) as u64)))?;
+        let s_f_lo_c = f.spread_lo.copy_advice(|| "s_ch s_f_lo", region, self.a3, offset+1)?;
+        let s_f_hi_c =  f.spread_hi.copy_advice(|| "s_ch s_f_hi", region, self.a4, offset+1)?;
+
+        self.spread.enable(region, offset+2, p_o_1)?;
+        region.assign_advice(|| "s_ch p_o_1 tag", self.a0, offset+2, || p_o_1.map(|p_o_1| F::from(create_tag(p_o_1) as u64)))?;
+        let p_o_1_c = region.assign_advice(|| "s_ch p_o_1", self.a1, offset+2, || p_o_1.map(|p_o_1| F::from(p_o_1 as u64)))?;
+        let s_p_o_1 = region.assign_advice(|| "s_ch s_p_o_1", self.a2, offset+2, || p_o_1.map(|p_o_1| F::from(create_interleave_num(p_o_1 as u32) as u64)))?;
         s_p_o_1.copy_advice(|| "s_ch s_p_o_1 copy", region, self.a3, offset)?;
 
-        let res = vec![p_o_1_c, s_e_lo_c, s_e_hi_c, s_f_lo_c, s_f_hi_c];
-        Ok(res)
+        Ok((p_o_1_c, e.with_spread(s_e_lo_c, s_e_hi_c), f.with_spread(s_f_lo_c, s_f_hi_c)))
 
     }
 
     pub fn assign_q(
         &self,
         region: &mut Region<F>,
-        q_lo: u32,
-        q_hi: u32,
-        q_o_lo_c: AssignedCell<F, F>,
-        q_o_hi_c: AssignedCell<F, F>,
-        s_e_n_lo: u32,
-        s_e_n_hi: u32,
-        s_e_lo: AssignedCell<F, F>,
-        s_e_hi: AssignedCell<F, F>,
-        s_g_lo: AssignedCell<F, F>,
-        s_g_hi: AssignedCell<F, F>,
+        q_lo: Value<u32>,
+        q_hi: Value<u32>,
+        q_o: &AssignedWord<F>,
+        e: &AssignedWord32<F>,
+        g: &AssignedWord32<F>,
         offset: usize,
-    ) -> Result<Vec<AssignedCell<F, F>>, Error>{
+    ) -> Result<AssignedWord32<F>, Error>{
 
         self.s_ch_neg.enable(region, offset)?;
-        let q_e_0 = even_bit(q_lo);
-        let q_o_0 = odd_bit(q_lo);
-        let q_e_1 = even_bit(q_hi);
-        let q_o_1 = odd_bit(q_hi);
-
-        region.assign_advice(|| "s_ch_neg q_e_0 tag", self.a0, offset-1, || Value::known(F::from(create_tag(q_e_0) as u64)))?;
-        region.assign_advice(|| "s_ch_neg q_e_0", self.a1, offset-1, || Value::known(F::from(q_e_0 as u64)))?;
-        region.assign_advice(|| "s_ch_neg s_q_e_0", self.a2, offset-1, || Value::known(F::from(create_interleave_num(q_e_0 as u32) as u64)))?;
-        region.assign_advice(|| "s_ch_neg s_e_n_lo", self.a3, offset-1, || Value::known(F::from(s_e_n_lo as u64)))?;
-        region.assign_advice(|| "s_ch_neg s_e_n_hi", self.a4, offset-1, || Value::known(F::from(s_e_n_hi as u64)))?;
-        let s_e_lo_c = s_e_lo.copy_advice(|| "s_ch_neg s_e_lo", region, self.a5, offset-1)?;
-
-        region.assign_advice(|| "s_ch_neg q_o_0 tag", self.a0, offset, || Value::known(F::from(create_tag(q_o_0) as u64)))?;
-        q_o_lo_c.copy_advice(|| "s_ch_neg q_o_0", region, self.a1, offset)?;
-        region.assign_advice(|| "s_ch_neg s_q_o_0", self.a2, offset, || Value::known(F::from(create_interleave_num(q_o_0 as u32) as u64)))?;
-        let s_e_hi_c = s_e_hi.copy_advice(|| "s_ch_neg s_e_hi", region, self.a5, offset)?;
-
-        region.assign_advice(|| "s_ch_neg q_e_1 tag", self.a0, offset+1, || Value::known(F::from(create_tag(q_e_1) as u64)))?;
-        region.assign_advice(|| "s_ch_neg q_e_1", self.a1, offset+1, || Value::known(F::from(q_e_1 as u64)))?;
-        region.assign_advice(|| "s_ch_neg s_q_e_1", self.a2, offset+1, || Value::known(F::from(create_interleave_num(q_e_1 as u32) as u64)))?;
-        s_g_lo.copy_advice(|| "s_ch_neg s_g_lo", region, self.a3, offset+1)?;
-        s_g_hi.copy_advice(|| "s_ch_neg s_g_hi", region, self.a4, offset+1)?;
-
-        region.assign_advice(|| "s_ch_neg q_o_1 tag", self.a0, offset+2, || Value::known(F::from(create_tag(q_o_1) as u64)))?;
-        q_o_hi_c.copy_advice(|| "s_ch_neg q_o_1", region, self.a1, offset+2)?;
-        let s_q_o_1 = region.assign_advice(|| "s_ch_neg s_q_o_1", self.a2, offset+2, || Value::known(F::from(create_interleave_num(q_o_1 as u32) as u64)))?;
+        let s_e_n_lo = e.value.map(|e| spread_not(e.lo));
+        let s_e_n_hi = e.value.map(|e| spread_not(e.hi));
+        let q_e_0 = q_lo.map(even_bit);
+        let q_o_0 = q_lo.map(odd_bit);
+        let q_e_1 = q_hi.map(even_bit);
+        let q_o_1 = q_hi.map(odd_bit);
+
+        self.spread.enable(region, offset-1, q_e_0)?;
+        region.assign_advice(|| "s_ch_neg q_e_0 tag", self.a0, offset-1, || q_e_0.map(|q_e_0| F::from(create_tag(q_e_0) as u64)))?;
+        region.assign_advice(|| "s_ch_neg q_e_0", self.a1, offset-1, || q_e_0.map(|q_e_0| F::from(q_e_0 as u64)))?;
+        region.assign_advice(|| "s_ch_neg s_q_e_0", self.a2, offset-1, || q_e_0.map(|q_e_0| F::from(create_interleave_num(q_e_0 as u32) as u64)))?;
+        region.assign_advice(|| "s_ch_neg s_e_n_lo", self.a3, offset-1, || s_e_n_lo.map(|s_e_n_lo| F::from(s_e_n_lo as u64)))?;
+        region.assign_advice(|| "s_ch_neg s_e_n_hi", self.a4, offset-1, || s_e_n_hi.map(|s_e_n_hi| F::from(s_e_n_hi as u64)))?;
+        let s_e_lo_c = e.spread_lo.copy_advice(|| "s_ch_neg s_e_lo", region, self.a5, offset-1)?;
+
+        self.spread.enable(region, offset, q_o_0)?;
+        region.assign_advice(|| "s_ch_neg q_o_0 tag", self.a0, offset, || q_o_0.map(|q_o_0| F::from(create_tag(q_o_0) as u64)))?;
+        q_o.lo.copy_advice(|| "s_ch_neg q_o_0", region, self.a1, offset)?;
+        region.assign_advice(|| "s_ch_neg s_q_o_0", self.a2, offset, || q_o_0.map(|q_o_0| F::from(create_interleave_num(q_o_0 as u32) as u64)))?;
+        let s_e_hi_c = e.spread_hi.copy_advice(|| "s_ch_neg s_e_hi", region, self.a5, offset)?;
+
+        self.spread.enable(region, offset+1, q_e_1)?;
+        region.assign_advice(|| "s_ch_neg q_e_1 tag", self.a0, offset+1, || q_e_1.map(|q_e_1| F::from(create_tag(q_e_1) as u64)))?;
+        region.assign_advice(|| "s_ch_neg q_e_1", self.a1, offset+1, || q_e_1.map(|q_e_1| F::from(q_e_1 as u64)))?;
+        region.assign_advice(|| "s_ch_neg s_q_e_1", self.a2, offset+1, || q_e_1.map(|q_e_1| F::from(create_interleave_num(q_e_1 as u32) as u64)))?;
+        g.spread_lo.copy_advice(|| "s_ch_neg s_g_lo", region, self.a3, offset+1)?;
+        g.spread_hi.copy_advice(|| "s_ch_neg s_g_hi", region, self.a4, offset+1)?;
+
+        self.spread.enable(region, offset+2, q_o_1)?;
+        region.assign_advice(|| "s_ch_neg q_o_1 tag", self.a0, offset+2, || q_o_1.map(|q_o_1| F::from(create_tag(q_o_1) as u64)))?;
+        q_o.hi.copy_advice(|| "s_ch_neg q_o_1", region, self.a1, offset+2)?;
+        let s_q_o_1 = region.assign_advice(|| "s_ch_neg s_q_o_1", self.a2, offset+2, || q_o_1.map(|q_o_1| F::from(create_interleave_num(q_o_1 as u32) as u64)))?;
         s_q_o_1.copy_advice(|| "s_ch_neg s_q_o_1 copy", region, self.a3, offset)?;
-        let res = vec![s_e_lo_c, s_e_hi_c];
 
-        Ok(res)
+        Ok(e.with_spread(s_e_lo_c, s_e_hi_c))
     }
     
 }
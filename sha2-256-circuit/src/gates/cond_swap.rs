@@ -0,0 +1,102 @@
+use std::marker::PhantomData;
+
+use crate::utils::create_value_2_check;
+use crate::word::{AssignedWord, Word32};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Selector, Error},
+    poly::Rotation,
+};
+
+/// Orders two words by a boolean `swap`, one limb per row: `(a, b)` in
+/// `a5`/`a6` is `(x, y)` from `a3`/`a4` when `swap` is 0 and `(y, x)` when it
+/// is 1. `swap` is in `a7`, copied from its first row to the others.
+#[derive(Debug, Clone)]
+pub struct CondSwapConfig<F: FieldExt> {
+    s_swap: Selector,
+    a3: Column<Advice>,
+    a4: Column<Advice>,
+    a5: Column<Advice>,
+    a6: Column<Advice>,
+    a7: Column<Advice>,
+    _marker: PhantomData<F>
+}
+
+impl<F: FieldExt> CondSwapConfig<F> {
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a3: Column<Advice>,
+        a4: Column<Advice>,
+        a5: Column<Advice>,
+        a6: Column<Advice>,
+        a7: Column<Advice>,
+    ) -> Self {
+        let s_swap = meta.selector();
+        meta.create_gate(
+            "Cond Swap Gate",
+            |meta| {
+                let s_swap = meta.query_selector(s_swap);
+                let x = meta.query_advice(a3, Rotation::cur());
+                let y = meta.query_advice(a4, Rotation::cur());
+                let a = meta.query_advice(a5, Rotation::cur());
+                let b = meta.query_advice(a6, Rotation::cur());
+                let swap = meta.query_advice(a7, Rotation::cur());
+
+                let a_constraint = x.clone() + swap.clone() * (y.clone() - x.clone()) - a;
+                let b_constraint = y.clone() + swap.clone() * (x - y) - b;
+
+                vec![
+                    s_swap.clone() * a_constraint,
+                    s_swap.clone() * b_constraint,
+                    s_swap * create_value_2_check(swap)
+                ]
+            }
+        );
+
+        Self {
+            s_swap,
+            a3, a4, a5, a6, a7,
+            _marker: PhantomData
+        }
+    }
+
+    /// Returns `(x, y)` when `swap` is 0 and `(y, x)` when it is 1, on one row
+    /// per limb with `swap` copied into every row.
+    pub fn assign(
+        &self,
+        region: &mut Region<F>,
+        swap: &AssignedCell<F, F>,
+        x: &[AssignedWord<F>],
+        y: &[AssignedWord<F>],
+        offset: usize,
+    ) -> Result<(Vec<AssignedWord<F>>, Vec<AssignedWord<F>>), Error> {
+        let swap_val = swap.value().map(|swap| *swap != F::zero());
+        let mut a = vec![];
+        let mut b = vec![];
+        for (i, (x, y)) in x.iter().zip(y.iter()).enumerate() {
+            let mut limbs = vec![];
+            for (j, (x, y)) in [(&x.lo, &y.lo), (&x.hi, &y.hi)].into_iter().enumerate() {
+                let row = offset + 2 * i + j;
+                self.s_swap.enable(region, row)?;
+                swap.copy_advice(|| "s_swap swap", region, self.a7, row)?;
+                let x = x.copy_advice(|| "s_swap x", region, self.a3, row)?;
+                let y = y.copy_advice(|| "s_swap y", region, self.a4, row)?;
+                let (a_val, b_val) = swap_val.zip(x.value().zip(y.value())).map(|(swap, (x, y))| {
+                    if swap { (*y, *x) } else { (*x, *y) }
+                }).unzip();
+                let a_c = region.assign_advice(|| "s_swap a", self.a5, row, || a_val)?;
+                let b_c = region.assign_advice(|| "s_swap b", self.a6, row, || b_val)?;
+                limbs.push((a_c, b_c));
+            }
+            let (a_val, b_val): (Value<Word32>, Value<Word32>) = swap_val.zip(x.value.zip(y.value)).map(|(swap, (x, y))| {
+                if swap { (y, x) } else { (x, y) }
+            }).unzip();
+            a.push(AssignedWord::new(limbs[0].0.clone(), limbs[1].0.clone(), a_val));
+            b.push(AssignedWord::new(limbs[0].1.clone(), limbs[1].1.clone(), b_val));
+        }
+        Ok((a, b))
+    }
+}
@@ -9,6 +9,10 @@ use crate::utils::{
     create_tag,
 };
 
+use crate::word::{AssignedWord, AssignedWord32, Word32};
+
+use crate::spread_table::SpreadLookupConfig;
+
 use halo2_proofs::{
     arithmetic::FieldExt,
     circuit::{Region, Value, AssignedCell},
@@ -29,6 +33,7 @@ pub struct DecomposeABCConfig<F: FieldExt> {
     a6: Column<Advice>,
     a7: Column<Advice>,
     a8: Column<Advice>,
+    spread: SpreadLookupConfig<F>,
     _marker: PhantomData<F>
 }
 
@@ -46,6 +51,7 @@ impl<F: FieldExt> DecomposeABCConfig<F> {
         a6: Column<Advice>,
         a7: Column<Advice>,
         a8: Column<Advice>,
+        spread: SpreadLookupConfig<F>,
     ) -> Self {
         let s_abc = meta.complex_selector();
 
@@ -122,6 +128,7 @@ impl<F: FieldExt> DecomposeABCConfig<F> {
         Self {
             s_abc,
             a0, a1, a2, a3, a4, a5, a6, a7, a8,
+            spread,
             _marker: PhantomData
         }
 
@@ -130,104 +137,122 @@ impl<F: FieldExt> DecomposeABCConfig<F> {
     pub fn assign(
         &self,
         region: &mut Region<F>,
-        a_lo: u16,
-        a_hi: u16,
+        word: Value<Word32>,
         offset: usize,
-    ) -> Result<Vec<AssignedCell<F, F>>, Error>{
+    ) -> Result<(Vec<AssignedCell<F, F>>, AssignedWord32<F>), Error>{
 
-        let a = a_lo as u32 + a_hi as u32 * ( 1<<16 );
-        let a_a = a & 0b11;
-        let a_b = (a >> 2) & 0x7FF;
-        let a_c = ( a >> 13) & 0x1FF;
-        let a_d = a >> 22;
+        let a_lo = word.map(|w| w.lo);
+        let a_hi = word.map(|w| w.hi);
+        let a = word.map(|w| w.value());
+        let a_a = a.map(|a| a & 0b11);
+        let a_b = a.map(|a| (a >> 2) & 0x7FF);
+        let a_c = a.map(|a| ( a >> 13) & 0x1FF);
+        let a_d = a.map(|a| a >> 22);
 
-        let a_c_lo = a_c & 0b111;
-        let a_c_mi = (a_c>>3) & 0b111;
-        let a_c_hi = a_c >> 6;
+        let a_c_lo = a_c.map(|a_c| a_c & 0b111);
+        let a_c_mi = a_c.map(|a_c| (a_c>>3) & 0b111);
+        let a_c_hi = a_c.map(|a_c| a_c >> 6);
 
         self.s_abc.enable(region, offset)?;
 
-        region.assign_advice(|| "sd_abc b tag", self.a0, offset, || Value::known(F::from(create_tag(a_b as u16) as u64)))?;
-        region.assign_advice(|| "sd_abc b", self.a1, offset, || Value::known(F::from(a_b as u64)))?;
-        let s_b = region.assign_advice(|| "sd_abc s_b", self.a2, offset, || Value::known(F::from(create_interleave_num(a_b) as u64)))?;
+        self.spread.enable(region, offset, a_b.map(|a_b| a_b as u16))?;
+        region.assign_advice(|| "sd_abc b tag", self.a0, offset, || a_b.map(|a_b| F::from(create_tag(a_b as u16) as u64)))?;
+        region.assign_advice(|| "sd_abc b", self.a1, offset, || a_b.map(|a_b| F::from(a_b as u64)))?;
+        let s_b = region.assign_advice(|| "sd_abc s_b", self.a2, offset, || a_b.map(|a_b| F::from(create_interleave_num(a_b) as u64)))?;
 
-        region.assign_advice(|| "sd_abc c_lo", self.a3, offset, || Value::known(F::from(a_c_lo as u64)))?;
-        let s_c_lo = region.assign_advice(|| "sd_abc s_c_lo", self.a4, offset, || Value::known(F::from(create_interleave_num(a_c_lo) as u64)))?;
+        region.assign_advice(|| "sd_abc c_lo", self.a3, offset, || a_c_lo.map(|a_c_lo| F::from(a_c_lo as u64)))?;
+        let s_c_lo = region.assign_advice(|| "sd_abc s_c_lo", self.a4, offset, || a_c_lo.map(|a_c_lo| F::from(create_interleave_num(a_c_lo) as u64)))?;
 
-        region.assign_advice(|| "sd_abc c_mi", self.a5, offset, || Value::known(F::from(a_c_mi as u64)))?;
-        let s_c_mi = region.assign_advice(|| "sd_abc s_b_hi", self.a6, offset, || Value::known(F::from(create_interleave_num(a_c_mi) as u64)))?;
+        region.assign_advice(|| "sd_abc c_mi", self.a5, offset, || a_c_mi.map(|a_c_mi| F::from(a_c_mi as u64)))?;
+        let s_c_mi = region.assign_advice(|| "sd_abc s_b_hi", self.a6, offset, || a_c_mi.map(|a_c_mi| F::from(create_interleave_num(a_c_mi) as u64)))?;
 
-        let a_lo_c = region.assign_advice(|| "sd_abc a_lo", self.a7, offset, || Value::known(F::from(a_lo as u64)))?;
-        let s_a_lo = region.assign_advice(|| "sd_abc s_a_lo", self.a8, offset, || Value::known(F::from(create_interleave_num(a_lo as u32) as u64)))?;
+        let a_lo_c = region.assign_advice(|| "sd_abc a_lo", self.a7, offset, || a_lo.map(|a_lo| F::from(a_lo as u64)))?;
+        let s_a_lo = region.assign_advice(|| "sd_abc s_a_lo", self.a8, offset, || a_lo.map(|a_lo| F::from(create_interleave_num(a_lo as u32) as u64)))?;
+        self.spread.assign_limb(region, offset, a_lo)?;
 
-        region.assign_advice(|| "sd_abc d tag", self.a0, offset+1, || Value::known(F::from(create_tag(a_d as u16) as u64)))?;
-        region.assign_advice(|| "sd_abc d", self.a1, offset+1, || Value::known(F::from(a_d as u64)))?;
-        let s_d = region.assign_advice(|| "sd_abc s_d", self.a2, offset+1, || Value::known(F::from(create_interleave_num(a_d) as u64)))?;
+        self.spread.enable(region, offset+1, a_d.map(|a_d| a_d as u16))?;
+        region.assign_advice(|| "sd_abc d tag", self.a0, offset+1, || a_d.map(|a_d| F::from(create_tag(a_d as u16) as u64)))?;
+        region.assign_advice(|| "sd_abc d", self.a1, offset+1, || a_d.map(|a_d| F::from(a_d as u64)))?;
+        let s_d = region.assign_advice(|| "sd_abc s_d", self.a2, offset+1, || a_d.map(|a_d| F::from(create_interleave_num(a_d) as u64)))?;
 
-        region.assign_advice(|| "sd_abc a", self.a3, offset+1, || Value::known(F::from(a_a as u64)))?;
-        let s_a = region.assign_advice(|| "sd_abc s_a", self.a4, offset+1, || Value::known(F::from(create_interleave_num(a_a) as u64)))?;
+        region.assign_advice(|| "sd_abc a", self.a3, offset+1, || a_a.map(|a_a| F::from(a_a as u64)))?;
+        let s_a = region.assign_advice(|| "sd_abc s_a", self.a4, offset+1, || a_a.map(|a_a| F::from(create_interleave_num(a_a) as u64)))?;
 
-        region.assign_advice(|| "sd_abc c_hi", self.a5, offset+1, || Value::known(F::from(a_c_hi as u64)))?;
-        let s_c_hi = region.assign_advice(|| "sd_abc s_c_hi", self.a6, offset+1, || Value::known(F::from(create_interleave_num(a_c_hi) as u64)))?;
+        region.assign_advice(|| "sd_abc c_hi", self.a5, offset+1, || a_c_hi.map(|a_c_hi| F::from(a_c_hi as u64)))?;
+        let s_c_hi = region.assign_advice(|| "sd_abc s_c_hi", self.a6, offset+1, || a_c_hi.map(|a_c_hi| F::from(create_interleave_num(a_c_hi) as u64)))?;
 
-        let a_hi_c = region.assign_advice(|| "sd_abc a_hi", self.a7, offset+1, || Value::known(F::from(a_hi as u64)))?;
-        let s_a_hi = region.assign_advice(|| "sd_abc s_a_hi", self.a8, offset+1, || Value::known(F::from(create_interleave_num(a_hi as u32) as u64)))?;
+        let a_hi_c = region.assign_advice(|| "sd_abc a_hi", self.a7, offset+1, || a_hi.map(|a_hi| F::from(a_hi as u64)))?;
+        let s_a_hi = region.assign_advice(|| "sd_abc s_a_hi", self.a8, offset+1, || a_hi.map(|a_hi| F::from(create_interleave_num(a_hi as u32) as u64)))?;
 
-        let res = vec![s_a, s_b, s_c_lo, s_c_mi, s_c_hi, s_d, s_a_lo, s_a_hi, a_lo_c, a_hi_c];
-        Ok(res)
+        let word = AssignedWord32 {
+            lo: a_lo_c,
+            hi: a_hi_c,
+            spread_lo: s_a_lo,
+            spread_hi: s_a_hi,
+            value: word,
+        };
+        Ok((vec![s_a, s_b, s_c_lo, s_c_mi, s_c_hi, s_d], word))
     }
 
 
     pub fn assign_steady(
         &self,
         region: &mut Region<F>,
-        a_lo: u16,
-        a_hi: u16,
-        a_lo_c: AssignedCell<F, F>,
-        a_hi_c: AssignedCell<F, F>,
+        word: &AssignedWord<F>,
         offset: usize,
-    ) -> Result<Vec<AssignedCell<F, F>>, Error>{
+    ) -> Result<(Vec<AssignedCell<F, F>>, AssignedWord32<F>), Error>{
 
-        let a = a_lo as u32 + a_hi as u32 * ( 1<<16 );
-        let a_a = a & 0b11;
-        let a_b = (a >> 2) & 0x7FF;
-        let a_c = ( a >> 13) & 0x1FF;
-        let a_d = a >> 22;
+        let a_lo = word.value.map(|w| w.lo);
+        let a_hi = word.value.map(|w| w.hi);
+        let a = word.value.map(|w| w.value());
+        let a_a = a.map(|a| a & 0b11);
+        let a_b = a.map(|a| (a >> 2) & 0x7FF);
+        let a_c = a.map(|a| ( a >> 13) & 0x1FF);
+        let a_d = a.map(|a| a >> 22);
 
-        let a_c_lo = a_c & 0b111;
-        let a_c_mi = (a_c>>3) & 0b111;
-        let a_c_hi = a_c >> 6;
+        let a_c_lo = a_c.map(|a_c| a_c & 0b111);
+        let a_c_mi = a_c.map(|a_c| (a_c>>3) & 0b111);
+        let a_c_hi = a_c.map(|a_c| a_c >> 6);
 
         self.s_abc.enable(region, offset)?;
 
-        region.assign_advice(|| "sd_abc b tag", self.a0, offset, || Value::known(F::from(create_tag(a_b as u16) as u64)))?;
-        region.assign_advice(|| "sd_abc b", self.a1, offset, || Value::known(F::from(a_b as u64)))?;
-        let s_b = region.assign_advice(|| "sd_abc s_b", self.a2, offset, || Value::known(F::from(create_interleave_num(a_b) as u64)))?;
+        self.spread.enable(region, offset, a_b.map(|a_b| a_b as u16))?;
+        region.assign_advice(|| "sd_abc b tag", self.a0, offset, || a_b.map(|a_b| F::from(create_tag(a_b as u16) as u64)))?;
+        region.assign_advice(|| "sd_abc b", self.a1, offset, || a_b.map(|a_b| F::from(a_b as u64)))?;
+        let s_b = region.assign_advice(|| "sd_abc s_b", self.a2, offset, || a_b.map(|a_b| F::from(create_interleave_num(a_b) as u64)))?;
 
-        region.assign_advice(|| "sd_abc c_lo", self.a3, offset, || Value::known(F::from(a_c_lo as u64)))?;
-        let s_c_lo = region.assign_advice(|| "sd_abc s_c_lo", self.a4, offset, || Value::known(F::from(create_interleave_num(a_c_lo) as u64)))?;
+        region.assign_advice(|| "sd_abc c_lo", self.a3, offset, || a_c_lo.map(|a_c_lo| F::from(a_c_lo as u64)))?;
+        let s_c_lo = region.assign_advice(|| "sd_abc s_c_lo", self.a4, offset, || a_c_lo.map(|a_c_lo| F::from(create_interleave_num(a_c_lo) as u64)))?;
 
-        region.assign_advice(|| "sd_abc c_mi", self.a5, offset, || Value::known(F::from(a_c_mi as u64)))?;
-        let s_c_mi = region.assign_advice(|| "sd_abc s_b_hi", self.a6, offset, || Value::known(F::from(create_interleave_num(a_c_mi) as u64)))?;
+        region.assign_advice(|| "sd_abc c_mi", self.a5, offset, || a_c_mi.map(|a_c_mi| F::from(a_c_mi as u64)))?;
+        let s_c_mi = region.assign_advice(|| "sd_abc s_b_hi", self.a6, offset, || a_c_mi.map(|a_c_mi| F::from(create_interleave_num(a_c_mi) as u64)))?;
 
-        let a_lo_c_2 = a_lo_c.copy_advice(|| "sd_abc a_lo", region, self.a7, offset)?;
-        let s_a_lo = region.assign_advice(|| "sd_abc s_a_lo", self.a8, offset, || Value::known(F::from(create_interleave_num(a_lo as u32) as u64)))?;
+        let a_lo_c_2 = word.lo.copy_advice(|| "sd_abc a_lo", region, self.a7, offset)?;
+        let s_a_lo = region.assign_advice(|| "sd_abc s_a_lo", self.a8, offset, || a_lo.map(|a_lo| F::from(create_interleave_num(a_lo as u32) as u64)))?;
+        self.spread.assign_limb(region, offset, a_lo)?;
 
-        region.assign_advice(|| "sd_abc d tag", self.a0, offset+1, || Value::known(F::from(create_tag(a_d as u16) as u64)))?;
-        region.assign_advice(|| "sd_abc d", self.a1, offset+1, || Value::known(F::from(a_d as u64)))?;
-        let s_d = region.assign_advice(|| "sd_abc s_d", self.a2, offset+1, || Value::known(F::from(create_interleave_num(a_d) as u64)))?;
+        self.spread.enable(region, offset+1, a_d.map(|a_d| a_d as u16))?;
+        region.assign_advice(|| "sd_abc d tag", self.a0, offset+1, || a_d.map(|a_d| F::from(create_tag(a_d as u16) as u64)))?;
+        region.assign_advice(|| "sd_abc d", self.a1, offset+1, || a_d.map(|a_d| F::from(a_d as u64)))?;
+        let s_d = region.assign_advice(|| "sd_abc s_d", self.a2, offset+1, || a_d.map(|a_d| F::from(create_interleave_num(a_d) as u64)))?;
 
-        region.assign_advice(|| "sd_abc a", self.a3, offset+1, || Value::known(F::from(a_a as u64)))?;
-        let s_a = region.assign_advice(|| "sd_abc s_a", self.a4, offset+1, || Value::known(F::from(create_interleave_num(a_a) as u64)))?;
+        region.assign_advice(|| "sd_abc a", self.a3, offset+1, || a_a.map(|a_a| F::from(a_a as u64)))?;
+        let s_a = region.assign_advice(|| "sd_abc s_a", self.a4, offset+1, || a_a.map(|a_a| F::from(create_interleave_num(a_a) as u64)))?;
 
-        region.assign_advice(|| "sd_abc c_hi", self.a5, offset+1, || Value::known(F::from(a_c_hi as u64)))?;
-        let s_c_hi = region.assign_advice(|| "sd_abc s_c_hi", self.a6, offset+1, || Value::known(F::from(create_interleave_num(a_c_hi) as u64)))?;
+        region.assign_advice(|| "sd_abc c_hi", self.a5, offset+1, || a_c_hi.map(|a_c_hi| F::from(a_c_hi as u64)))?;
+        let s_c_hi = region.assign_advice(|| "sd_abc s_c_hi", self.a6, offset+1, || a_c_hi.map(|a_c_hi| F::from(create_interleave_num(a_c_hi) as u64)))?;
 
-        let a_hi_c_2 = a_hi_c.copy_advice(|| "sd_abc a_hi", region, self.a7, offset+1)?;
-        let s_a_hi = region.assign_advice(|| "sd_abc s_a_hi", self.a8, offset+1, || Value::known(F::from(create_interleave_num(a_hi as u32) as u64)))?;
+        let a_hi_c_2 = word.hi.copy_advice(|| "sd_abc a_hi", region, self.a7, offset+1)?;
+        let s_a_hi = region.assign_advice(|| "sd_abc s_a_hi", self.a8, offset+1, || a_hi.map(|a_hi| F::from(create_interleave_num(a_hi as u32) as u64)))?;
 
-        let res = vec![s_a, s_b, s_c_lo, s_c_mi, s_c_hi, s_d, s_a_lo, s_a_hi, a_lo_c_2, a_hi_c_2];
-        Ok(res)
+        let word = AssignedWord32 {
+            lo: a_lo_c_2,
+            hi: a_hi_c_2,
+            spread_lo: s_a_lo,
+            spread_hi: s_a_hi,
+            value: word.value,
+        };
+        Ok((vec![s_a, s_b, s_c_lo, s_c_mi, s_c_hi, s_d], word))
     }
 
 }
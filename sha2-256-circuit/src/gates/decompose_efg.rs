@@ -10,6 +10,10 @@ use crate::utils::{
     create_tag,
 };
 
+use crate::word::{AssignedWord, AssignedWord32, Word32};
+
+use crate::spread_table::SpreadLookupConfig;
+
 use halo2_proofs::{
     arithmetic::FieldExt,
     circuit::{Region, Value, AssignedCell},
@@ -30,6 +34,7 @@ pub struct DecomposeEFGConfig<F: FieldExt> {
     a6: Column<Advice>,
     a7: Column<Advice>,
     a8: Column<Advice>,
+    spread: SpreadLookupConfig<F>,
     _marker: PhantomData<F>
 }
 
@@ -47,6 +52,7 @@ impl<F: FieldExt> DecomposeEFGConfig<F> {
         a6: Column<Advice>,
         a7: Column<Advice>,
         a8: Column<Advice>,
+        spread: SpreadLookupConfig<F>,
     ) -> Self {
         let s_efg = meta.complex_selector();
 
@@ -121,6 +127,7 @@ impl<F: FieldExt> DecomposeEFGConfig<F> {
         Self {
             s_efg,
             a0, a1, a2, a3, a4, a5, a6, a7, a8,
+            spread,
             _marker: PhantomData
         }
     }
@@ -128,107 +135,125 @@ impl<F: FieldExt> DecomposeEFGConfig<F> {
     pub fn assign(
         &self,
         region: &mut Region<F>,
-        e_lo: u16,
-        e_hi: u16,
+        word: Value<Word32>,
         offset: usize,
-    ) -> Result<Vec<AssignedCell<F, F>>, Error>{
+    ) -> Result<(Vec<AssignedCell<F, F>>, AssignedWord32<F>), Error>{
 
-        let e: u32 = e_lo as u32 + e_hi as u32 * (1 << 16);
-        let e_a = e & 0b111111;
-        let e_b = (e >> 6) & 0b11111;
-        let e_c = (e >> 11) & 0x3FFF;
-        let e_d = e >> 25;
+        let e_lo = word.map(|w| w.lo);
+        let e_hi = word.map(|w| w.hi);
+        let e = word.map(|w| w.value());
+        let e_a = e.map(|e| e & 0b111111);
+        let e_b = e.map(|e| (e >> 6) & 0b11111);
+        let e_c = e.map(|e| (e >> 11) & 0x3FFF);
+        let e_d = e.map(|e| e >> 25);
 
-        let e_a_lo = e_a & 0b111;
-        let e_a_hi = e_a >> 3;
-        let e_b_lo = e_b & 0b11;
-        let e_b_hi = e_b >> 2;
+        let e_a_lo = e_a.map(|e_a| e_a & 0b111);
+        let e_a_hi = e_a.map(|e_a| e_a >> 3);
+        let e_b_lo = e_b.map(|e_b| e_b & 0b11);
+        let e_b_hi = e_b.map(|e_b| e_b >> 2);
 
         self.s_efg.enable(region, offset)?;
 
-        region.assign_advice(|| "sd_efg d tag", self.a0, offset, || Value::known(F::from(create_tag(e_d as u16) as u64)))?;
-        region.assign_advice(|| "sd_efg d", self.a1, offset, || Value::known(F::from(e_d as u64)))?;
-        let s_d = region.assign_advice(|| "sd_efg s_d", self.a2, offset, || Value::known(F::from(create_interleave_num(e_d) as u64)))?;
+        self.spread.enable(region, offset, e_d.map(|e_d| e_d as u16))?;
+        region.assign_advice(|| "sd_efg d tag", self.a0, offset, || e_d.map(|e_d| F::from(create_tag(e_d as u16) as u64)))?;
+        region.assign_advice(|| "sd_efg d", self.a1, offset, || e_d.map(|e_d| F::from(e_d as u64)))?;
+        let s_d = region.assign_advice(|| "sd_efg s_d", self.a2, offset, || e_d.map(|e_d| F::from(create_interleave_num(e_d) as u64)))?;
 
-        region.assign_advice(|| "sd_efg b_lo", self.a3, offset, || Value::known(F::from(e_b_lo as u64)))?;
-        let s_b_lo = region.assign_advice(|| "sd_efg s_b_lo", self.a4, offset, || Value::known(F::from(create_interleave_num(e_b_lo) as u64)))?;
+        region.assign_advice(|| "sd_efg b_lo", self.a3, offset, || e_b_lo.map(|e_b_lo| F::from(e_b_lo as u64)))?;
+        let s_b_lo = region.assign_advice(|| "sd_efg s_b_lo", self.a4, offset, || e_b_lo.map(|e_b_lo| F::from(create_interleave_num(e_b_lo) as u64)))?;
 
-        region.assign_advice(|| "sd_efg b_hi", self.a5, offset, || Value::known(F::from(e_b_hi as u64)))?;
-        let s_b_hi = region.assign_advice(|| "sd_efg s_b_hi", self.a6, offset, || Value::known(F::from(create_interleave_num(e_b_hi) as u64)))?;
+        region.assign_advice(|| "sd_efg b_hi", self.a5, offset, || e_b_hi.map(|e_b_hi| F::from(e_b_hi as u64)))?;
+        let s_b_hi = region.assign_advice(|| "sd_efg s_b_hi", self.a6, offset, || e_b_hi.map(|e_b_hi| F::from(create_interleave_num(e_b_hi) as u64)))?;
 
-        let e_lo_c = region.assign_advice(|| "sd_efg e_lo", self.a7, offset, || Value::known(F::from(e_lo as u64)))?;
-        let s_e_lo =region.assign_advice(|| "sd_efg s_e_lo", self.a8, offset, || Value::known(F::from(create_interleave_num(e_lo as u32) as u64)))?;
+        let e_lo_c = region.assign_advice(|| "sd_efg e_lo", self.a7, offset, || e_lo.map(|e_lo| F::from(e_lo as u64)))?;
+        let s_e_lo =region.assign_advice(|| "sd_efg s_e_lo", self.a8, offset, || e_lo.map(|e_lo| F::from(create_interleave_num(e_lo as u32) as u64)))?;
+        self.spread.assign_limb(region, offset, e_lo)?;
 
-        region.assign_advice(|| "sd_efg c tag", self.a0, offset+1, || Value::known(F::from(create_tag(e_c as u16) as u64)))?;
-        region.assign_advice(|| "sd_efg c", self.a1, offset+1, || Value::known(F::from(e_c as u64)))?;
-        let s_c = region.assign_advice(|| "sd_efg s_c", self.a2, offset+1, || Value::known(F::from(create_interleave_num(e_c) as u64)))?;
+        self.spread.enable(region, offset+1, e_c.map(|e_c| e_c as u16))?;
+        region.assign_advice(|| "sd_efg c tag", self.a0, offset+1, || e_c.map(|e_c| F::from(create_tag(e_c as u16) as u64)))?;
+        region.assign_advice(|| "sd_efg c", self.a1, offset+1, || e_c.map(|e_c| F::from(e_c as u64)))?;
+        let s_c = region.assign_advice(|| "sd_efg s_c", self.a2, offset+1, || e_c.map(|e_c| F::from(create_interleave_num(e_c) as u64)))?;
 
-        region.assign_advice(|| "sd_efg a_lo", self.a3, offset+1, || Value::known(F::from(e_a_lo as u64)))?;
-        let s_a_lo = region.assign_advice(|| "sd_efg s_a_lo", self.a4, offset+1, || Value::known(F::from(create_interleave_num(e_a_lo) as u64)))?;
+        region.assign_advice(|| "sd_efg a_lo", self.a3, offset+1, || e_a_lo.map(|e_a_lo| F::from(e_a_lo as u64)))?;
+        let s_a_lo = region.assign_advice(|| "sd_efg s_a_lo", self.a4, offset+1, || e_a_lo.map(|e_a_lo| F::from(create_interleave_num(e_a_lo) as u64)))?;
 
-        region.assign_advice(|| "sd_efg b_hi", self.a5, offset+1, || Value::known(F::from(e_a_hi as u64)))?;
-        let s_a_hi = region.assign_advice(|| "sd_efg s_b_hi", self.a6, offset+1, || Value::known(F::from(create_interleave_num(e_a_hi) as u64)))?;
+        region.assign_advice(|| "sd_efg b_hi", self.a5, offset+1, || e_a_hi.map(|e_a_hi| F::from(e_a_hi as u64)))?;
+        let s_a_hi = region.assign_advice(|| "sd_efg s_b_hi", self.a6, offset+1, || e_a_hi.map(|e_a_hi| F::from(create_interleave_num(e_a_hi) as u64)))?;
 
-        let e_hi_c = region.assign_advice(|| "sd_efg e_hi", self.a7, offset+1, || Value::known(F::from(e_hi as u64)))?;
-        let s_e_hi = region.assign_advice(|| "sd_efg s_e_hi", self.a8, offset+1, || Value::known(F::from(create_interleave_num(e_hi as u32) as u64)))?;
+        let e_hi_c = region.assign_advice(|| "sd_efg e_hi", self.a7, offset+1, || e_hi.map(|e_hi| F::from(e_hi as u64)))?;
+        let s_e_hi = region.assign_advice(|| "sd_efg s_e_hi", self.a8, offset+1, || e_hi.map(|e_hi| F::from(create_interleave_num(e_hi as u32) as u64)))?;
         
-        let res = vec![s_a_lo, s_a_hi, s_b_lo, s_b_hi, s_c, s_d, s_e_lo, s_e_hi, e_lo_c, e_hi_c];
-
-        Ok(res)
+        let word = AssignedWord32 {
+            lo: e_lo_c,
+            hi: e_hi_c,
+            spread_lo: s_e_lo,
+            spread_hi: s_e_hi,
+            value: word,
+        };
+
+        Ok((vec![s_a_lo, s_a_hi, s_b_lo, s_b_hi, s_c, s_d], word))
     }
 
     pub fn assign_steady(
         &self,
         region: &mut Region<F>,
-        e_lo: u16,
-        e_hi: u16,
-        e_lo_c: AssignedCell<F, F>,
-        e_hi_c: AssignedCell<F, F>,
+        word: &AssignedWord<F>,
         offset: usize,
-    ) -> Result<Vec<AssignedCell<F, F>>, Error>{
+    ) -> Result<(Vec<AssignedCell<F, F>>, AssignedWord32<F>), Error>{
 
-        let e: u32 = e_lo as u32 + e_hi as u32 * (1 << 16);
-        let e_a = e & 0b111111;
-        let e_b = (e >> 6) & 0b11111;
-        let e_c = (e >> 11) & 0x3FFF;
-        let e_d = e >> 25;
+        let e_lo = word.value.map(|w| w.lo);
+        let e_hi = word.value.map(|w| w.hi);
+        let e = word.value.map(|w| w.value());
+        let e_a = e.map(|e| e & 0b111111);
+        let e_b = e.map(|e| (e >> 6) & 0b11111);
+        let e_c = e.map(|e| (e >> 11) & 0x3FFF);
+        let e_d = e.map(|e| e >> 25);
 
-        let e_a_lo = e_a & 0b111;
-        let e_a_hi = e_a >> 3;
-        let e_b_lo = e_b & 0b11;
-        let e_b_hi = e_b >> 2;
+        let e_a_lo = e_a.map(|e_a| e_a & 0b111);
+        let e_a_hi = e_a.map(|e_a| e_a >> 3);
+        let e_b_lo = e_b.map(|e_b| e_b & 0b11);
+        let e_b_hi = e_b.map(|e_b| e_b >> 2);
 
         self.s_efg.enable(region, offset)?;
 
-        region.assign_advice(|| "sd_efg d tag", self.a0, offset, || Value::known(F::from(create_tag(e_d as u16) as u64)))?;
-        region.assign_advice(|| "sd_efg d", self.a1, offset, || Value::known(F::from(e_d as u64)))?;
-        let s_d = region.assign_advice(|| "sd_efg s_d", self.a2, offset, || Value::known(F::from(create_interleave_num(e_d) as u64)))?;
+        self.spread.enable(region, offset, e_d.map(|e_d| e_d as u16))?;
+        region.assign_advice(|| "sd_efg d tag", self.a0, offset, || e_d.map(|e_d| F::from(create_tag(e_d as u16) as u64)))?;
+        region.assign_advice(|| "sd_efg d", self.a1, offset, || e_d.map(|e_d| F::from(e_d as u64)))?;
+        let s_d = region.assign_advice(|| "sd_efg s_d", self.a2, offset, || e_d.map(|e_d| F::from(create_interleave_num(e_d) as u64)))?;
 
-        region.assign_advice(|| "sd_efg b_lo", self.a3, offset, || Value::known(F::from(e_b_lo as u64)))?;
-        let s_b_lo = region.assign_advice(|| "sd_efg s_b_lo", self.a4, offset, || Value::known(F::from(create_interleave_num(e_b_lo) as u64)))?;
+        region.assign_advice(|| "sd_efg b_lo", self.a3, offset, || e_b_lo.map(|e_b_lo| F::from(e_b_lo as u64)))?;
+        let s_b_lo = region.assign_advice(|| "sd_efg s_b_lo", self.a4, offset, || e_b_lo.map(|e_b_lo| F::from(create_interleave_num(e_b_lo) as u64)))?;
 
-        region.assign_advice(|| "sd_efg b_hi", self.a5, offset, || Value::known(F::from(e_b_hi as u64)))?;
-        let s_b_hi = region.assign_advice(|| "sd_efg s_b_hi", self.a6, offset, || Value::known(F::from(create_interleave_num(e_b_hi) as u64)))?;
+        region.assign_advice(|| "sd_efg b_hi", self.a5, offset, || e_b_hi.map(|e_b_hi| F::from(e_b_hi as u64)))?;
+        let s_b_hi = region.assign_advice(|| "sd_efg s_b_hi", self.a6, offset, || e_b_hi.map(|e_b_hi| F::from(create_interleave_num(e_b_hi) as u64)))?;
 
-        let e_lo_c_2 = e_lo_c.copy_advice(|| "sd_efg e_lo", region, self.a7, offset)?;
-        let s_e_lo =region.assign_advice(|| "sd_efg s_e_lo", self.a8, offset, || Value::known(F::from(create_interleave_num(e_lo as u32) as u64)))?;
+        let e_lo_c_2 = word.lo.copy_advice(|| "sd_efg e_lo", region, self.a7, offset)?;
+        let s_e_lo =region.assign_advice(|| "sd_efg s_e_lo", self.a8, offset, || e_lo.map(|e_lo| F::from(create_interleave_num(e_lo as u32) as u64)))?;
+        self.spread.assign_limb(region, offset, e_lo)?;
 
-        region.assign_advice(|| "sd_efg c tag", self.a0, offset+1, || Value::known(F::from(create_tag(e_c as u16) as u64)))?;
-        region.assign_advice(|| "sd_efg c", self.a1, offset+1, || Value::known(F::from(e_c as u64)))?;
-        let s_c = region.assign_advice(|| "sd_efg s_c", self.a2, offset+1, || Value::known(F::from(create_interleave_num(e_c) as u64)))?;
+        self.spread.enable(region, offset+1, e_c.map(|e_c| e_c as u16))?;
+        region.assign_advice(|| "sd_efg c tag", self.a0, offset+1, || e_c.map(|e_c| F::from(create_tag(e_c as u16) as u64)))?;
+        region.assign_advice(|| "sd_efg c", self.a1, offset+1, || e_c.map(|e_c| F::from(e_c as u64)))?;
+        let s_c = region.assign_advice(|| "sd_efg s_c", self.a2, offset+1, || e_c.map(|e_c| F::from(create_interleave_num(e_c) as u64)))?;
 
-        region.assign_advice(|| "sd_efg a_lo", self.a3, offset+1, || Value::known(F::from(e_a_lo as u64)))?;
-        let s_a_lo = region.assign_advice(|| "sd_efg s_a_lo", self.a4, offset+1, || Value::known(F::from(create_interleave_num(e_a_lo) as u64)))?;
+        region.assign_advice(|| "sd_efg a_lo", self.a3, offset+1, || e_a_lo.map(|e_a_lo| F::from(e_a_lo as u64)))?;
+        let s_a_lo = region.assign_advice(|| "sd_efg s_a_lo", self.a4, offset+1, || e_a_lo.map(|e_a_lo| F::from(create_interleave_num(e_a_lo) as u64)))?;
 
-        region.assign_advice(|| "sd_efg b_hi", self.a5, offset+1, || Value::known(F::from(e_a_hi as u64)))?;
-        let s_a_hi = region.assign_advice(|| "sd_efg s_b_hi", self.a6, offset+1, || Value::known(F::from(create_interleave_num(e_a_hi) as u64)))?;
+        region.assign_advice(|| "sd_efg b_hi", self.a5, offset+1, || e_a_hi.map(|e_a_hi| F::from(e_a_hi as u64)))?;
+        let s_a_hi = region.assign_advice(|| "sd_efg s_b_hi", self.a6, offset+1, || e_a_hi.map(|e_a_hi| F::from(create_interleave_num(e_a_hi) as u64)))?;
 
-        let e_hi_c_2 = e_hi_c.copy_advice(|| "sd_efg e_hi", region, self.a7, offset+1)?;
-        let s_e_hi = region.assign_advice(|| "sd_efg s_e_hi", self.a8, offset+1, || Value::known(F::from(create_interleave_num(e_hi as u32) as u64)))?;
+        let e_hi_c_2 = word.hi.copy_advice(|| "sd_efg e_hi", region, self.a7, offset+1)?;
+        let s_e_hi = region.assign_advice(|| "sd_efg s_e_hi", self.a8, offset+1, || e_hi.map(|e_hi| F::from(create_interleave_num(e_hi as u32) as u64)))?;
         
-        let res = vec![s_a_lo, s_a_hi, s_b_lo, s_b_hi, s_c, s_d, s_e_lo, s_e_hi, e_lo_c_2, e_hi_c_2];
-
-        Ok(res)
+        let word = AssignedWord32 {
+            lo: e_lo_c_2,
+            hi: e_hi_c_2,
+            spread_lo: s_e_lo,
+            spread_hi: s_e_hi,
+            value: word.value,
+        };
+
+        Ok((vec![s_a_lo, s_a_hi, s_b_lo, s_b_hi, s_c, s_d], word))
     }
 
 }
@@ -8,6 +8,8 @@ use halo2_proofs::{
 };
 
 use crate::utils::{create_interleave_num, create_tag};
+use crate::spread_table::SpreadLookupConfig;
+use crate::word::Word32;
 
 
 #[derive(Debug, Clone)]
@@ -18,6 +20,7 @@ pub struct DecomposeThreeConfig<F: FieldExt> {
     a3: Column<Advice>,
     a4: Column<Advice>,
     s_three: Selector,
+    spread: SpreadLookupConfig<F>,
     _marker: PhantomData<F>
 }
 
@@ -32,6 +35,7 @@ impl<F: FieldExt> DecomposeThreeConfig<F> {
         a3: Column<Advice>,
         a4: Column<Advice>,
         a5: Column<Advice>,
+        spread: SpreadLookupConfig<F>,
     ) -> Self {
         let s_three = meta.selector();
 
@@ -62,6 +66,7 @@ impl<F: FieldExt> DecomposeThreeConfig<F> {
         Self {
             a0, a1, a2, a3, a4,
             s_three,
+            spread,
             _marker: PhantomData
         }
 
@@ -70,28 +75,29 @@ impl<F: FieldExt> DecomposeThreeConfig<F> {
     pub fn assign(
         &self,
         region: &mut Region<F>,
-        w_lo: u16,
-        w_hi: u16,
+        w: Value<Word32>,
         offset: usize,
-    ) -> Result<(Vec<AssignedCell<F, F>>, u32, u32), Error> {
-
-        let w = w_lo as u32 + (w_hi as u32) * (1<<16);
-        let w_a = w & 0b1111111111;
-        let w_b = ( w >> 10 ) & 0b1111111;
-        let w_c = ( w >> 17 ) & 0b11;
-        let w_d = w >> 19;
-        region.assign_advice(|| "sd3 wd tag", self.a0, offset, || Value::known(F::from(create_tag(w_d as u16))))?;
-        region.assign_advice(|| "sd3 wd", self.a1, offset, || Value::known(F::from(w_d as u64)))?;
-        let s_d = region.assign_advice(|| "sd3 wd spread", self.a2, offset, || Value::known(F::from(create_interleave_num(w_d) as u64)))?;
-
-        region.assign_advice(|| "sd3 wa tag", self.a0, offset+1, || Value::known(F::from(create_tag(w_a as u16))))?;
-        region.assign_advice(|| "sd3 wa", self.a1, offset+1, || Value::known(F::from(w_a as u64)))?;
-        let s_a = region.assign_advice(|| "sd3 wa spread", self.a2, offset+1, || Value::known(F::from(create_interleave_num(w_a) as u64)))?;
-
-        let c = region.assign_advice(|| "sd3 wc", self.a3, offset+1, || Value::known(F::from(w_c as u64)))?;
-        let b = region.assign_advice(|| "sd3 wb", self.a4, offset+1, || Value::known(F::from(w_b as u64)))?;
+    ) -> Result<(Vec<AssignedCell<F, F>>, Value<u16>, Value<u16>), Error> {
+
+        let w = w.map(|w| w.value());
+        let w_a = w.map(|w| w & 0b1111111111);
+        let w_b = w.map(|w| ( w >> 10 ) & 0b1111111);
+        let w_c = w.map(|w| ( w >> 17 ) & 0b11);
+        let w_d = w.map(|w| w >> 19);
+        self.spread.enable(region, offset, w_d.map(|w_d| w_d as u16))?;
+        region.assign_advice(|| "sd3 wd tag", self.a0, offset, || w_d.map(|w_d| F::from(create_tag(w_d as u16))))?;
+        region.assign_advice(|| "sd3 wd", self.a1, offset, || w_d.map(|w_d| F::from(w_d as u64)))?;
+        let s_d = region.assign_advice(|| "sd3 wd spread", self.a2, offset, || w_d.map(|w_d| F::from(create_interleave_num(w_d) as u64)))?;
+
+        self.spread.enable(region, offset+1, w_a.map(|w_a| w_a as u16))?;
+        region.assign_advice(|| "sd3 wa tag", self.a0, offset+1, || w_a.map(|w_a| F::from(create_tag(w_a as u16))))?;
+        region.assign_advice(|| "sd3 wa", self.a1, offset+1, || w_a.map(|w_a| F::from(w_a as u64)))?;
+        let s_a = region.assign_advice(|| "sd3 wa spread", self.a2, offset+1, || w_a.map(|w_a| F::from(create_interleave_num(w_a) as u64)))?;
+
+        let c = region.assign_advice(|| "sd3 wc", self.a3, offset+1, || w_c.map(|w_c| F::from(w_c as u64)))?;
+        let b = region.assign_advice(|| "sd3 wb", self.a4, offset+1, || w_b.map(|w_b| F::from(w_b as u64)))?;
         let res = vec![s_d, s_a, c, b];
-        Ok((res, w_b, w_c))
+        Ok((res, w_b.map(|b| b as u16), w_c.map(|c| c as u16)))
     }
 
 }
@@ -6,7 +6,10 @@ use halo2_proofs::{
     plonk::{Advice, Column, ConstraintSystem, Expression, Selector, Error},
     poly::Rotation,
 };
+
 use crate::utils::{create_interleave_num, create_tag};
+use crate::spread_table::SpreadLookupConfig;
+use crate::word::Word32;
 
 #[derive(Debug, Clone)]
 pub struct DecomposeTwoConfig<F: FieldExt> {
@@ -17,6 +20,7 @@ pub struct DecomposeTwoConfig<F: FieldExt> {
     a4: Column<Advice>,
     a5: Column<Advice>,
     s_two: Selector,
+    spread: SpreadLookupConfig<F>,
     _marker: PhantomData<F>
 }
 
@@ -31,6 +35,7 @@ impl<F: FieldExt> DecomposeTwoConfig<F> {
         a3: Column<Advice>,
         a4: Column<Advice>,
         a5: Column<Advice>,
+        spread: SpreadLookupConfig<F>,
     ) -> Self {
         let s_two = meta.selector();
 
@@ -67,6 +72,7 @@ impl<F: FieldExt> DecomposeTwoConfig<F> {
         Self {
             a0, a1, a2, a3, a4, a5,
             s_two,
+            spread,
             _marker: PhantomData
         }
 
@@ -75,42 +81,51 @@ impl<F: FieldExt> DecomposeTwoConfig<F> {
     pub fn assign(
         &self,
         region: &mut Region<F>,
-        w_lo: u16,
-        w_hi: u16,
+        w: Value<Word32>,
         offset: usize,
-    ) -> Result<(Vec<AssignedCell<F,F>>, u32, u32, u32, u32, u32), Error> {
+    ) -> Result<(Vec<AssignedCell<F,F>>, Value<u16>, Value<u16>, Value<u16>, Value<u16>, Value<u16>), Error> {
         
-        let w = w_lo as u32 + (w_hi as u32) * (1 << 16);
-        let w_a = w & 0b111;
-        let w_b = (w >> 3) & 0b1111;
-        let w_c = (w >> 7) & 0b111;
-        let w_d = (w >> 10) & 0b1111111;
-        let w_e = (w >> 17) & 0b1;
-        let w_f = (w >> 18) & 0b1;
-        let w_g = w >> 19;
+        let w = w.map(|w| w.value());
+        let w_a = w.map(|w| w & 0b111);
+        let w_b = w.map(|w| (w >> 3) & 0b1111);
+        let w_c = w.map(|w| (w >> 7) & 0b111);
+        let w_d = w.map(|w| (w >> 10) & 0b1111111);
+        let w_e = w.map(|w| (w >> 17) & 0b1);
+        let w_f = w.map(|w| (w >> 18) & 0b1);
+        let w_g = w.map(|w| w >> 19);
 
         self.s_two.enable(region, offset)?;
 
-        region.assign_advice(|| "sd2 g tag", self.a0, offset-1, || Value::known(F::from(create_tag(w_g as u16) as u64)))?;
-        region.assign_advice(|| "sd2 g", self.a1, offset-1, || Value::known(F::from(w_g as u64)))?;
-        let s_g = region.assign_advice(|| "sd2 g spread", self.a2, offset-1, || Value::known(F::from(create_interleave_num(w_g) as u64)))?;
+        self.spread.enable(region, offset-1, w_g.map(|w_g| w_g as u16))?;
+        region.assign_advice(|| "sd2 g tag", self.a0, offset-1, || w_g.map(|w_g| F::from(create_tag(w_g as u16) as u64)))?;
+        region.assign_advice(|| "sd2 g", self.a1, offset-1, || w_g.map(|w_g| F::from(w_g as u64)))?;
+        let s_g = region.assign_advice(|| "sd2 g spread", self.a2, offset-1, || w_g.map(|w_g| F::from(create_interleave_num(w_g) as u64)))?;
 
-        let a = region.assign_advice(|| "sd2 a", self.a3, offset-1, || Value::known(F::from(w_a as u64)))?;
-        let c = region.assign_advice(|| "sd2 c", self.a4, offset-1, || Value::known(F::from(w_c as u64)))?;
+        let a = region.assign_advice(|| "sd2 a", self.a3, offset-1, || w_a.map(|w_a| F::from(w_a as u64)))?;
+        let c = region.assign_advice(|| "sd2 c", self.a4, offset-1, || w_c.map(|w_c| F::from(w_c as u64)))?;
 
-        region.assign_advice(|| "sd2 d tag", self.a0, offset, || Value::known(F::from(create_tag(w_d as u16) as u64)))?;
-        region.assign_advice(|| "sd2 d", self.a1, offset, || Value::known(F::from(w_d as u64)))?;
-        let s_d = region.assign_advice(|| "sd2 d spread", self.a2, offset, || Value::known(F::from(create_interleave_num(w_d) as u64)))?;
+        self.spread.enable(region, offset, w_d.map(|w_d| w_d as u16))?;
+        region.assign_advice(|| "sd2 d tag", self.a0, offset, || w_d.map(|w_d| F::from(create_tag(w_d as u16) as u64)))?;
+        region.assign_advice(|| "sd2 d", self.a1, offset, || w_d.map(|w_d| F::from(w_d as u64)))?;
+        let s_d = region.assign_advice(|| "sd2 d spread", self.a2, offset, || w_d.map(|w_d| F::from(create_interleave_num(w_d) as u64)))?;
 
-        region.assign_advice(|| "sd2 b tag", self.a0, offset+1, || Value::known(F::from(create_tag(w_b as u16) as u64)))?;
-        let b = region.assign_advice(|| "sd2 b", self.a1, offset+1, || Value::known(F::from(w_b as u64)))?;
-        region.assign_advice(|| "sd2 b spread", self.a2, offset+1, || Value::known(F::from(create_interleave_num(w_b) as u64)))?;
+        self.spread.enable(region, offset+1, w_b.map(|w_b| w_b as u16))?;
+        region.assign_advice(|| "sd2 b tag", self.a0, offset+1, || w_b.map(|w_b| F::from(create_tag(w_b as u16) as u64)))?;
+        let b = region.assign_advice(|| "sd2 b", self.a1, offset+1, || w_b.map(|w_b| F::from(w_b as u64)))?;
+        region.assign_advice(|| "sd2 b spread", self.a2, offset+1, || w_b.map(|w_b| F::from(create_interleave_num(w_b) as u64)))?;
 
-        region.assign_advice(|| "sd2 e", self.a3, offset+1, || Value::known(F::from(w_e as u64)))?;
-        region.assign_advice(|| "sd2 f", self.a4, offset+1, || Value::known(F::from(w_f as u64)))?;
+        region.assign_advice(|| "sd2 e", self.a3, offset+1, || w_e.map(|w_e| F::from(w_e as u64)))?;
+        region.assign_advice(|| "sd2 f", self.a4, offset+1, || w_f.map(|w_f| F::from(w_f as u64)))?;
 
         let res = vec![s_g, s_d, b, a, c];
-        Ok((res, w_a, w_b, w_c, w_e, w_f))
+        Ok((
+            res,
+            w_a.map(|a| a as u16),
+            w_b.map(|b| b as u16),
+            w_c.map(|c| c as u16),
+            w_e.map(|e| e as u16),
+            w_f.map(|f| f as u16),
+        ))
     }
 
 }
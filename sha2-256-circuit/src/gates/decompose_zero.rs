@@ -8,6 +8,8 @@ use halo2_proofs::{
 };
 
 use crate::utils::{create_interleave_num, create_tag};
+use crate::spread_table::SpreadLookupConfig;
+use crate::word::{AssignedWord, Word32};
 
 
 #[derive(Debug, Clone)]
@@ -19,6 +21,7 @@ pub struct DecomposeZeroConfig<F: FieldExt> {
     pub a3: Column<Advice>,
     pub a4: Column<Advice>,
     pub a5: Column<Advice>,
+    spread: SpreadLookupConfig<F>,
     _marker: PhantomData<F>
 }
 
@@ -33,6 +36,7 @@ impl<F: FieldExt> DecomposeZeroConfig<F> {
         a3: Column<Advice>,
         a4: Column<Advice>,
         a5: Column<Advice>,
+        spread: SpreadLookupConfig<F>,
     ) -> Self {
         let s_zero = meta.selector();
 
@@ -64,6 +68,7 @@ impl<F: FieldExt> DecomposeZeroConfig<F> {
             a4,
             a5,
             s_zero,
+            spread,
             _marker: PhantomData
         }
 
@@ -72,78 +77,82 @@ impl<F: FieldExt> DecomposeZeroConfig<F> {
     pub fn assign_special_0(
         &self,
         region: &mut Region<F>,
-        w_lo: u16,
-        w_hi: u16,
+        word: Value<Word32>,
         offset: usize
-    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+    ) -> Result<AssignedWord<F>, Error> {
+        let w_lo = word.map(|w| w.lo);
+        let w_hi = word.map(|w| w.hi);
 
         self.s_zero.enable(region, offset)?;
+        self.spread.enable(region, offset, w_lo)?;
         region.assign_advice(
             || "W0 Low Tag", 
             self.a0, offset, 
-            || Value::known(F::from(create_tag(w_lo)))
+            || w_lo.map(|w_lo| F::from(create_tag(w_lo)))
         )?;
         let w0_lo = region.assign_advice(
             || "W0 Low Value", 
             self.a1, offset, 
-            || Value::known(F::from(w_lo as u64))
+            || w_lo.map(|w_lo| F::from(w_lo as u64))
         )?;
         region.assign_advice(
             || "W0 Low Value Spread", 
             self.a2, offset, 
-            || Value::known(F::from(create_interleave_num(w_lo as u32) as u64))
+            || w_lo.map(|w_lo| F::from(create_interleave_num(w_lo as u32) as u64))
         )?;
         let w0_lo_c = w0_lo.copy_advice(|| "Same W0 Low Value", region, self.a3, offset)?;
         region.assign_advice(
             || "W0", 
             self.a5, offset, 
-            || Value::known(F::from((w_lo as u32 + (w_hi as u32) * (1 << 16)) as u64))
+            || word.map(|w| F::from(w.value() as u64))
         )?;
+        self.spread.enable(region, offset+1, w_hi)?;
         region.assign_advice(
             || "W0 High Tag", 
             self.a0, offset+1, 
-            || Value::known(F::from(create_tag(w_hi)))
+            || w_hi.map(|w_hi| F::from(create_tag(w_hi)))
         )?;
         let w0_hi = region.assign_advice(
             || "W0 High Value", 
             self.a1, offset+1, 
-            || Value::known(F::from(w_hi as u64))
+            || w_hi.map(|w_hi| F::from(w_hi as u64))
         )?;
         region.assign_advice(
             || "W0 High Value Spread", 
             self.a2, offset+1, 
-            || Value::known(F::from(create_interleave_num(w_hi as u32) as u64))
+            || w_hi.map(|w_hi| F::from(create_interleave_num(w_hi as u32) as u64))
         )?;
         let w0_hi_c = w0_hi.copy_advice(|| "Same W0 High Value", region, self.a4, offset)?;
 
-        Ok(vec![w0_lo_c, w0_hi_c])
+        Ok(AssignedWord::new(w0_lo_c, w0_hi_c, word))
     }
 
     pub fn assign(
         &self,
         region: &mut Region<F>,
-        w_lo: u16,
-        w_hi: u16,
+        word: Value<Word32>,
         offset: usize
-    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+    ) -> Result<AssignedWord<F>, Error> {
+        let w_lo = word.map(|w| w.lo);
+        let w_hi = word.map(|w| w.hi);
 
         self.s_zero.enable(region, offset)?;
         let w_lo_c = region.assign_advice(
             || "W low", 
             self.a3, offset, 
-            || Value::known(F::from(w_lo as u64))
+            || w_lo.map(|w_lo| F::from(w_lo as u64))
         )?;
         let w_hi_c = region.assign_advice(
             || "W high", 
             self.a4, offset, 
-            || Value::known(F::from(w_hi as u64))
+            || w_hi.map(|w_hi| F::from(w_hi as u64))
         )?;
         region.assign_advice(
             || "W", 
             self.a5, offset, 
-            || Value::known(F::from((w_lo as u32 + (w_hi as u32) * (1 << 16)) as u64))
+            || word.map(|w| F::from(w.value() as u64))
         )?;
-        Ok(vec![w_lo_c, w_hi_c])
+        Ok(AssignedWord::new(w_lo_c, w_hi_c, word))
     }
 
     pub fn assign_special_1(
@@ -151,7 +160,7 @@ impl<F: FieldExt> DecomposeZeroConfig<F> {
         region: &mut Region<F>,
         w_lo: AssignedCell<F, F>,
         w_hi: AssignedCell<F, F>,
-        w: u32,
+        w: Value<u32>,
         offset: usize
     ) -> Result<(), Error> {
 
@@ -161,7 +170,7 @@ impl<F: FieldExt> DecomposeZeroConfig<F> {
         region.assign_advice(
             || "w", 
             self.a5, offset, 
-            || Value::known(F::from(w as u64))
+            || w.map(|w| F::from(w as u64))
         )?;
         Ok(())
     }
@@ -187,26 +196,27 @@ impl<F: FieldExt> DecomposeZeroConfig<F> {
     pub fn assign_special_3(
         &self,
         region: &mut Region<F>,
-        w_lo: u16,
-        w_hi: u16,
+        word: Value<Word32>,
         w: AssignedCell<F, F>,
         offset: usize
-    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+    ) -> Result<AssignedWord<F>, Error> {
+        let w_lo = word.map(|w| w.lo);
+        let w_hi = word.map(|w| w.hi);
 
         self.s_zero.enable(region, offset)?;
         let w_lo_c = region.assign_advice(
             || "w low", 
             self.a3, offset, 
-            || Value::known(F::from(w_lo as u64))
+            || w_lo.map(|w_lo| F::from(w_lo as u64))
         )?;
         let w_hi_c = region.assign_advice(
             || "w high", 
             self.a4, offset, 
-            || Value::known(F::from(w_hi as u64))
+            || w_hi.map(|w_hi| F::from(w_hi as u64))
         )?;
         w.copy_advice(|| "w", region, self.a5, offset)?;
 
-        Ok(vec![w_lo_c, w_hi_c])
+        Ok(AssignedWord::new(w_lo_c, w_hi_c, word))
     }
 
 }
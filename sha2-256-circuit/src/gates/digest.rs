@@ -7,16 +7,26 @@ use halo2_proofs::{
     poly::Rotation,
 };
 
+use crate::spread_table::SpreadLookupConfig;
+use crate::utils::{create_interleave_num, create_tag, create_value_2_check};
+use crate::word::{AssignedWord, Word32};
+
+/// Adds the working variables to the state. The digest of a word takes two
+/// rows, whose `a1` cells hold its limbs and are looked up in the spread table,
+/// so that they are range checked to 16 bits, while its carry is boolean.
 #[derive(Debug, Clone)]
 pub struct DigestConfig<F: FieldExt> {
     pub s_digest: Selector,
+    pub s_word: Selector,
+    a0: Column<Advice>,
+    a1: Column<Advice>,
+    a2: Column<Advice>,
     a3: Column<Advice>,
     a4: Column<Advice>,
     a5: Column<Advice>,
     a6: Column<Advice>,
-    a7: Column<Advice>,
-    a8: Column<Advice>,
     a9: Column<Advice>,
+    spread: SpreadLookupConfig<F>,
     _marker: PhantomData<F>
 }
 
@@ -24,15 +34,18 @@ impl<F: FieldExt> DigestConfig<F> {
 
     pub fn configure(
         meta: &mut ConstraintSystem<F>,
+        a0: Column<Advice>,
+        a1: Column<Advice>,
+        a2: Column<Advice>,
         a3: Column<Advice>,
         a4: Column<Advice>,
         a5: Column<Advice>,
         a6: Column<Advice>,
-        a7: Column<Advice>,
-        a8: Column<Advice>,
         a9: Column<Advice>,
+        spread: SpreadLookupConfig<F>,
     ) -> Self {
         let s_digest = meta.selector();
+        let s_word = meta.selector();
         meta.create_gate(
             "Digest Gate", 
             |meta| {
@@ -41,8 +54,8 @@ impl<F: FieldExt> DigestConfig<F> {
                 let a_hi = meta.query_advice(a4, Rotation::cur());
                 let h_lo = meta.query_advice(a5, Rotation::cur());
                 let h_hi = meta.query_advice(a6, Rotation::cur());
-                let h_n_lo = meta.query_advice(a7, Rotation::cur());
-                let h_n_hi = meta.query_advice(a8, Rotation::cur());
+                let h_n_lo = meta.query_advice(a1, Rotation::cur());
+                let h_n_hi = meta.query_advice(a1, Rotation::next());
                 let h_n_c = meta.query_advice(a9, Rotation::cur());
 
                 let lhs = 
@@ -51,17 +64,34 @@ impl<F: FieldExt> DigestConfig<F> {
 
                 let rhs = 
                     h_n_lo + h_n_hi * Expression::Constant(F::from( 1<< 16)) + 
-                    h_n_c * Expression::Constant(F::from( 1<< 32));
+                    h_n_c.clone() * Expression::Constant(F::from( 1<< 32));
                 
                 vec![
-                    s_digest * (lhs - rhs)
+                    s_digest.clone() * (lhs - rhs),
+                    s_digest * create_value_2_check(h_n_c)
+                ]
+            }
+        );
+
+        meta.create_gate(
+            "Digest Word Gate", 
+            |meta| {
+                let s_word = meta.query_selector(s_word);
+                let h_lo = meta.query_advice(a3, Rotation::cur());
+                let h_hi = meta.query_advice(a4, Rotation::cur());
+                let h = meta.query_advice(a5, Rotation::cur());
+
+                vec![
+                    s_word * (h_lo + h_hi * Expression::Constant(F::from( 1<< 16)) - h)
                 ]
             }
         );
 
         Self {
             s_digest,
-            a3, a4, a5, a6, a7, a8, a9,
+            s_word,
+            a0, a1, a2, a3, a4, a5, a6, a9,
+            spread,
             _marker: PhantomData
         }
 
@@ -70,26 +100,171 @@ impl<F: FieldExt> DigestConfig<F> {
     pub fn assign(
         &self,
         region: &mut Region<F>,
-        h_n_lo: u16,
-        h_n_hi: u16,
-        h_n_c: u64,
-        h_lo: AssignedCell<F, F>,
-        h_hi: AssignedCell<F, F>,
-        a_lo: AssignedCell<F, F>,
-        a_hi: AssignedCell<F, F>,
+        h_n: Value<Word32>,
+        h_n_c: Value<u64>,
+        h: &AssignedWord<F>,
+        a: &AssignedWord<F>,
+        offset: usize
+    ) -> Result<AssignedWord<F>, Error> {
+        let h_n_lo = h_n.map(|h_n| F::from(h_n.lo as u64));
+        let h_n_hi = h_n.map(|h_n| F::from(h_n.hi as u64));
+        let (h_n_lo_c, h_n_hi_c) = self.assign_cells(region, h_n_lo, h_n_hi, h_n_c.map(F::from), h, a, offset)?;
+
+        Ok(AssignedWord::new(h_n_lo_c, h_n_hi_c, h_n))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn assign_cells(
+        &self,
+        region: &mut Region<F>,
+        h_n_lo: Value<F>,
+        h_n_hi: Value<F>,
+        h_n_c: Value<F>,
+        h: &AssignedWord<F>,
+        a: &AssignedWord<F>,
         offset: usize
-    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
         self.s_digest.enable(region, offset)?;
-        a_lo.copy_advice(|| "s_digest a_lo", region, self.a3, offset)?;
-        a_hi.copy_advice(|| "s_digest a_hi", region, self.a4, offset)?;
-        h_lo.copy_advice(|| "s_digest h_lo", region, self.a5, offset)?;
-        h_hi.copy_advice(|| "s_digest h_hi", region, self.a6, offset)?;
-        let h_n_lo_c = region.assign_advice(|| "s_digest h_n_lo", self.a7, offset, || Value::known(F::from(h_n_lo as u64)))?;
-        let h_n_hi_c = region.assign_advice(|| "s_digest h_n_hi", self.a8, offset, || Value::known(F::from(h_n_hi as u64)))?;
-        region.assign_advice(|| "s_digest h_n_c", self.a9, offset, || Value::known(F::from(h_n_c)))?;
-
-        let res = vec![h_n_lo_c, h_n_hi_c];
-        Ok(res)
+        a.lo.copy_advice(|| "s_digest a_lo", region, self.a3, offset)?;
+        a.hi.copy_advice(|| "s_digest a_hi", region, self.a4, offset)?;
+        h.lo.copy_advice(|| "s_digest h_lo", region, self.a5, offset)?;
+        h.hi.copy_advice(|| "s_digest h_hi", region, self.a6, offset)?;
+        let h_n_lo_c = self.assign_limb(region, "s_digest h_n_lo", h_n_lo, offset)?;
+        let h_n_hi_c = self.assign_limb(region, "s_digest h_n_hi", h_n_hi, offset+1)?;
+        region.assign_advice(|| "s_digest h_n_c", self.a9, offset, || h_n_c)?;
+
+        Ok((h_n_lo_c, h_n_hi_c))
+    }
+
+    /// Range checks the limbs of `word` to 16 bits, by copying them into the
+    /// looked up `a1` cells of `offset` and `offset+1`.
+    pub fn assign_range(
+        &self,
+        region: &mut Region<F>,
+        word: &AssignedWord<F>,
+        offset: usize
+    ) -> Result<(), Error> {
+        for (i, limb) in [&word.lo, &word.hi].into_iter().enumerate() {
+            let limb_c = self.assign_limb(region, "s_digest range", limb.value().copied(), offset+i)?;
+            region.constrain_equal(limb_c.cell(), limb.cell())?;
+        }
+        Ok(())
     }
 
-}
\ No newline at end of file
+    /// Assigns `limb` to the `a1` cell of `offset` and looks it up in the
+    /// spread table.
+    fn assign_limb(
+        &self,
+        region: &mut Region<F>,
+        name: &str,
+        limb: Value<F>,
+        offset: usize
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let value = limb.map(|limb| limb.get_lower_32() as u16);
+        self.spread.enable(region, offset, value)?;
+        region.assign_advice(|| format!("{} tag", name), self.a0, offset, || value.map(|value| F::from(create_tag(value))))?;
+        region.assign_advice(|| format!("{} spread", name), self.a2, offset, || value.map(|value| F::from(create_interleave_num(value as u32) as u64)))?;
+        region.assign_advice(|| name, self.a1, offset, || limb)
+    }
+
+    pub fn assign_word(
+        &self,
+        region: &mut Region<F>,
+        h: &AssignedWord<F>,
+        offset: usize
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.s_word.enable(region, offset)?;
+        let h_lo = h.lo.copy_advice(|| "s_word h_lo", region, self.a3, offset)?;
+        let h_hi = h.hi.copy_advice(|| "s_word h_hi", region, self.a4, offset)?;
+        let h = h_lo.value().zip(h_hi.value()).map(|(lo, hi)| *lo + *hi * F::from(1 << 16));
+        region.assign_advice(|| "s_word h", self.a5, offset, || h)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::spread_table::SpreadTableBits;
+    use halo2_proofs::circuit::{Layouter, SimpleFloorPlanner};
+    use halo2_proofs::plonk::Circuit;
+    use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+
+    /// Adds `a = 2^32 - 1` to `h = 1`, whose digest is `0` with a carry of
+    /// `1`, with the limbs and carry of the digest given as field elements.
+    #[derive(Clone)]
+    struct DigestCircuit {
+        h_n_lo: Fr,
+        h_n_hi: Fr,
+        h_n_c: Fr,
+    }
+
+    impl Circuit<Fr> for DigestCircuit {
+        type Config = (DigestConfig<Fr>, SpreadLookupConfig<Fr>, Column<Advice>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            let cols: Vec<Column<Advice>> = (0..8).map(|_| meta.advice_column()).collect();
+            for col in cols.iter() {
+                meta.enable_equality(*col);
+            }
+            let spread = SpreadLookupConfig::configure(meta, cols[0], cols[1], cols[2], SpreadTableBits::Eight);
+            let digest = DigestConfig::configure(
+                meta, cols[0], cols[1], cols[2], cols[3], cols[4], cols[5], cols[6], cols[7], spread
+            );
+            (digest, spread, cols[3])
+        }
+
+        fn synthesize(&self, (digest, spread, a3): Self::Config, mut layouter: impl Layouter<Fr>) -> Result<(), Error> {
+            spread.load(&mut layouter)?;
+            layouter.assign_region(
+                || "digest",
+                |mut region| {
+                    let mut word = |word: Word32, offset: usize| -> Result<AssignedWord<Fr>, Error> {
+                        let lo = region.assign_advice(|| "lo", a3, offset, || Value::known(Fr::from(word.lo as u64)))?;
+                        let hi = region.assign_advice(|| "hi", a3, offset+1, || Value::known(Fr::from(word.hi as u64)))?;
+                        Ok(AssignedWord::new(lo, hi, Value::known(word)))
+                    };
+                    let a = word(Word32::new(0xFFFF, 0xFFFF), 0)?;
+                    let h = word(Word32::new(1, 0), 2)?;
+                    digest.assign_cells(
+                        &mut region,
+                        Value::known(self.h_n_lo),
+                        Value::known(self.h_n_hi),
+                        Value::known(self.h_n_c),
+                        &h, &a, 4
+                    )?;
+                    Ok(())
+                }
+            )
+        }
+    }
+
+    fn verify(h_n_lo: Fr, h_n_hi: Fr, h_n_c: Fr) -> bool {
+        let circuit = DigestCircuit { h_n_lo, h_n_hi, h_n_c };
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        prover.verify().is_ok()
+    }
+
+    #[test]
+    fn test_digest() {
+        assert!(verify(Fr::from(0), Fr::from(0), Fr::from(1)));
+    }
+
+    #[test]
+    fn test_digest_forged_carry() {
+        // 0 + 2^16 * -2^16 + 2^32 * 2 == 2^32
+        assert!(!verify(Fr::from(0), -Fr::from(1 << 16), Fr::from(2)));
+    }
+
+    #[test]
+    fn test_digest_forged_limbs() {
+        // 2^16 + 2^16 * -1 + 2^32 * 1 == 2^32
+        assert!(!verify(Fr::from(1 << 16), -Fr::from(1), Fr::from(1)));
+    }
+}
@@ -2,12 +2,13 @@ use std::{marker::PhantomData, vec};
 
 use halo2_proofs::{
     arithmetic::FieldExt,
-    circuit::{Region, Value, AssignedCell},
+    circuit::{Region, Value},
     plonk::{Advice, Column, ConstraintSystem, Expression, Selector, Error},
     poly::Rotation,
 };
 
 use crate::utils::create_value_2_check;
+use crate::word::{AssignedWord, Word32};
 
 #[derive(Debug, Clone)]
 pub struct ENewConfig<F: FieldExt> {
@@ -68,48 +69,42 @@ impl<F: FieldExt> ENewConfig<F> {
     pub fn assign(
         &self,
         region: &mut Region<F>,
-        d_lo: u16,
-        d_hi: u16,
-        e_lo: u16,
-        e_hi: u16,
-        e_c: u64,
+        d: Value<Word32>,
+        e: Value<Word32>,
+        e_c: Value<u64>,
         offset: usize
-    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+    ) -> Result<(AssignedWord<F>, AssignedWord<F>), Error> {
         self.s_e_new.enable(region, offset)?;
         
-        let d_lo_c = region.assign_advice(|| "s_e_new d_lo", self.a7, offset, || Value::known(F::from(d_lo as u64)))?;
-        let e_n_lo = region.assign_advice(|| "s_e_new e_n_lo", self.a8, offset, || Value::known(F::from(e_lo as u64)))?;
+        let d_lo_c = region.assign_advice(|| "s_e_new d_lo", self.a7, offset, || d.map(|d| F::from(d.lo as u64)))?;
+        let e_n_lo = region.assign_advice(|| "s_e_new e_n_lo", self.a8, offset, || e.map(|e| F::from(e.lo as u64)))?;
 
-        let d_hi_c = region.assign_advice(|| "s_e_new d_hi", self.a7, offset+1, || Value::known(F::from(d_hi as u64)))?;
-        let e_n_hi = region.assign_advice(|| "s_e_new e_n_hi", self.a8, offset+1, || Value::known(F::from(e_hi as u64)))?;
-        region.assign_advice(|| "s_e_new e_n_c", self.a9, offset+1, || Value::known(F::from(e_c)))?;
+        let d_hi_c = region.assign_advice(|| "s_e_new d_hi", self.a7, offset+1, || d.map(|d| F::from(d.hi as u64)))?;
+        let e_n_hi = region.assign_advice(|| "s_e_new e_n_hi", self.a8, offset+1, || e.map(|e| F::from(e.hi as u64)))?;
+        region.assign_advice(|| "s_e_new e_n_c", self.a9, offset+1, || e_c.map(F::from))?;
 
-        let res = vec![e_n_lo, e_n_hi, d_lo_c, d_hi_c];
-        Ok(res)
+        Ok((AssignedWord::new(e_n_lo, e_n_hi, e), AssignedWord::new(d_lo_c, d_hi_c, d)))
 
     }
 
     pub fn assign_steady(
         &self,
         region: &mut Region<F>,
-        d_lo: AssignedCell<F, F>,
-        d_hi: AssignedCell<F, F>,
-        e_lo: u16,
-        e_hi: u16,
-        e_c: u64,
+        d: &AssignedWord<F>,
+        e: Value<Word32>,
+        e_c: Value<u64>,
         offset: usize
-    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+    ) -> Result<(AssignedWord<F>, AssignedWord<F>), Error> {
         self.s_e_new.enable(region, offset)?;
         
-        let d_lo_c = d_lo.copy_advice(|| "s_e_new d_lo", region, self.a7, offset)?;
-        let e_n_lo = region.assign_advice(|| "s_e_new e_n_lo", self.a8, offset, || Value::known(F::from(e_lo as u64)))?;
+        let d_lo_c = d.lo.copy_advice(|| "s_e_new d_lo", region, self.a7, offset)?;
+        let e_n_lo = region.assign_advice(|| "s_e_new e_n_lo", self.a8, offset, || e.map(|e| F::from(e.lo as u64)))?;
 
-        let d_hi_c = d_hi.copy_advice(|| "s_e_new d_hi", region, self.a7, offset+1)?;
-        let e_n_hi = region.assign_advice(|| "s_e_new e_n_hi", self.a8, offset+1, || Value::known(F::from(e_hi as u64)))?;
-        region.assign_advice(|| "s_e_new e_n_c", self.a9, offset+1, || Value::known(F::from(e_c)))?;
+        let d_hi_c = d.hi.copy_advice(|| "s_e_new d_hi", region, self.a7, offset+1)?;
+        let e_n_hi = region.assign_advice(|| "s_e_new e_n_hi", self.a8, offset+1, || e.map(|e| F::from(e.hi as u64)))?;
+        region.assign_advice(|| "s_e_new e_n_c", self.a9, offset+1, || e_c.map(F::from))?;
 
-        let res = vec![e_n_lo, e_n_hi, d_lo_c, d_hi_c];
-        Ok(res)
+        Ok((AssignedWord::new(e_n_lo, e_n_hi, e), AssignedWord::new(d_lo_c, d_hi_c, d.value)))
 
     }
 
@@ -1,6 +1,7 @@
 use std::{marker::PhantomData, vec};
 
-use crate::utils::create_value_4_check;
+use crate::utils::{create_value_4_check, ROUND_CONSTANTS};
+use crate::word::{AssignedWord, Word32};
 
 use halo2_proofs::{
     arithmetic::FieldExt,
@@ -93,88 +94,90 @@ impl<F: FieldExt> HPrimeConfig<F> {
     pub fn assign(
         &self,
         region: &mut Region<F>,
-        h_lo: u16,
-        h_hi: u16,
-        h_prime_lo: u16,
-        h_prime_hi: u16,
-        h_prime_c: u64,
-        k_lo: u16,
-        k_hi: u16,
+        h: Value<Word32>,
+        h_prime: Value<Word32>,
+        h_prime_c: Value<u64>,
+        round: usize,
         p_hi: AssignedCell<F, F>,
-        q_lo: u16,
-        q_hi: u16,
-        sum_lo: AssignedCell<F, F>,
-        sum_hi: AssignedCell<F, F>,
-        w_lo: AssignedCell<F, F>,
-        w_hi: AssignedCell<F, F>,
+        q: Value<Word32>,
+        sum: &AssignedWord<F>,
+        w: &AssignedWord<F>,
         offset: usize,
-    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+    ) -> Result<(AssignedWord<F>, AssignedWord<F>, AssignedWord<F>), Error> {
 
         self.s_h_prime.enable(region, offset)?;
-
-        let q_lo_c = region.assign_advice(|| "h_prime q_lo", self.a5, offset-1, || Value::known(F::from(q_lo as u64)))?;
-        region.assign_advice(|| "h_prime k_lo", self.a6, offset-1, || Value::known(F::from(k_lo as u64)))?;
-        let h_lo_c = region.assign_advice(|| "h_prime h_lo", self.a7, offset-1, || Value::known(F::from(h_lo as u64)))?;
-        w_lo.copy_advice(|| "h_prime w_lo", region, self.a8, offset-1)?;
-
-        sum_lo.copy_advice(|| "h_prime sum_lo", region, self.a4, offset)?;
-        sum_hi.copy_advice(|| "h_prime sum_hi", region, self.a5, offset)?;
-        region.assign_advice(|| "h_prime k_hi", self.a6, offset, || Value::known(F::from(k_hi as u64)))?;
-        let h_hi_c = region.assign_advice(|| "h_prime h_hi", self.a7, offset, || Value::known(F::from(h_hi as u64)))?;
-        w_hi.copy_advice(|| "h_prime w_hi", region, self.a8, offset)?;
-
-        let q_hi_c = region.assign_advice(|| "h_prime q_hi", self.a5, offset+1, || Value::known(F::from(q_hi as u64)))?;
+        let k = Word32::from(ROUND_CONSTANTS[round]);
+        let q_lo = q.map(|q| q.lo);
+        let q_hi = q.map(|q| q.hi);
+        let h_lo = h.map(|h| h.lo);
+        let h_hi = h.map(|h| h.hi);
+
+        let q_lo_c = region.assign_advice(|| "h_prime q_lo", self.a5, offset-1, || q_lo.map(|q_lo| F::from(q_lo as u64)))?;
+        region.assign_advice_from_constant(|| "h_prime k_lo", self.a6, offset-1, F::from(k.lo as u64))?;
+        let h_lo_c = region.assign_advice(|| "h_prime h_lo", self.a7, offset-1, || h_lo.map(|h_lo| F::from(h_lo as u64)))?;
+        w.lo.copy_advice(|| "h_prime w_lo", region, self.a8, offset-1)?;
+
+        sum.lo.copy_advice(|| "h_prime sum_lo", region, self.a4, offset)?;
+        sum.hi.copy_advice(|| "h_prime sum_hi", region, self.a5, offset)?;
+        region.assign_advice_from_constant(|| "h_prime k_hi", self.a6, offset, F::from(k.hi as u64))?;
+        let h_hi_c = region.assign_advice(|| "h_prime h_hi", self.a7, offset, || h_hi.map(|h_hi| F::from(h_hi as u64)))?;
+        w.hi.copy_advice(|| "h_prime w_hi", region, self.a8, offset)?;
+
+        let q_hi_c = region.assign_advice(|| "h_prime q_hi", self.a5, offset+1, || q_hi.map(|q_hi| F::from(q_hi as u64)))?;
         p_hi.copy_advice(|| "h_prime p_hi", region, self.a6, offset+1)?;
-        let h_p_lo_c = region.assign_advice(|| "h_prime h_prime_lo", self.a7, offset+1, || Value::known(F::from(h_prime_lo as u64)))?;
-        let h_p_hi_c = region.assign_advice(|| "h_prime h_prime_hi", self.a8, offset+1, || Value::known(F::from(h_prime_hi as u64)))?;
-        region.assign_advice(|| "h_prime h_prime_c", self.a9, offset+1, || Value::known(F::from(h_prime_c)))?;
+        let h_p_lo_c = region.assign_advice(|| "h_prime h_prime_lo", self.a7, offset+1, || h_prime.map(|h_prime| F::from(h_prime.lo as u64)))?;
+        let h_p_hi_c = region.assign_advice(|| "h_prime h_prime_hi", self.a8, offset+1, || h_prime.map(|h_prime| F::from(h_prime.hi as u64)))?;
+        region.assign_advice(|| "h_prime h_prime_c", self.a9, offset+1, || h_prime_c.map(F::from))?;
 
-        let res = vec![h_p_lo_c, h_p_hi_c, q_lo_c, q_hi_c, h_lo_c, h_hi_c];
-        Ok(res)
+        Ok((
+            AssignedWord::new(h_p_lo_c, h_p_hi_c, h_prime),
+            AssignedWord::new(q_lo_c, q_hi_c, q),
+            AssignedWord::new(h_lo_c, h_hi_c, h),
+        ))
 
     }
 
     pub fn assign_steady(
         &self,
         region: &mut Region<F>,
-        h_lo: AssignedCell<F, F>,
-        h_hi: AssignedCell<F, F>,
-        h_prime_lo: u16,
-        h_prime_hi: u16,
-        h_prime_c: u64,
-        k_lo: u16,
-        k_hi: u16,
+        h: &AssignedWord<F>,
+        h_prime: Value<Word32>,
+        h_prime_c: Value<u64>,
+        round: usize,
         p_hi: AssignedCell<F, F>,
-        q_lo: u16,
-        q_hi: u16,
-        sum_lo: AssignedCell<F, F>,
-        sum_hi: AssignedCell<F, F>,
-        w_lo: AssignedCell<F, F>,
-        w_hi: AssignedCell<F, F>,
+        q: Value<Word32>,
+        sum: &AssignedWord<F>,
+        w: &AssignedWord<F>,
         offset: usize,
-    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+    ) -> Result<(AssignedWord<F>, AssignedWord<F>, AssignedWord<F>), Error> {
 
         self.s_h_prime.enable(region, offset)?;
-
-        let q_lo_c = region.assign_advice(|| "h_prime q_lo", self.a5, offset-1, || Value::known(F::from(q_lo as u64)))?;
-        region.assign_advice(|| "h_prime k_lo", self.a6, offset-1, || Value::known(F::from(k_lo as u64)))?;
-        let h_lo_c = h_lo.copy_advice(|| "h_prime h_lo", region, self.a7, offset-1)?;
-        w_lo.copy_advice(|| "h_prime w_lo", region, self.a8, offset-1)?;
-
-        sum_lo.copy_advice(|| "h_prime sum_lo", region, self.a4, offset)?;
-        sum_hi.copy_advice(|| "h_prime sum_hi", region, self.a5, offset)?;
-        region.assign_advice(|| "h_prime k_hi", self.a6, offset, || Value::known(F::from(k_hi as u64)))?;
-        let h_hi_c = h_hi.copy_advice(|| "h_prime h_hi", region, self.a7, offset)?;
-        w_hi.copy_advice(|| "h_prime w_hi", region, self.a8, offset)?;
-
-        let q_hi_c = region.assign_advice(|| "h_prime q_hi", self.a5, offset+1, || Value::known(F::from(q_hi as u64)))?;
+        let k = Word32::from(ROUND_CONSTANTS[round]);
+        let q_lo = q.map(|q| q.lo);
+        let q_hi = q.map(|q| q.hi);
+
+        let q_lo_c = region.assign_advice(|| "h_prime q_lo", self.a5, offset-1, || q_lo.map(|q_lo| F::from(q_lo as u64)))?;
+        region.assign_advice_from_constant(|| "h_prime k_lo", self.a6, offset-1, F::from(k.lo as u64))?;
+        let h_lo_c = h.lo.copy_advice(|| "h_prime h_lo", region, self.a7, offset-1)?;
+        w.lo.copy_advice(|| "h_prime w_lo", region, self.a8, offset-1)?;
+
+        sum.lo.copy_advice(|| "h_prime sum_lo", region, self.a4, offset)?;
+        sum.hi.copy_advice(|| "h_prime sum_hi", region, self.a5, offset)?;
+        region.assign_advice_from_constant(|| "h_prime k_hi", self.a6, offset, F::from(k.hi as u64))?;
+        let h_hi_c = h.hi.copy_advice(|| "h_prime h_hi", region, self.a7, offset)?;
+        w.hi.copy_advice(|| "h_prime w_hi", region, self.a8, offset)?;
+
+        let q_hi_c = region.assign_advice(|| "h_prime q_hi", self.a5, offset+1, || q_hi.map(|q_hi| F::from(q_hi as u64)))?;
         p_hi.copy_advice(|| "h_prime p_hi", region, self.a6, offset+1)?;
-        let h_p_lo_c = region.assign_advice(|| "h_prime h_prime_lo", self.a7, offset+1, || Value::known(F::from(h_prime_lo as u64)))?;
-        let h_p_hi_c = region.assign_advice(|| "h_prime h_prime_hi", self.a8, offset+1, || Value::known(F::from(h_prime_hi as u64)))?;
-        region.assign_advice(|| "h_prime h_prime_c", self.a9, offset+1, || Value::known(F::from(h_prime_c)))?;
-
-        let res = vec![h_p_lo_c, h_p_hi_c, q_lo_c, q_hi_c, h_lo_c, h_hi_c];
-        Ok(res)
+        let h_p_lo_c = region.assign_advice(|| "h_prime h_prime_lo", self.a7, offset+1, || h_prime.map(|h_prime| F::from(h_prime.lo as u64)))?;
+        let h_p_hi_c = region.assign_advice(|| "h_prime h_prime_hi", self.a8, offset+1, || h_prime.map(|h_prime| F::from(h_prime.hi as u64)))?;
+        region.assign_advice(|| "h_prime h_prime_c", self.a9, offset+1, || h_prime_c.map(F::from))?;
+
+        Ok((
+            AssignedWord::new(h_p_lo_c, h_p_hi_c, h_prime),
+            AssignedWord::new(q_lo_c, q_hi_c, q),
+            AssignedWord::new(h_lo_c, h_hi_c, h.value),
+        ))
 
     }
 
@@ -7,9 +7,13 @@ use crate::utils::{
     create_tag
 };
 
+use crate::word::AssignedWord32;
+
+use crate::spread_table::SpreadLookupConfig;
+
 use halo2_proofs::{
     arithmetic::FieldExt,
-    circuit::{Region, Value, AssignedCell},
+    circuit::{Region, Value},
     plonk::{Advice, Column, ConstraintSystem, Expression, Selector, Error},
     poly::Rotation,
 };
@@ -23,6 +27,7 @@ pub struct MajConfig<F: FieldExt> {
     a3: Column<Advice>,
     a4: Column<Advice>,
     a5: Column<Advice>,
+    spread: SpreadLookupConfig<F>,
     _marker: PhantomData<F>
 }
 
@@ -36,6 +41,7 @@ impl<F: FieldExt> MajConfig<F> {
         a3: Column<Advice>,
         a4: Column<Advice>,
         a5: Column<Advice>,
+        spread: SpreadLookupConfig<F>,
     ) -> Self {
         let s_maj = meta.selector();
         meta.create_gate(
@@ -66,6 +72,7 @@ impl<F: FieldExt> MajConfig<F> {
         Self {
             s_maj,
             a0, a1, a2, a3, a4, a5,
+            spread,
             _marker: PhantomData
         }
     }
@@ -73,49 +80,52 @@ impl<F: FieldExt> MajConfig<F> {
     pub fn assign(
         &self,
         region: &mut Region<F>,
-        m_lo: u32,
-        m_hi: u32,
-        s_a_lo: AssignedCell<F, F>,
-        s_a_hi: AssignedCell<F, F>,
-        s_b_lo: AssignedCell<F, F>,
-        s_b_hi: AssignedCell<F, F>,
-        s_c_lo: AssignedCell<F, F>,
-        s_c_hi: AssignedCell<F, F>,
+        m_lo: Value<u32>,
+        m_hi: Value<u32>,
+        a: &AssignedWord32<F>,
+        b: &AssignedWord32<F>,
+        c: &AssignedWord32<F>,
         offset: usize,
-    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+    ) -> Result<(AssignedWord32<F>, AssignedWord32<F>, AssignedWord32<F>), Error> {
 
         self.s_maj.enable(region, offset)?;
-        let m_e_0 = even_bit(m_lo);
-        let m_o_0 = odd_bit(m_lo);
-        let m_e_1 = even_bit(m_hi);
-        let m_o_1 = odd_bit(m_hi);
+        let m_e_0 = m_lo.map(even_bit);
+        let m_o_0 = m_lo.map(odd_bit);
+        let m_e_1 = m_hi.map(even_bit);
+        let m_o_1 = m_hi.map(odd_bit);
 
-        region.assign_advice(|| "s_maj m_e_0 tag", self.a0, offset-1, || Value::known(F::from(create_tag(m_e_0) as u64)))?;
-        region.assign_advice(|| "s_maj m_e_0", self.a1, offset-1, || Value::known(F::from(m_e_0 as u64)))?;
-        region.assign_advice(|| "s_maj s_m_e_0", self.a2, offset-1, || Value::known(F::from(create_interleave_num(m_e_0 as u32) as u64)))?;
-        let s_a_lo_c = s_a_lo.copy_advice(|| "s_maj s_a_lo", region, self.a4, offset-1)?;
-        let s_a_hi_c = s_a_hi.copy_advice(|| "s_maj s_a_hi", region, self.a5, offset-1)?;
+        self.spread.enable(region, offset-1, m_e_0)?;
+        region.assign_advice(|| "s_maj m_e_0 tag", self.a0, offset-1, || m_e_0.map(|m_e_0| F::from(create_tag(m_e_0) as u64)))?;
+        region.assign_advice(|| "s_maj m_e_0", self.a1, offset-1, || m_e_0.map(|m_e_0| F::from(m_e_0 as u64)))?;
+        region.assign_advice(|| "s_maj s_m_e_0", self.a2, offset-1, || m_e_0.map(|m_e_0| F::from(create_interleave_num(m_e_0 as u32) as u64)))?;
+        let s_a_lo_c = a.spread_lo.copy_advice(|| "s_maj s_a_lo", region, self.a4, offset-1)?;
+        let s_a_hi_c = a.spread_hi.copy_advice(|| "s_maj s_a_hi", region, self.a5, offset-1)?;
         
-        region.assign_advice(|| "s_maj m_o_0 tag", self.a0, offset, || Value::known(F::from(create_tag(m_o_0) as u64)))?;
-        region.assign_advice(|| "s_maj m_o_0", self.a1, offset, || Value::known(F::from(m_o_0 as u64)))?;
-        region.assign_advice(|| "s_maj s_m_o_0", self.a2, offset, || Value::known(F::from(create_interleave_num(m_o_0 as u32) as u64)))?;
-        let s_b_lo_c = s_b_lo.copy_advice(|| "s_maj s_b_lo", region, self.a4, offset)?;
-        let s_b_hi_c = s_b_hi.copy_advice(|| "s_maj s_b_hi", region, self.a5, offset)?;
+        self.spread.enable(region, offset, m_o_0)?;
+        region.assign_advice(|| "s_maj m_o_0 tag", self.a0, offset, || m_o_0.map(|m_o_0| F::from(create_tag(m_o_0) as u64)))?;
+        region.assign_advice(|| "s_maj m_o_0", self.a1, offset, || m_o_0.map(|m_o_0| F::from(m_o_0 as u64)))?;
+        region.assign_advice(|| "s_maj s_m_o_0", self.a2, offset, || m_o_0.map(|m_o_0| F::from(create_interleave_num(m_o_0 as u32) as u64)))?;
+        let s_b_lo_c = b.spread_lo.copy_advice(|| "s_maj s_b_lo", region, self.a4, offset)?;
+        let s_b_hi_c = b.spread_hi.copy_advice(|| "s_maj s_b_hi", region, self.a5, offset)?;
 
-        region.assign_advice(|| "s_maj m_e_1 tag", self.a0, offset+1, || Value::known(F::from(create_tag(m_e_1) as u64)))?;
-        region.assign_advice(|| "s_maj m_e_1", self.a1, offset+1, || Value::known(F::from(m_e_1 as u64)))?;
-        region.assign_advice(|| "s_maj s_m_e_1", self.a2, offset+1, || Value::known(F::from(create_interleave_num(m_e_1 as u32) as u64)))?;
-        let s_c_lo_c = s_c_lo.copy_advice(|| "s_maj s_c_lo", region, self.a4, offset+1)?;
-        let s_c_hi_c = s_c_hi.copy_advice(|| "s_maj s_c_hi", region, self.a5, offset+1)?;
+        self.spread.enable(region, offset+1, m_e_1)?;
+        region.assign_advice(|| "s_maj m_e_1 tag", self.a0, offset+1, || m_e_1.map(|m_e_1| F::from(create_tag(m_e_1) as u64)))?;
+        region.assign_advice(|| "s_maj m_e_1", self.a1, offset+1, || m_e_1.map(|m_e_1| F::from(m_e_1 as u64)))?;
+        region.assign_advice(|| "s_maj s_m_e_1", self.a2, offset+1, || m_e_1.map(|m_e_1| F::from(create_interleave_num(m_e_1 as u32) as u64)))?;
+        let s_c_lo_c = c.spread_lo.copy_advice(|| "s_maj s_c_lo", region, self.a4, offset+1)?;
+        let s_c_hi_c = c.spread_hi.copy_advice(|| "s_maj s_c_hi", region, self.a5, offset+1)?;
 
-        region.assign_advice(|| "s_maj m_o_1 tag", self.a0, offset+2, || Value::known(F::from(create_tag(m_o_1) as u64)))?;
-        let m_o_1_c = region.assign_advice(|| "s_maj m_o_1", self.a1, offset+2, || Value::known(F::from(m_o_1 as u64)))?;
-        let s_m_o_1 = region.assign_advice(|| "s_maj s_m_o_1", self.a2, offset+2, || Value::known(F::from(create_interleave_num(m_o_1 as u32) as u64)))?;
+        self.spread.enable(region, offset+2, m_o_1)?;
+        region.assign_advice(|| "s_maj m_o_1 tag", self.a0, offset+2, || m_o_1.map(|m_o_1| F::from(create_tag(m_o_1) as u64)))?;
+        let m_o_1_c = region.assign_advice(|| "s_maj m_o_1", self.a1, offset+2, || m_o_1.map(|m_o_1| F::from(m_o_1 as u64)))?;
+        let s_m_o_1 = region.assign_advice(|| "s_maj s_m_o_1", self.a2, offset+2, || m_o_1.map(|m_o_1| F::from(create_interleave_num(m_o_1 as u32) as u64)))?;
         m_o_1_c.copy_advice(|| "s_maj m_o_1 copy", region, self.a3, offset-1)?;
         s_m_o_1.copy_advice(|| "s_maj s_m_o_1 copy", region, self.a3, offset)?;
 
-        let res = vec![s_a_lo_c, s_a_hi, s_b_lo_c, s_b_hi_c, s_c_lo_c, s_c_hi_c];
-
-        Ok(res)
+        Ok((
+            a.with_spread(s_a_lo_c, s_a_hi_c),
+            b.with_spread(s_b_lo_c, s_b_hi_c),
+            c.with_spread(s_c_lo_c, s_c_hi_c),
+        ))
     }
 }
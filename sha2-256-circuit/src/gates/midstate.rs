@@ -0,0 +1,114 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Region},
+    plonk::{Advice, Column, ConstraintSystem, Expression, Selector, Error},
+    poly::Rotation,
+};
+
+/// Tracks the number of blocks hashed into a midstate and checks the length
+/// field of the final block against it.
+///
+/// The counter gate adds the constant number of blocks `n` in `a4` to the
+/// counter in `a3`, giving the new counter in `a5`. The length gate recomposes
+/// the 8 big-endian bytes of the length field, in `a3` on the 8 rows ending at
+/// the current one, and constrains them to the bit length of a message of
+/// `blocks` blocks, in `a4`, followed by `tail` bytes, in `a5`.
+#[derive(Debug, Clone)]
+pub struct MidstateConfig<F: FieldExt> {
+    s_count: Selector,
+    s_length: Selector,
+    a3: Column<Advice>,
+    a4: Column<Advice>,
+    a5: Column<Advice>,
+    _marker: PhantomData<F>
+}
+
+impl<F: FieldExt> MidstateConfig<F> {
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a3: Column<Advice>,
+        a4: Column<Advice>,
+        a5: Column<Advice>,
+    ) -> Self {
+        let s_count = meta.selector();
+        let s_length = meta.selector();
+
+        meta.create_gate(
+            "Block Count Gate",
+            |meta| {
+                let s_count = meta.query_selector(s_count);
+                let blocks = meta.query_advice(a3, Rotation::cur());
+                let n = meta.query_advice(a4, Rotation::cur());
+                let blocks_new = meta.query_advice(a5, Rotation::cur());
+
+                vec![
+                    s_count * (blocks + n - blocks_new)
+                ]
+            }
+        );
+
+        meta.create_gate(
+            "Length Field Gate",
+            |meta| {
+                let s_length = meta.query_selector(s_length);
+                let blocks = meta.query_advice(a4, Rotation::cur());
+                let tail = meta.query_advice(a5, Rotation::cur());
+
+                let mut length = Expression::Constant(F::zero());
+                for i in 0..8 {
+                    let byte = meta.query_advice(a3, Rotation(i - 7));
+                    length = length * Expression::Constant(F::from(1 << 8)) + byte;
+                }
+                let bits = blocks * Expression::Constant(F::from(512)) + tail * Expression::Constant(F::from(8));
+
+                vec![
+                    s_length * (length - bits)
+                ]
+            }
+        );
+
+        Self {
+            s_count,
+            s_length,
+            a3, a4, a5,
+            _marker: PhantomData
+        }
+    }
+
+    /// Returns the counter `blocks + n`, with `n` constrained to a constant.
+    pub fn assign_count(
+        &self,
+        region: &mut Region<F>,
+        blocks: &AssignedCell<F, F>,
+        n: u64,
+        offset: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.s_count.enable(region, offset)?;
+        let blocks = blocks.copy_advice(|| "s_count blocks", region, self.a3, offset)?;
+        region.assign_advice_from_constant(|| "s_count n", self.a4, offset, F::from(n))?;
+        let blocks_new = blocks.value().map(|blocks| *blocks + F::from(n));
+        region.assign_advice(|| "s_count blocks_new", self.a5, offset, || blocks_new)
+    }
+
+    /// Constrains the 8 bytes of a length field to the bit length of `blocks`
+    /// blocks followed by `tail` bytes, with `tail` constrained to a constant.
+    pub fn assign_length(
+        &self,
+        region: &mut Region<F>,
+        length: &[AssignedCell<F, F>],
+        blocks: &AssignedCell<F, F>,
+        tail: u64,
+        offset: usize,
+    ) -> Result<(), Error> {
+        self.s_length.enable(region, offset + 7)?;
+        for (i, byte) in length.iter().enumerate() {
+            byte.copy_advice(|| "s_length byte", region, self.a3, offset + i)?;
+        }
+        blocks.copy_advice(|| "s_length blocks", region, self.a4, offset + 7)?;
+        region.assign_advice_from_constant(|| "s_length tail", self.a5, offset + 7, F::from(tail))?;
+        Ok(())
+    }
+}
@@ -9,6 +9,8 @@ pub mod decompose_one;
 pub mod decompose_two;
 pub mod decompose_three;
 pub mod digest;
+pub mod block_state;
+pub mod byte_decompose;
 pub mod h_prime;
 pub mod e_new;
 pub mod a_new;
@@ -17,3 +19,6 @@ pub mod sigma_zero_v_one;
 pub mod sigma_zero_v_two;
 pub mod sigma_one_v_one;
 pub mod sigma_one_v_two;
+pub mod pad_xor;
+pub mod cond_swap;
+pub mod midstate;
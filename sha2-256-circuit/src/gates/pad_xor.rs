@@ -0,0 +1,150 @@
+use std::marker::PhantomData;
+
+use crate::utils::{
+    even_bit,
+    odd_bit,
+    create_interleave_num,
+    create_tag,
+    HMAC_IPAD,
+    HMAC_OPAD,
+};
+
+use crate::word::{AssignedWord, Word32};
+
+use crate::spread_table::SpreadLookupConfig;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Expression, Selector, Error},
+    poly::Rotation,
+};
+
+/// The HMAC pad a key block is XORed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HmacPad {
+    Inner,
+    Outer,
+}
+
+impl HmacPad {
+    /// The pad byte repeated over a 16-bit limb.
+    pub fn limb(&self) -> u16 {
+        let byte = match self {
+            Self::Inner => HMAC_IPAD,
+            Self::Outer => HMAC_OPAD,
+        };
+        u16::from_be_bytes([byte, byte])
+    }
+}
+
+/// XORs the limbs of a word with a pad constant through their spread form:
+/// the sum of the spread limb and the spread pad is split into its even bits,
+/// the XOR, and its odd bits, the AND. The limb, the XOR and the AND are each
+/// on a `(tag, value, spread)` row of `a0..a2`, the limb first, on which
+/// `spread` enables the spread table lookup.
+#[derive(Debug, Clone)]
+pub struct PadXorConfig<F: FieldExt> {
+    s_ipad: Selector,
+    s_opad: Selector,
+    a0: Column<Advice>,
+    a1: Column<Advice>,
+    a2: Column<Advice>,
+    spread: SpreadLookupConfig<F>,
+    _marker: PhantomData<F>
+}
+
+impl<F: FieldExt> PadXorConfig<F> {
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a0: Column<Advice>,
+        a1: Column<Advice>,
+        a2: Column<Advice>,
+        spread: SpreadLookupConfig<F>,
+    ) -> Self {
+        let s_ipad = meta.selector();
+        let s_opad = meta.selector();
+        meta.create_gate(
+            "Pad Xor Gate",
+            |meta| {
+                let s_ipad = meta.query_selector(s_ipad);
+                let s_opad = meta.query_selector(s_opad);
+                let s_k = meta.query_advice(a2, Rotation::cur());
+                let s_x = meta.query_advice(a2, Rotation::next());
+                let s_y = meta.query_advice(a2, Rotation(2));
+
+                let s_ipad_limb = create_interleave_num(HmacPad::Inner.limb() as u32);
+                let s_opad_limb = create_interleave_num(HmacPad::Outer.limb() as u32);
+                let rhs = s_x + s_y * Expression::Constant(F::from(2));
+
+                vec![
+                    s_ipad * (s_k.clone() + Expression::Constant(F::from(s_ipad_limb as u64)) - rhs.clone()),
+                    s_opad * (s_k + Expression::Constant(F::from(s_opad_limb as u64)) - rhs),
+                ]
+            }
+        );
+
+        Self {
+            s_ipad,
+            s_opad,
+            a0, a1, a2,
+            spread,
+            _marker: PhantomData
+        }
+    }
+
+    /// Number of rows used by `assign`, three per limb.
+    pub fn rows() -> usize {
+        6
+    }
+
+    /// Returns `word ^ pad`, with `pad` repeated over the four bytes of the
+    /// word.
+    pub fn assign(
+        &self,
+        region: &mut Region<F>,
+        word: &AssignedWord<F>,
+        pad: HmacPad,
+        offset: usize,
+    ) -> Result<AssignedWord<F>, Error> {
+        let lo = self.assign_limb(region, &word.lo, word.value.map(|word| word.lo), pad, offset)?;
+        let hi = self.assign_limb(region, &word.hi, word.value.map(|word| word.hi), pad, offset + 3)?;
+        let value = word.value.map(|word| Word32::new(word.lo ^ pad.limb(), word.hi ^ pad.limb()));
+        Ok(AssignedWord::new(lo, hi, value))
+    }
+
+    fn assign_limb(
+        &self,
+        region: &mut Region<F>,
+        limb: &AssignedCell<F, F>,
+        k: Value<u16>,
+        pad: HmacPad,
+        offset: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        match pad {
+            HmacPad::Inner => self.s_ipad.enable(region, offset)?,
+            HmacPad::Outer => self.s_opad.enable(region, offset)?,
+        };
+        let sum = k.map(|k| create_interleave_num(k as u32) + create_interleave_num(pad.limb() as u32));
+        let x = sum.map(even_bit);
+        let y = sum.map(odd_bit);
+
+        self.spread.enable(region, offset, k)?;
+        region.assign_advice(|| "s_pad k tag", self.a0, offset, || k.map(|k| F::from(create_tag(k))))?;
+        limb.copy_advice(|| "s_pad k", region, self.a1, offset)?;
+        region.assign_advice(|| "s_pad s_k", self.a2, offset, || k.map(|k| F::from(create_interleave_num(k as u32) as u64)))?;
+
+        self.spread.enable(region, offset+1, x)?;
+        region.assign_advice(|| "s_pad x tag", self.a0, offset+1, || x.map(|x| F::from(create_tag(x))))?;
+        let x_c = region.assign_advice(|| "s_pad x", self.a1, offset+1, || x.map(|x| F::from(x as u64)))?;
+        region.assign_advice(|| "s_pad s_x", self.a2, offset+1, || x.map(|x| F::from(create_interleave_num(x as u32) as u64)))?;
+
+        self.spread.enable(region, offset+2, y)?;
+        region.assign_advice(|| "s_pad y tag", self.a0, offset+2, || y.map(|y| F::from(create_tag(y))))?;
+        region.assign_advice(|| "s_pad y", self.a1, offset+2, || y.map(|y| F::from(y as u64)))?;
+        region.assign_advice(|| "s_pad s_y", self.a2, offset+2, || y.map(|y| F::from(create_interleave_num(y as u32) as u64)))?;
+
+        Ok(x_c)
+    }
+}
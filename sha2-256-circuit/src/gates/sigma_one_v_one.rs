@@ -11,6 +11,8 @@ use crate::utils::{
     odd_bit
 };
 
+use crate::spread_table::SpreadLookupConfig;
+
 use halo2_proofs::{
     arithmetic::FieldExt,
     circuit::{Region, Value, AssignedCell},
@@ -29,6 +31,7 @@ pub struct Sigma1V1Config<F: FieldExt> {
     a5: Column<Advice>,
     a6: Column<Advice>,
     s_sigma1v1: Selector,
+    spread: SpreadLookupConfig<F>,
     _marker: PhantomData<F>
 }
 
@@ -44,6 +47,7 @@ impl<F: FieldExt> Sigma1V1Config<F> {
         a4: Column<Advice>,
         a5: Column<Advice>,
         a6: Column<Advice>,
+        spread: SpreadLookupConfig<F>,
     ) -> Self {
         let s_sigma1v1 = meta.selector();
 
@@ -127,6 +131,7 @@ impl<F: FieldExt> Sigma1V1Config<F> {
         Self {
             a0, a1, a2, a3, a4, a5, a6,
             s_sigma1v1,
+            spread,
             _marker: PhantomData
         }
 
@@ -135,61 +140,65 @@ impl<F: FieldExt> Sigma1V1Config<F> {
     pub fn assign(
         &self,
         region: &mut Region<F>,
-        r_lo: u32,
-        r_hi: u32,
+        r_lo: Value<u32>,
+        r_hi: Value<u32>,
         s_w_a: AssignedCell<F, F>,
         s_w_d: AssignedCell<F, F>,
         w_b: AssignedCell<F, F>,
-        b: u16,
-        c: u16,
+        b: Value<u16>,
+        c: Value<u16>,
         w_c: AssignedCell<F, F>,
         r_e_0_c: AssignedCell<F, F>,
         r_e_1_c: AssignedCell<F, F>,
         offset: usize
     ) -> Result<(), Error> {
 
-        let r_e_0 = even_bit(r_lo);
-        let r_o_0 = odd_bit(r_lo);
-        let r_e_1 = even_bit(r_hi);
-        let r_o_1 = odd_bit(r_hi);
+        let r_e_0 = r_lo.map(even_bit);
+        let r_o_0 = r_lo.map(odd_bit);
+        let r_e_1 = r_hi.map(even_bit);
+        let r_o_1 = r_hi.map(odd_bit);
 
-        let b_lo = b & 0b11;
-        let b_mi = (b >> 2) & 0b11;
-        let b_hi = b >> 4;
+        let b_lo = b.map(|b| b & 0b11);
+        let b_mi = b.map(|b| (b >> 2) & 0b11);
+        let b_hi = b.map(|b| b >> 4);
 
         self.s_sigma1v1.enable(region, offset)?;
 
-        region.assign_advice(|| "ss1v1 re0 tag", self.a0, offset-1, || Value::known(F::from(create_tag(r_e_0) as u64)))?;
+        self.spread.enable(region, offset-1, r_e_0)?;
+        region.assign_advice(|| "ss1v1 re0 tag", self.a0, offset-1, || r_e_0.map(|r_e_0| F::from(create_tag(r_e_0) as u64)))?;
         r_e_0_c.copy_advice(|| "ss1v1 re0", region, self.a1, offset-1)?;
-        region.assign_advice(|| "ss1v1 s_re0", self.a2, offset-1, || Value::known(F::from(create_interleave_num(r_e_0 as u32) as u64)))?;
+        region.assign_advice(|| "ss1v1 s_re0", self.a2, offset-1, || r_e_0.map(|r_e_0| F::from(create_interleave_num(r_e_0 as u32) as u64)))?;
         
-        region.assign_advice(|| "ss1v1 ro0 tag", self.a0, offset, || Value::known(F::from(create_tag(r_o_0) as u64)))?;
-        region.assign_advice(|| "ss1v1 ro0", self.a1, offset, || Value::known(F::from(r_o_0 as u64)))?;
-        region.assign_advice(|| "ss1v1 s_ro0", self.a2, offset, || Value::known(F::from(create_interleave_num(r_o_0 as u32) as u64)))?;
+        self.spread.enable(region, offset, r_o_0)?;
+        region.assign_advice(|| "ss1v1 ro0 tag", self.a0, offset, || r_o_0.map(|r_o_0| F::from(create_tag(r_o_0) as u64)))?;
+        region.assign_advice(|| "ss1v1 ro0", self.a1, offset, || r_o_0.map(|r_o_0| F::from(r_o_0 as u64)))?;
+        region.assign_advice(|| "ss1v1 s_ro0", self.a2, offset, || r_o_0.map(|r_o_0| F::from(create_interleave_num(r_o_0 as u32) as u64)))?;
 
-        region.assign_advice(|| "ss1v1 re1 tag", self.a0, offset+1, || Value::known(F::from(create_tag(r_e_1) as u64)))?;
+        self.spread.enable(region, offset+1, r_e_1)?;
+        region.assign_advice(|| "ss1v1 re1 tag", self.a0, offset+1, || r_e_1.map(|r_e_1| F::from(create_tag(r_e_1) as u64)))?;
         r_e_1_c.copy_advice(|| "ss1v1 re1", region, self.a1, offset+1)?;
-        region.assign_advice(|| "ss1v1 s_re1", self.a2, offset+1, || Value::known(F::from(create_interleave_num(r_e_1 as u32) as u64)))?;
+        region.assign_advice(|| "ss1v1 s_re1", self.a2, offset+1, || r_e_1.map(|r_e_1| F::from(create_interleave_num(r_e_1 as u32) as u64)))?;
         
-        region.assign_advice(|| "ss1v1 ro1 tag", self.a0, offset+2, || Value::known(F::from(create_tag(r_o_1) as u64)))?;
-        region.assign_advice(|| "ss1v1 ro1", self.a1, offset+2, || Value::known(F::from(r_o_1 as u64)))?;
-        let s_ro1 = region.assign_advice(|| "ss1v1 s_ro1", self.a2, offset+2, || Value::known(F::from(create_interleave_num(r_o_1 as u32) as u64)))?;
+        self.spread.enable(region, offset+2, r_o_1)?;
+        region.assign_advice(|| "ss1v1 ro1 tag", self.a0, offset+2, || r_o_1.map(|r_o_1| F::from(create_tag(r_o_1) as u64)))?;
+        region.assign_advice(|| "ss1v1 ro1", self.a1, offset+2, || r_o_1.map(|r_o_1| F::from(r_o_1 as u64)))?;
+        let s_ro1 = region.assign_advice(|| "ss1v1 s_ro1", self.a2, offset+2, || r_o_1.map(|r_o_1| F::from(create_interleave_num(r_o_1 as u32) as u64)))?;
         s_ro1.copy_advice(|| "ss1v2 s_ro1 copy", region, self.a3, offset)?;
 
-        region.assign_advice(|| "ss1v1 b_lo", self.a3, offset-1, || Value::known(F::from(b_lo as u64)))?;
-        region.assign_advice(|| "ss1v1 s_b_lo", self.a4, offset-1, || Value::known(F::from(create_interleave_num(b_lo as u32) as u64)))?;
+        region.assign_advice(|| "ss1v1 b_lo", self.a3, offset-1, || b_lo.map(|b_lo| F::from(b_lo as u64)))?;
+        region.assign_advice(|| "ss1v1 s_b_lo", self.a4, offset-1, || b_lo.map(|b_lo| F::from(create_interleave_num(b_lo as u32) as u64)))?;
 
-        region.assign_advice(|| "ss1v1 b_mi", self.a5, offset-1, || Value::known(F::from(b_mi as u64)))?;
-        region.assign_advice(|| "ss1v1 s_b_mi", self.a6, offset-1, || Value::known(F::from(create_interleave_num(b_mi as u32) as u64)))?;
+        region.assign_advice(|| "ss1v1 b_mi", self.a5, offset-1, || b_mi.map(|b_mi| F::from(b_mi as u64)))?;
+        region.assign_advice(|| "ss1v1 s_b_mi", self.a6, offset-1, || b_mi.map(|b_mi| F::from(create_interleave_num(b_mi as u32) as u64)))?;
 
-        region.assign_advice(|| "ss1v1 b_hi", self.a5, offset+1, || Value::known(F::from(b_hi as u64)))?;
-        region.assign_advice(|| "ss1v1 s_b_hi", self.a6, offset+1, || Value::known(F::from(create_interleave_num(b_hi as u32) as u64)))?;
+        region.assign_advice(|| "ss1v1 b_hi", self.a5, offset+1, || b_hi.map(|b_hi| F::from(b_hi as u64)))?;
+        region.assign_advice(|| "ss1v1 s_b_hi", self.a6, offset+1, || b_hi.map(|b_hi| F::from(create_interleave_num(b_hi as u32) as u64)))?;
 
         s_w_a.copy_advice(|| "ss1v1 s_a", region, self.a4, offset)?;
         s_w_d.copy_advice(|| "ss1v1 s_d", region, self.a5, offset)?;
         w_b.copy_advice(|| "ss1v1 b", region, self.a6, offset)?;
         w_c.copy_advice(|| "ss1v1 c", region, self.a3, offset+1)?;
-        region.assign_advice(|| "ss1v1 s_c", self.a4, offset+1, || Value::known(F::from(create_interleave_num(c as u32) as u64)))?;
+        region.assign_advice(|| "ss1v1 s_c", self.a4, offset+1, || c.map(|c| F::from(create_interleave_num(c as u32) as u64)))?;
 
 
         Ok(())
@@ -11,6 +11,8 @@ use crate::utils::{
     odd_bit,
 };
 
+use crate::spread_table::SpreadLookupConfig;
+
 use halo2_proofs::{
     arithmetic::FieldExt,
     circuit::{Region, Value, AssignedCell},
@@ -30,6 +32,7 @@ pub struct Sigma1V2Config<F: FieldExt> {
     a6: Column<Advice>,
     a7: Column<Advice>,
     s_sigma1v2: Selector,
+    spread: SpreadLookupConfig<F>,
     _marker: PhantomData<F>
 }
 
@@ -46,6 +49,7 @@ impl<F: FieldExt> Sigma1V2Config<F> {
         a5: Column<Advice>,
         a6: Column<Advice>,
         a7: Column<Advice>,
+        spread: SpreadLookupConfig<F>,
     ) -> Self {
         let s_sigma1v2 = meta.selector();
 
@@ -143,6 +147,7 @@ impl<F: FieldExt> Sigma1V2Config<F> {
         Self {
             a0, a1, a2, a3, a4, a5, a6, a7,
             s_sigma1v2,
+            spread,
             _marker: PhantomData
         }
 
@@ -151,67 +156,71 @@ impl<F: FieldExt> Sigma1V2Config<F> {
     pub fn assign(
         &self,
         region: &mut Region<F>,
-        r_lo: u32,
-        r_hi: u32,
+        r_lo: Value<u32>,
+        r_hi: Value<u32>,
         s_w_d: AssignedCell<F, F>,
         s_w_g: AssignedCell<F, F>,
         w_b: AssignedCell<F, F>,
-        b: u16,
+        b: Value<u16>,
         w_a: AssignedCell<F, F>,
-        a: u16,
+        a: Value<u16>,
         w_c: AssignedCell<F, F>,
-        c: u16,    
-        w_e: u16,
-        w_f: u16,
+        c: Value<u16>,
+        w_e: Value<u16>,
+        w_f: Value<u16>,
         r_e_0_c: AssignedCell<F, F>,
         r_e_1_c: AssignedCell<F, F>,
         offset: usize
     ) -> Result<(), Error> {
 
-        let r_e_0 = even_bit(r_lo);
-        let r_o_0 = odd_bit(r_lo);
-        let r_e_1 = even_bit(r_hi);
-        let r_o_1 = odd_bit(r_hi);
+        let r_e_0 = r_lo.map(even_bit);
+        let r_o_0 = r_lo.map(odd_bit);
+        let r_e_1 = r_hi.map(even_bit);
+        let r_o_1 = r_hi.map(odd_bit);
 
-        let b_lo = b & 0b11;
-        let b_hi = b >> 2;
-        let s_b_lo = create_interleave_num(b_lo as u32) as u64;
-        let s_b_hi = create_interleave_num(b_hi as u32) as u64;
-        let s_a = create_interleave_num(a as u32) as u64;
+        let b_lo = b.map(|b| b & 0b11);
+        let b_hi = b.map(|b| b >> 2);
+        let s_b_lo = b_lo.map(|b_lo| create_interleave_num(b_lo as u32) as u64);
+        let s_b_hi = b_hi.map(|b_hi| create_interleave_num(b_hi as u32) as u64);
+        let s_a = a.map(|a| create_interleave_num(a as u32) as u64);
         self.s_sigma1v2.enable(region, offset)?;
 
-        region.assign_advice(|| "ss1v2 re0 tag", self.a0, offset-1, || Value::known(F::from(create_tag(r_e_0) as u64)))?;
+        self.spread.enable(region, offset-1, r_e_0)?;
+        region.assign_advice(|| "ss1v2 re0 tag", self.a0, offset-1, || r_e_0.map(|r_e_0| F::from(create_tag(r_e_0) as u64)))?;
         r_e_0_c.copy_advice(|| "ss1v2 re0", region, self.a1, offset-1)?;
-        region.assign_advice(|| "ss1v2 s_re0", self.a2, offset-1, || Value::known(F::from(create_interleave_num(r_e_0 as u32) as u64)))?;
+        region.assign_advice(|| "ss1v2 s_re0", self.a2, offset-1, || r_e_0.map(|r_e_0| F::from(create_interleave_num(r_e_0 as u32) as u64)))?;
 
-        region.assign_advice(|| "ss1v2 ro0 tag", self.a0, offset, || Value::known(F::from(create_tag(r_o_0) as u64)))?;
-        region.assign_advice(|| "ss1v2 ro0", self.a1, offset, || Value::known(F::from(r_o_0 as u64)))?;
-        region.assign_advice(|| "ss1v2 s_ro0", self.a2, offset, || Value::known(F::from(create_interleave_num(r_o_0 as u32) as u64)))?;
+        self.spread.enable(region, offset, r_o_0)?;
+        region.assign_advice(|| "ss1v2 ro0 tag", self.a0, offset, || r_o_0.map(|r_o_0| F::from(create_tag(r_o_0) as u64)))?;
+        region.assign_advice(|| "ss1v2 ro0", self.a1, offset, || r_o_0.map(|r_o_0| F::from(r_o_0 as u64)))?;
+        region.assign_advice(|| "ss1v2 s_ro0", self.a2, offset, || r_o_0.map(|r_o_0| F::from(create_interleave_num(r_o_0 as u32) as u64)))?;
 
-        region.assign_advice(|| "ss1v2 re1 tag", self.a0, offset+1, || Value::known(F::from(create_tag(r_e_1) as u64)))?;
+        self.spread.enable(region, offset+1, r_e_1)?;
+        region.assign_advice(|| "ss1v2 re1 tag", self.a0, offset+1, || r_e_1.map(|r_e_1| F::from(create_tag(r_e_1) as u64)))?;
         r_e_1_c.copy_advice(|| "ss1v2 re1", region, self.a1, offset+1)?;
-        region.assign_advice(|| "ss1v2 s_re1", self.a2, offset+1, || Value::known(F::from(create_interleave_num(r_e_1 as u32) as u64)))?;
+        region.assign_advice(|| "ss1v2 s_re1", self.a2, offset+1, || r_e_1.map(|r_e_1| F::from(create_interleave_num(r_e_1 as u32) as u64)))?;
         
-        region.assign_advice(|| "ss1v2 ro1 tag", self.a0, offset+2, || Value::known(F::from(create_tag(r_o_1) as u64)))?;
-        region.assign_advice(|| "ss1v2 ro1", self.a1, offset+2, || Value::known(F::from(r_o_1 as u64)))?;
-        let s_ro1 = region.assign_advice(|| "ss1v2 s_ro1", self.a2, offset+2, || Value::known(F::from(create_interleave_num(r_o_1 as u32) as u64)))?;
+        self.spread.enable(region, offset+2, r_o_1)?;
+        region.assign_advice(|| "ss1v2 ro1 tag", self.a0, offset+2, || r_o_1.map(|r_o_1| F::from(create_tag(r_o_1) as u64)))?;
+        region.assign_advice(|| "ss1v2 ro1", self.a1, offset+2, || r_o_1.map(|r_o_1| F::from(r_o_1 as u64)))?;
+        let s_ro1 = region.assign_advice(|| "ss1v2 s_ro1", self.a2, offset+2, || r_o_1.map(|r_o_1| F::from(create_interleave_num(r_o_1 as u32) as u64)))?;
         s_ro1.copy_advice(|| "ss0v2 s_ro1 copy", region, self.a3, offset)?;
         
-        region.assign_advice(|| "ss1v2 b_lo", self.a3, offset-1, || Value::known(F::from(b_lo as u64)))?;
-        region.assign_advice(|| "ss1v2 s_b_lo", self.a4, offset-1, || Value::known(F::from(s_b_lo)))?;
+        region.assign_advice(|| "ss1v2 b_lo", self.a3, offset-1, || b_lo.map(|b_lo| F::from(b_lo as u64)))?;
+        region.assign_advice(|| "ss1v2 s_b_lo", self.a4, offset-1, || s_b_lo.map(F::from))?;
 
-        region.assign_advice(|| "ss1v2 b_hi", self.a5, offset-1, || Value::known(F::from(b_hi as u64)))?;
-        region.assign_advice(|| "ss1v2 s_b_hi", self.a6, offset-1, || Value::known(F::from(s_b_hi)))?;
+        region.assign_advice(|| "ss1v2 b_hi", self.a5, offset-1, || b_hi.map(|b_hi| F::from(b_hi as u64)))?;
+        region.assign_advice(|| "ss1v2 s_b_hi", self.a6, offset-1, || s_b_hi.map(F::from))?;
 
         s_w_d.copy_advice(|| "ss1v2 s_d", region, self.a4, offset)?;
         s_w_g.copy_advice(|| "ss1v2 s_g", region, self.a5, offset)?;
         w_b.copy_advice(|| "ss1v2 b", region, self.a6, offset)?;
         w_a.copy_advice(|| "ss1v2 a", region, self.a3, offset+1)?;
         w_c.copy_advice(|| "ss1v2 c", region, self.a5, offset+1)?;
-        region.assign_advice(|| "ss1v2 s_c", self.a6, offset+1, || Value::known(F::from(create_interleave_num(c as u32) as u64)))?;
-        region.assign_advice(|| "ss1v2 s_a", self.a4, offset+1, || Value::known(F::from(s_a)))?;
-        region.assign_advice(|| "ss1v2 e", self.a7, offset, || Value::known(F::from(w_e as u64)))?;
-        region.assign_advice(|| "ss1v2 f", self.a7, offset+1, || Value::known(F::from(w_f as u64)))?;
+        region.assign_advice(|| "ss1v2 s_c", self.a6, offset+1, || c.map(|c| F::from(create_interleave_num(c as u32) as u64)))?;
+        region.assign_advice(|| "ss1v2 s_a", self.a4, offset+1, || s_a.map(F::from))?;
+        region.assign_advice(|| "ss1v2 e", self.a7, offset, || w_e.map(|w_e| F::from(w_e as u64)))?;
+        region.assign_advice(|| "ss1v2 f", self.a7, offset+1, || w_f.map(|w_f| F::from(w_f as u64)))?;
 
         Ok(())
     }
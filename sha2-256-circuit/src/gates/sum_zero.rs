@@ -7,6 +7,10 @@ use crate::utils::{
     create_tag
 };
 
+use crate::word::{AssignedWord, Word32};
+
+use crate::spread_table::SpreadLookupConfig;
+
 use halo2_proofs::{
     arithmetic::FieldExt,
     circuit::{Region, Value, AssignedCell},
@@ -23,6 +27,7 @@ pub struct SumZeroConfig<F: FieldExt> {
     a3: Column<Advice>,
     a4: Column<Advice>,
     a5: Column<Advice>,
+    spread: SpreadLookupConfig<F>,
     _marker: PhantomData<F>
 }
 
@@ -36,6 +41,7 @@ impl<F: FieldExt> SumZeroConfig<F> {
         a3: Column<Advice>,
         a4: Column<Advice>,
         a5: Column<Advice>,
+        spread: SpreadLookupConfig<F>,
     ) -> Self {
         let s_sum_zero = meta.selector();
         meta.create_gate(
@@ -76,6 +82,7 @@ impl<F: FieldExt> SumZeroConfig<F> {
         Self {
             s_sum_zero,
             a0, a1, a2, a3, a4, a5,
+            spread,
             _marker: PhantomData
         }
     }
@@ -83,8 +90,8 @@ impl<F: FieldExt> SumZeroConfig<F> {
     pub fn assign(
         &self,
         region: &mut Region<F>,
-        r_lo: u32,
-        r_hi: u32,
+        r_lo: Value<u32>,
+        r_hi: Value<u32>,
         s_a: AssignedCell<F, F>,
         s_b: AssignedCell<F, F>,
         s_c_lo: AssignedCell<F, F>,
@@ -92,40 +99,44 @@ impl<F: FieldExt> SumZeroConfig<F> {
         s_c_hi: AssignedCell<F, F>,
         s_d: AssignedCell<F, F>,
         offset: usize,
-    ) -> Result<Vec<AssignedCell<F, F>>, Error>{
+    ) -> Result<AssignedWord<F>, Error>{
 
         self.s_sum_zero.enable(region, offset)?;
-        let r_e_0 = even_bit(r_lo);
-        let r_o_0 = odd_bit(r_lo);
-        let r_e_1 = even_bit(r_hi);
-        let r_o_1 = odd_bit(r_hi);
-
-        region.assign_advice(|| "s_sum_zero r_e_0 tag", self.a0, offset-1, || Value::known(F::from(create_tag(r_e_0) as u64)))?;
-        let r_e_0_c = region.assign_advice(|| "s_sum_zero r_e_0", self.a1, offset-1, || Value::known(F::from(r_e_0 as u64)))?;
-        region.assign_advice(|| "s_sum_zero s_r_e_0", self.a2, offset-1, || Value::known(F::from(create_interleave_num(r_e_0 as u32) as u64)))?;
+        let r_e_0 = r_lo.map(even_bit);
+        let r_o_0 = r_lo.map(odd_bit);
+        let r_e_1 = r_hi.map(even_bit);
+        let r_o_1 = r_hi.map(odd_bit);
+
+        self.spread.enable(region, offset-1, r_e_0)?;
+        region.assign_advice(|| "s_sum_zero r_e_0 tag", self.a0, offset-1, || r_e_0.map(|r_e_0| F::from(create_tag(r_e_0) as u64)))?;
+        let r_e_0_c = region.assign_advice(|| "s_sum_zero r_e_0", self.a1, offset-1, || r_e_0.map(|r_e_0| F::from(r_e_0 as u64)))?;
+        region.assign_advice(|| "s_sum_zero s_r_e_0", self.a2, offset-1, || r_e_0.map(|r_e_0| F::from(create_interleave_num(r_e_0 as u32) as u64)))?;
         s_c_lo.copy_advice(|| "s_sum_zero s_c_lo", region, self.a3, offset-1)?;
         s_c_mi.copy_advice(|| "s_sum_zero s_c_mi", region, self.a4, offset-1)?;
 
 
-        region.assign_advice(|| "s_sum_zero r_o_0 tag", self.a0, offset, || Value::known(F::from(create_tag(r_o_0) as u64)))?;
-        region.assign_advice(|| "s_sum_zero r_o_0", self.a1, offset, || Value::known(F::from(r_o_0 as u64)))?;
-        region.assign_advice(|| "s_sum_zero s_r_o_0", self.a2, offset, || Value::known(F::from(create_interleave_num(r_o_0 as u32) as u64)))?;
+        self.spread.enable(region, offset, r_o_0)?;
+        region.assign_advice(|| "s_sum_zero r_o_0 tag", self.a0, offset, || r_o_0.map(|r_o_0| F::from(create_tag(r_o_0) as u64)))?;
+        region.assign_advice(|| "s_sum_zero r_o_0", self.a1, offset, || r_o_0.map(|r_o_0| F::from(r_o_0 as u64)))?;
+        region.assign_advice(|| "s_sum_zero s_r_o_0", self.a2, offset, || r_o_0.map(|r_o_0| F::from(create_interleave_num(r_o_0 as u32) as u64)))?;
         s_d.copy_advice(|| "s_sum_zero s_d", region, self.a4, offset)?;
         s_b.copy_advice(|| "s_sum_zero s_b", region, self.a5, offset)?;
 
-        region.assign_advice(|| "s_sum_zero r_e_1 tag", self.a0, offset+1, || Value::known(F::from(create_tag(r_e_1) as u64)))?;
-        let r_e_1_c = region.assign_advice(|| "s_sum_zero r_e_1", self.a1, offset+1, || Value::known(F::from(r_e_1 as u64)))?;
-        region.assign_advice(|| "s_sum_zero s_r_e_1", self.a2, offset+1, || Value::known(F::from(create_interleave_num(r_e_1 as u32) as u64)))?;
+        self.spread.enable(region, offset+1, r_e_1)?;
+        region.assign_advice(|| "s_sum_zero r_e_1 tag", self.a0, offset+1, || r_e_1.map(|r_e_1| F::from(create_tag(r_e_1) as u64)))?;
+        let r_e_1_c = region.assign_advice(|| "s_sum_zero r_e_1", self.a1, offset+1, || r_e_1.map(|r_e_1| F::from(r_e_1 as u64)))?;
+        region.assign_advice(|| "s_sum_zero s_r_e_1", self.a2, offset+1, || r_e_1.map(|r_e_1| F::from(create_interleave_num(r_e_1 as u32) as u64)))?;
         s_a.copy_advice(|| "s_sum_zero s_a", region, self.a3, offset+1)?;
         s_c_hi.copy_advice(|| "s_sum_zero s_c_hi", region, self.a4, offset+1)?;
 
-        region.assign_advice(|| "s_sum_zero r_o_1 tag", self.a0, offset+2, || Value::known(F::from(create_tag(r_o_1) as u64)))?;
-        region.assign_advice(|| "s_sum_zero r_o_1", self.a1, offset+2, || Value::known(F::from(r_o_1 as u64)))?;
-        let s_o_1 = region.assign_advice(|| "s_sum_zero s_r_o_1", self.a2, offset+2, || Value::known(F::from(create_interleave_num(r_o_1 as u32) as u64)))?;
+        self.spread.enable(region, offset+2, r_o_1)?;
+        region.assign_advice(|| "s_sum_zero r_o_1 tag", self.a0, offset+2, || r_o_1.map(|r_o_1| F::from(create_tag(r_o_1) as u64)))?;
+        region.assign_advice(|| "s_sum_zero r_o_1", self.a1, offset+2, || r_o_1.map(|r_o_1| F::from(r_o_1 as u64)))?;
+        let s_o_1 = region.assign_advice(|| "s_sum_zero s_r_o_1", self.a2, offset+2, || r_o_1.map(|r_o_1| F::from(create_interleave_num(r_o_1 as u32) as u64)))?;
         s_o_1.copy_advice(|| "s_sum_zero s_r_o_0 copy", region, self.a3, offset)?;
 
-        let res = vec![r_e_0_c, r_e_1_c];
-        Ok(res)
+        let value = r_e_0.zip(r_e_1).map(|(lo, hi)| Word32::new(lo, hi));
+        Ok(AssignedWord::new(r_e_0_c, r_e_1_c, value))
 
 
     }
@@ -8,6 +8,7 @@ use halo2_proofs::{
 };
 
 use crate::utils::{create_range_2_check, reduce4, sigma0, sigma1};
+use crate::word::Word32;
 
 #[derive(Debug, Clone)]
 pub struct WNewConfig<F: FieldExt> {
@@ -76,37 +77,32 @@ impl<F: FieldExt> WNewConfig<F> {
     pub fn assign(
         &self,
         region: &mut Region<F>,
-        w_i_16_lo: u16,
-        w_i_16_hi: u16,
-        w_i_15_lo: u16,
-        w_i_15_hi: u16,
-        w_i_7_lo: u16,
-        w_i_7_hi: u16,
-        w_i_2_lo: u16,
-        w_i_2_hi: u16,
+        w_i_16: Value<Word32>,
+        w_i_15: Value<Word32>,
+        w_i_7: Value<Word32>,
+        w_i_2: Value<Word32>,
         offset: usize,
-    ) -> Result<(Vec<AssignedCell<F, F>>, u32), Error>{
-        let (s0_lo, s0_hi) = sigma0(w_i_15_lo, w_i_15_hi);
-        let (s1_lo, s1_hi) = sigma1(w_i_2_lo, w_i_2_hi);
-        let (new_w, carry) = reduce4(
-            s0_lo as u32 + (s0_hi as u32) * (1 << 16), 
-            s1_lo as u32 + (s1_hi as u32) * (1 << 16), 
-            w_i_16_lo as u32 + (w_i_16_hi as u32) * (1 << 16), 
-            w_i_7_lo as u32 + (w_i_7_hi as u32) * (1 << 16)
-        );
+    ) -> Result<(Vec<AssignedCell<F, F>>, Value<Word32>), Error>{
+        let (s0_lo, s0_hi) = w_i_15.map(|w| sigma0(w.lo, w.hi)).unzip();
+        let (s1_lo, s1_hi) = w_i_2.map(|w| sigma1(w.lo, w.hi)).unzip();
+        let s0 = s0_lo.zip(s0_hi).map(|(lo, hi)| lo as u32 + (hi as u32) * (1 << 16));
+        let s1 = s1_lo.zip(s1_hi).map(|(lo, hi)| lo as u32 + (hi as u32) * (1 << 16));
+        let (new_w, carry) = s0.zip(s1).zip(w_i_16).zip(w_i_7)
+            .map(|(((s0, s1), w_i_16), w_i_7)| reduce4(s0, s1, w_i_16.value(), w_i_7.value()))
+            .unzip();
         self.s_w_new.enable(region, offset)?;
-        let w_n = region.assign_advice(|| "New W_(i)", self.a5, offset, || Value::known(F::from(new_w as u64)))?;
-        let s0_lo = region.assign_advice(|| "Sigma 0 Lo", self.a6, offset-1, || Value::known(F::from(s0_lo as u64)))?;
-        let s0_hi = region.assign_advice(|| "Sigma 0 Hi", self.a6, offset, || Value::known(F::from(s0_hi as u64)))?;
-        let s1_lo = region.assign_advice(|| "Sigma 1 Lo", self.a7, offset-1, || Value::known(F::from(s1_lo as u64)))?;
-        let s1_hi = region.assign_advice(|| "Sigma 1 Hi", self.a7, offset, || Value::known(F::from(s1_hi as u64)))?;
-        region.assign_advice(|| "W_(i-7) Lo", self.a8, offset-1, || Value::known(F::from(w_i_7_lo as u64)))?;
-        region.assign_advice(|| "W_(i-7) Hi", self.a8, offset, || Value::known(F::from(w_i_7_hi as u64)))?;
-        region.assign_advice(|| "Carry", self.a9, offset, || Value::known(F::from(carry)))?;
+        let w_n = region.assign_advice(|| "New W_(i)", self.a5, offset, || new_w.map(|new_w| F::from(new_w as u64)))?;
+        let s0_lo = region.assign_advice(|| "Sigma 0 Lo", self.a6, offset-1, || s0_lo.map(|s0_lo| F::from(s0_lo as u64)))?;
+        let s0_hi = region.assign_advice(|| "Sigma 0 Hi", self.a6, offset, || s0_hi.map(|s0_hi| F::from(s0_hi as u64)))?;
+        let s1_lo = region.assign_advice(|| "Sigma 1 Lo", self.a7, offset-1, || s1_lo.map(|s1_lo| F::from(s1_lo as u64)))?;
+        let s1_hi = region.assign_advice(|| "Sigma 1 Hi", self.a7, offset, || s1_hi.map(|s1_hi| F::from(s1_hi as u64)))?;
+        region.assign_advice(|| "W_(i-7) Lo", self.a8, offset-1, || w_i_7.map(|w_i_7| F::from(w_i_7.lo as u64)))?;
+        region.assign_advice(|| "W_(i-7) Hi", self.a8, offset, || w_i_7.map(|w_i_7| F::from(w_i_7.hi as u64)))?;
+        region.assign_advice(|| "Carry", self.a9, offset, || carry.map(F::from))?;
 
         let res = vec![w_n, s0_lo, s0_hi, s1_lo, s1_hi];
 
-        Ok((res, new_w))
+        Ok((res, new_w.map(Word32::from)))
     }
 
 }
\ No newline at end of file
@@ -0,0 +1,237 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::Error,
+};
+
+use crate::{Sha2Config, Sha256CompressionGadget};
+use crate::gates::pad_xor::{HmacPad, PadXorConfig};
+use crate::regions::table::TABLE_ROWS_PER_BLOCK;
+use crate::utils::padding_words;
+use crate::word::AssignedWord;
+
+/// HMAC over the hash of the mode of a `Sha2Config`, HMAC-SHA256 by default:
+/// `H((K ^ opad) || H((K ^ ipad) || m))`.
+///
+/// The chip runs on the columns, gates and lookups of the config, whose tables
+/// must be loaded with `Sha2Config::load_tables`, and hashes through
+/// `Sha256CompressionGadget`. The key block is XORed with the pads limb by
+/// limb through the spread table, and the inner digest is fed to the outer
+/// hash as words, with the padding of the outer message constrained to
+/// constants.
+#[derive(Clone, Debug)]
+pub struct HmacSha256Chip<F: FieldExt> {
+    config: Sha2Config<F>,
+}
+
+impl<F: FieldExt> HmacSha256Chip<F> {
+    pub fn construct(config: Sha2Config<F>) -> Self {
+        Self { config }
+    }
+
+    /// Returns the MAC of a message under a key, both assigned as bytes by
+    /// another chip, as the `digest_words` words of the digest of the mode.
+    ///
+    /// A key longer than a block is hashed first, as HMAC specifies. The
+    /// lengths of the key and of the message are fixed by the number of cells.
+    pub fn hmac(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        key: &[AssignedCell<F, F>],
+        message: &[AssignedCell<F, F>],
+    ) -> Result<Vec<AssignedWord<F>>, Error> {
+        let gadget = Sha256CompressionGadget::construct(self.config.clone());
+        let digest_words = self.config.mode.digest_words();
+        let key = self.key_block(layouter, &gadget, key)?;
+
+        let inner_key = self.xor_pad(layouter, &key, HmacPad::Inner)?;
+        let iv = gadget.iv(layouter)?;
+        let inner = gadget.compress(layouter, iv, inner_key)?;
+        let inner = gadget.hash_bytes(layouter, inner, message, TABLE_ROWS_PER_BLOCK)?;
+
+        let outer_key = self.xor_pad(layouter, &key, HmacPad::Outer)?;
+        let iv = gadget.iv(layouter)?;
+        let outer = gadget.compress(layouter, iv, outer_key)?;
+
+        // The outer message is the padded key block followed by the inner
+        // digest, so only the digest and its padding are left to compress.
+        let outer_len = TABLE_ROWS_PER_BLOCK + 4 * digest_words;
+        let mut block = inner[..digest_words].to_vec();
+        block.extend(gadget.constant_words(layouter, &padding_words(outer_len))?);
+        let block = block.try_into().map_err(|_| Error::Synthesis)?;
+        let outer = gadget.compress(layouter, outer, block)?;
+
+        Ok(outer[..digest_words].to_vec())
+    }
+
+    /// Assigns the key zero-padded to a block as its 16 words, hashing it
+    /// first when it is longer than a block.
+    fn key_block(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        gadget: &Sha256CompressionGadget<F>,
+        key: &[AssignedCell<F, F>],
+    ) -> Result<[AssignedWord<F>; 16], Error> {
+        if key.len() > TABLE_ROWS_PER_BLOCK {
+            let digest_words = self.config.mode.digest_words();
+            let iv = gadget.iv(layouter)?;
+            let hashed = gadget.hash_bytes(layouter, iv, key, 0)?;
+            let mut words = hashed[..digest_words].to_vec();
+            words.extend(gadget.constant_words(layouter, &vec![0; 16 - digest_words])?);
+            return words.try_into().map_err(|_| Error::Synthesis);
+        }
+
+        let words = layouter.assign_region(
+            || "HMAC key block",
+            |mut region| {
+                let block: Vec<Value<u8>> = (0..TABLE_ROWS_PER_BLOCK)
+                    .map(|j| match key.get(j) {
+                        Some(byte) => byte.value().map(|byte| byte.get_lower_32() as u8),
+                        None => Value::known(0),
+                    })
+                    .collect();
+                let (byte_c, words) = self.config.table_chip.assign_bytes(&mut region, &block, 0)?;
+                for (j, byte_c) in byte_c.iter().enumerate() {
+                    match key.get(j) {
+                        Some(byte) => region.constrain_equal(byte.cell(), byte_c.cell())?,
+                        None => region.constrain_constant(byte_c.cell(), F::zero())?,
+                    }
+                }
+                Ok(words)
+            }
+        )?;
+        words.try_into().map_err(|_| Error::Synthesis)
+    }
+
+    fn xor_pad(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        key: &[AssignedWord<F>; 16],
+        pad: HmacPad,
+    ) -> Result<[AssignedWord<F>; 16], Error> {
+        let words = layouter.assign_region(
+            || format!("HMAC {:?} pad", pad),
+            |mut region| {
+                let mut words = vec![];
+                for (i, word) in key.iter().enumerate() {
+                    words.push(self.config.pad_xor.assign(&mut region, word, pad, i * PadXorConfig::<F>::rows())?);
+                }
+                Ok(words)
+            }
+        )?;
+        words.try_into().map_err(|_| Error::Synthesis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::marker::PhantomData;
+    use crate::Sha2Table;
+    use ethers_core::utils::hex;
+    use halo2_proofs::circuit::SimpleFloorPlanner;
+    use halo2_proofs::plonk::{Circuit, ConstraintSystem};
+    use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr as F};
+
+    #[derive(Default, Clone)]
+    struct HmacCircuit<F: FieldExt> {
+        key: Vec<u8>,
+        message: Vec<u8>,
+        _marker: PhantomData<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for HmacCircuit<F> {
+        type Config = Sha2Config<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = Sha2Table::construct(meta);
+            Sha2Config::configure(meta, table, 1)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            config.load_tables(&mut layouter)?;
+            let (key, message) = layouter.assign_region(
+                || "key and message bytes",
+                |mut region| {
+                    let mut key = vec![];
+                    for (i, byte) in self.key.iter().enumerate() {
+                        key.push(region.assign_advice(
+                            || "key byte", config.cols[3], i, || Value::known(F::from(*byte as u64))
+                        )?);
+                    }
+                    let mut message = vec![];
+                    for (i, byte) in self.message.iter().enumerate() {
+                        message.push(region.assign_advice(
+                            || "message byte", config.cols[4], i, || Value::known(F::from(*byte as u64))
+                        )?);
+                    }
+                    Ok((key, message))
+                }
+            )?;
+
+            let chip = HmacSha256Chip::construct(config.clone());
+            let mac = chip.hmac(&mut layouter, &key, &message)?;
+            let mac = layouter.assign_region(
+                || "mac words",
+                |mut region| config.compression_chip.assign_words(&mut region, &mac, 0)
+            )?;
+            for (i, word) in mac.iter().enumerate() {
+                layouter.constrain_instance(word.cell(), config.instance, i)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn mac_words(mac: &str) -> Vec<F> {
+        hex::decode(mac)
+            .expect("MAC is hex")
+            .chunks(4)
+            .map(|word| F::from(u32::from_be_bytes(word.try_into().expect("SHA-256 word is 4-bytes")) as u64))
+            .collect()
+    }
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 test cases 2 and 6, and a key of exactly one block.
+        let vectors = [
+            (
+                b"Jefe".to_vec(),
+                b"what do ya want for nothing?".to_vec(),
+                "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            ),
+            (
+                vec![0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First".to_vec(),
+                "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+            ),
+            (
+                vec![b'k'; 64],
+                vec![b'm'; 100],
+                "cf90f2bd3d834da81ed00241edca4400cd0286aca0dc917f775169e8f09b46cb",
+            ),
+        ];
+        for (key, message, mac) in vectors {
+            let circuit = HmacCircuit::<F> { key: key.clone(), message, _marker: PhantomData };
+            let prover = MockProver::run(17, &circuit, vec![mac_words(mac)]).unwrap();
+            assert_eq!(prover.verify(), Ok(()), "key of {} bytes", key.len());
+        }
+    }
+
+    #[test]
+    fn test_hmac_sha256_wrong_key() {
+        let circuit = HmacCircuit::<F> {
+            key: b"Jeff".to_vec(),
+            message: b"what do ya want for nothing?".to_vec(),
+            _marker: PhantomData,
+        };
+        let mac = mac_words("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+        let prover = MockProver::run(17, &circuit, vec![mac]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
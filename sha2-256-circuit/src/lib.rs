@@ -23,19 +23,21 @@
 //! named columns in the circuit's layout, and the `configure` method is meant
 //! to define the relationship between those columns over its neighbouring rows.
 //!
-//! For instance, for the `id` field to be an incremental field, one may specify
-//! the following relationship:
+//! For instance, for the `id` field to be held along the rows of a block, one
+//! may specify the following relationship, enabled on every row of a block but
+//! its first:
 //! ```
 //! # impl<F: FieldExt> Sha2Config<F> {
 //!     pub fn configure(meta: &mut ConstraintSystem<F>, table: Sha2Table) -> Self {
-//!         meta.create_gate("validity check over all rows", |meta| {
+//!         let s_link = meta.selector();
+//!         meta.create_gate("id is held within a block", |meta| {
 //!             let mut cb = BaseConstraintBuilder::default();
 //!             cb.require_equal(
-//!                 "id field is incremental, i.e. id::cur + 1 == id::next",
-//!                 meta.query_advice(table.id, Rotation::cur()) + 1.expr(),
-//!                 meta.query_advice(table.id, Rotation::next()),
+//!                 "id field is held, i.e. id::prev == id::cur",
+//!                 meta.query_advice(table.id, Rotation::prev()),
+//!                 meta.query_advice(table.id, Rotation::cur()),
 //!             );
-//!             cb.gate(1.expr()) // enable this gate over all rows.
+//!             cb.gate(meta.query_selector(s_link))
 //!         });
 //!
 //!         Self {
@@ -47,7 +49,87 @@
 //! ```
 //!
 //! We also describe how the EVM circuit would lookup to the SHA2 circuit via lookup
-//! arguments [`here`]. Currently, the table is a dummy column named `id`.
+//! arguments [`here`].
+//!
+//! `Sha2Table` carries one row per byte of every padded message block. The rows
+//! of a call share its `id`, `tag` and `input_len`. The `id` is constrained to
+//! number the calls `1, 2, ...` in order, incremented on the first row of every
+//! call, so unused all-zero rows never match a call and the rows of two calls
+//! never share an `id`. `index` is the position of the byte within
+//! the padded message, and the last row of the final block has `is_final` set
+//! together with the digest split into two 128-bit halves `digest_hi` and
+//! `digest_lo`. The digest columns are copy-constrained to the output of the
+//! compression function, so a consumer circuit can look up
+//! `(id, is_final, input_len, digest_hi, digest_lo)` with `meta.lookup_any`.
+//! The digest columns are zero on the last row of any other block.
+//!
+//! For consumers comparing byte strings by random linear combination, as the
+//! EVM circuit does, `input_rlc` and `output_rlc` hold the combinations of the
+//! message and digest bytes with the challenge `Sha2Table::challenge`, so the
+//! final row of a call can be looked up as `(input_rlc, input_len, output_rlc)`.
+//! They are second phase columns, assigned once the challenge is known. The
+//! `gas` column holds the cost of the SHA256 precompile for the message,
+//! `60 + 12 * ceil(input_len / 32)`, counted row by row from the words that
+//! hold message bytes, so it can be checked through the same lookup.
+//!
+//! The padding of a message is checked on these rows: `input` holds the raw
+//! message bytes up to `index == input_len`, then a `0x80` byte and zeros, and
+//! the final block ends with the 64-bit big-endian bit length of the message.
+//! The bytes of every block are range checked to 8 bits and recomposed into the
+//! 16-bit limbs of the message words fed to the message schedule.
+//!
+//! The circuit has a fixed capacity of `max_blocks` blocks chosen at configure
+//! time, and every block slot is laid out whether it is used or not, so the
+//! layout (and thus the verifying key) does not depend on the inputs. Slots
+//! past the last input hold dummy blocks: each is an unterminated block of
+//! zero bytes starting from the IV, with `is_final` unset, so it has no
+//! padding and never outputs a digest. Each dummy block is numbered as a call
+//! of its own after the inputs. Each slot starts either from the IV or from
+//! the output of the previous slot, depending on whether it is the first block
+//! of a message.
+//!
+//! The digests can also be exposed as public inputs through the `instance`
+//! column of `Sha2Config`, where block slot `i` occupies rows `8 * i..8 * i + 8`
+//! holding the eight 32-bit words `H0..H7` of its output when it is the final
+//! block of a message, and zeros otherwise.
+//!
+//! A config set up with `Sha2Mode::Sha224` hashes SHA-224 instead: the IV is
+//! swapped for the SHA-224 one, `digest_lo` only packs the 96 bits of `H4..H6`,
+//! and block slot `i` occupies the instance rows `7 * i..7 * i + 7`.
+//!
+//! In `Sha2Mode::Sha256d`, every block slot also hashes its output state a
+//! second time, as a 32-byte message, within its region. A call whose `tag` is
+//! set in `Sha2Table` outputs this second digest, `sha256(sha256(x))`, in place
+//! of its SHA-256 digest, so a consumer circuit adds `tag` to its lookup to
+//! pick either hash. The `tag` of a call is 0 in the other modes.
+//!
+//! Circuits composing SHA-256 with their own logic can instead call the
+//! compression function on words they have assigned themselves through
+//! `Sha256CompressionGadget`, which reuses the chips of a `Sha2Config`. A
+//! message whose bytes are already assigned by another chip is hashed with
+//! `Sha2Chip::hash_assigned`, which copies the byte cells into the circuit;
+//! the length of such a message is fixed by the number of cells.
+//! A long message can also be hashed in steps through a `Sha256Midstate`, the
+//! state after some number of blocks: `Sha2Chip::update` absorbs whole blocks
+//! and `Sha2Chip::finalize` pads and hashes the rest, with the length in the
+//! padding constrained to the block counter of the midstate. A midstate can be
+//! witnessed with `assign_midstate` and tied to public inputs with
+//! `expose_midstate`, to precompute a fixed prefix or to split a message over
+//! several proofs.
+//! `HmacSha256Chip` computes HMAC on top of the gadget in the same way, and
+//! `MerklePathGadget` verifies Merkle inclusion proofs over SHA-256 nodes.
+//!
+//! SHA-512, SHA-384 and SHA-512/256 are hashed by the separate `Sha512Config`,
+//! which assigns 64-bit words as four 16-bit limbs on the same spread table.
+//! Its calls are looked up through `Sha512Table`, laid out and constrained
+//! like `Sha2Table` over 128-byte blocks.
+//!
+//! The gates look words up in the spread table as 16-bit limbs, in a table of
+//! `2^16` rows by default, so a circuit needs `k >= 17`. A config set up with
+//! `Sha2Config::configure_with_spread` and `SpreadTableBits::Eight` instead
+//! looks every limb up as its two bytes in a table of `2^8` rows, with a few
+//! more columns, so a circuit of a few blocks fits in a much smaller `k`.
+//! `Sha2Layout::min_k` accounts for the table of the config.
 //!
 //! The following tasks are expected to be done:
 //! - Define the layout of the SHA2-256 circuit through columns in `Sha2Config`.
@@ -66,41 +148,163 @@ use std::marker::PhantomData;
 
 use halo2_proofs::{
     arithmetic::FieldExt,
-    circuit::Layouter,
-    plonk::{Advice, Any, Column, ConstraintSystem, Error},
-    poly::Rotation,
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{Advice, Any, Challenge, Column, ConstraintSystem, Error, FirstPhase, Fixed, Instance, SecondPhase},
 };
 
+mod gadget;
 mod gates;
+mod hmac;
+mod merkle;
 mod regions;
+mod sha512;
 mod spread_table;
+mod trace;
 mod utils;
+mod word;
 
-use spread_table::SpreadTableConfig;
+use spread_table::SpreadLookupConfig;
+use gates::block_state::BlockStateConfig;
+use gates::cond_swap::CondSwapConfig;
+use gates::midstate::MidstateConfig;
+use gates::pad_xor::PadXorConfig;
 use regions::{
     compression::CompressionChip, 
-    message_schedule::MessageScheduleChip
+    message_schedule::MessageScheduleChip,
+    layout::{BlockLayout, DoubleLayout},
+    table::{TableChip, TABLE_ROWS_PER_BLOCK},
 };
+use utils::{create_interleave_num, create_tag, padding_words, IV, IV_224};
+
+pub use gadget::Sha256CompressionGadget;
+pub use hmac::HmacSha256Chip;
+pub use merkle::{MerkleHashing, MerklePathGadget};
+pub use regions::layout::Sha2Layout;
+pub use sha512::{Sha512Chip, Sha512Config, Sha512Mode, Sha512Table, Sha512Witness};
+pub use spread_table::SpreadTableBits;
+pub use trace::{BlockTrace, RoundTrace, Sha256Trace};
+pub use word::{AssignedWord, AssignedWord32, AssignedWord64, Word32, Word64};
 
 
 #[derive(Clone, Debug)]
 pub struct Sha2Table {
-    id: Column<Advice>,
+    pub id: Column<Advice>,
+    pub tag: Column<Advice>,
+    pub is_final: Column<Advice>,
+    pub input_len: Column<Advice>,
+    pub index: Column<Advice>,
+    pub input: Column<Advice>,
+    pub digest_hi: Column<Advice>,
+    pub digest_lo: Column<Advice>,
+    /// Gas of the SHA256 precompile for the message, `60 + 12 * ceil(len / 32)`
+    /// on the final row, and for the bytes read so far on the others.
+    pub gas: Column<Advice>,
+    /// Random linear combination of the message bytes read so far, with
+    /// `challenge`, in the second phase.
+    pub input_rlc: Column<Advice>,
+    /// Random linear combination of the digest bytes with `challenge` on the
+    /// last row of the final block, in the second phase.
+    pub output_rlc: Column<Advice>,
+    /// Challenge of the random linear combinations, available after the first
+    /// phase.
+    pub challenge: Challenge,
 }
 
 impl Sha2Table {
     pub fn construct<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> Self {
+        let tag = meta.advice_column();
+        let is_final = meta.advice_column();
+        let digest_hi = meta.advice_column();
+        let digest_lo = meta.advice_column();
+        meta.enable_equality(tag);
+        meta.enable_equality(is_final);
+        meta.enable_equality(digest_hi);
+        meta.enable_equality(digest_lo);
+        let id = meta.advice_column();
+        let input_len = meta.advice_column();
+        let index = meta.advice_column();
+        let input = meta.advice_column();
+        let gas = meta.advice_column();
+
+        let input_rlc = meta.advice_column_in(SecondPhase);
+        let output_rlc = meta.advice_column_in(SecondPhase);
+        meta.enable_equality(input_rlc);
+        let challenge = meta.challenge_usable_after(FirstPhase);
+
         Self {
-            id: meta.advice_column(),
+            id,
+            tag,
+            is_final,
+            input_len,
+            index,
+            input,
+            digest_hi,
+            digest_lo,
+            gas,
+            input_rlc,
+            output_rlc,
+            challenge,
         }
     }
 
     pub fn columns(&self) -> Vec<Column<Any>> {
-        vec![self.id.into()]
+        vec![
+            self.id.into(),
+            self.tag.into(),
+            self.is_final.into(),
+            self.input_len.into(),
+            self.index.into(),
+            self.input.into(),
+            self.digest_hi.into(),
+            self.digest_lo.into(),
+            self.gas.into(),
+            self.input_rlc.into(),
+            self.output_rlc.into(),
+        ]
     }
 
     pub fn annotations(&self) -> Vec<String> {
-        vec![String::from("id")]
+        vec![
+            String::from("id"),
+            String::from("tag"),
+            String::from("is_final"),
+            String::from("input_len"),
+            String::from("index"),
+            String::from("input"),
+            String::from("digest_hi"),
+            String::from("digest_lo"),
+            String::from("gas"),
+            String::from("input_rlc"),
+            String::from("output_rlc"),
+        ]
+    }
+}
+
+/// Variant of the SHA-2 family hashed by a `Sha2Config`. SHA-224 is SHA-256
+/// started from another IV, with its digest truncated to the first 7 words.
+/// `Sha256d` hashes SHA-256, and also SHA256d, `sha256(sha256(x))`, for the
+/// calls whose `tag` is set in the table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sha2Mode {
+    Sha256,
+    Sha224,
+    Sha256d,
+}
+
+impl Sha2Mode {
+    pub fn iv(&self) -> [u32; 8] {
+        match self {
+            Self::Sha256 | Self::Sha256d => IV,
+            Self::Sha224 => IV_224,
+        }
+    }
+
+    /// Number of 32-bit words of the digest.
+    pub fn digest_words(&self) -> usize {
+        match self {
+            Self::Sha256 | Self::Sha256d => 8,
+            Self::Sha224 => 7,
+        }
     }
 }
 
@@ -108,9 +312,18 @@ impl Sha2Table {
 pub struct Sha2Config<F: FieldExt> {
     table: Sha2Table,
     cols: Vec<Column<Advice>>,
-    spread_table: SpreadTableConfig<F, 16>,
+    constants: Column<Fixed>,
+    instance: Column<Instance>,
+    spread: SpreadLookupConfig<F>,
     compression_chip: CompressionChip<F>,
     message_schedule_chip: MessageScheduleChip<F>,
+    table_chip: TableChip<F>,
+    block_state: BlockStateConfig<F>,
+    pad_xor: PadXorConfig<F>,
+    cond_swap: CondSwapConfig<F>,
+    midstate: MidstateConfig<F>,
+    layout: Sha2Layout,
+    mode: Sha2Mode,
     _marker: PhantomData<F>,
 }
 
@@ -118,70 +331,112 @@ impl<F: FieldExt> Sha2Config<F> {
     pub fn configure(
         meta: &mut ConstraintSystem<F>, 
         table: Sha2Table,
+        max_blocks: usize,
+    ) -> Self {
+        Self::configure_with_mode(meta, table, max_blocks, Sha2Mode::Sha256)
+    }
+
+    /// Configures a circuit hashing with `mode`, whose IV is constrained to
+    /// constants and whose digests are truncated in the table and instances.
+    pub fn configure_with_mode(
+        meta: &mut ConstraintSystem<F>,
+        table: Sha2Table,
+        max_blocks: usize,
+        mode: Sha2Mode,
+    ) -> Self {
+        Self::configure_with_spread(meta, table, max_blocks, mode, SpreadTableBits::Sixteen)
+    }
+
+    /// Configures a circuit hashing with `mode` whose gates look their 16-bit
+    /// values up in a spread table of `bits`, which sets the smallest `k` of
+    /// the circuit.
+    pub fn configure_with_spread(
+        meta: &mut ConstraintSystem<F>,
+        table: Sha2Table,
+        max_blocks: usize,
+        mode: Sha2Mode,
+        bits: SpreadTableBits,
     ) -> Self {
         let mut cols: Vec<Column<Advice>> = vec![];
         for i in 0..10 {
             cols.push(meta.advice_column());
             meta.enable_equality(cols[i]);
         }
-        let spread_table = SpreadTableConfig::configure(meta);
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        let spread = SpreadLookupConfig::configure(meta, cols[0], cols[1], cols[2], bits);
         let compression_chip = CompressionChip::configure(
             meta, 
             cols[0], cols[1], cols[2], cols[3], cols[4], 
-            cols[5], cols[6], cols[7], cols[8], cols[9]
+            cols[5], cols[6], cols[7], cols[8], cols[9], spread
         );
         let message_schedule_chip = MessageScheduleChip::configure(
             meta, cols[0], cols[1], cols[2], cols[3], cols[4], 
-            cols[5], cols[6], cols[7], cols[8], cols[9]
+            cols[5], cols[6], cols[7], cols[8], cols[9], spread
         );
-        
-        meta.lookup(
-            "Consistent Lookup 1", 
-            |table| {
-                let a0 = table.query_advice(cols[0], Rotation::cur());
-                let a1 = table.query_advice(cols[1], Rotation::cur());
-                let a2 = table.query_advice(cols[2], Rotation::cur());
-
-                vec![
-                    (a0, spread_table.tag),
-                    (a1, spread_table.table),
-                    (a2, spread_table.spread)
-                ]
-            }
+        let table_chip = TableChip::configure(meta, table.clone(), mode);
+        let block_state = BlockStateConfig::configure(
+            meta, cols[3], cols[4], cols[5], cols[6], cols[7], cols[8]
         );
-
-        meta.lookup("Consistent Lookup 2", |table| {
-
-            let sd_abc = table.query_selector(compression_chip.sd_abc.s_abc);
-            let sd_efg = table.query_selector(compression_chip.sd_efg.s_efg);
-            let a7 = table.query_advice(cols[7], Rotation::cur());
-            let a8 = table.query_advice(cols[8], Rotation::cur());
-
-            let flag = sd_abc.clone() + sd_efg.clone() + sd_abc * sd_efg;
-
-            vec![
-                (flag.clone() * a7, spread_table.table),
-                (flag * a8,         spread_table.spread),
-            ]
-        });
-
+        let pad_xor = PadXorConfig::configure(meta, cols[0], cols[1], cols[2], spread);
+        let cond_swap = CondSwapConfig::configure(meta, cols[3], cols[4], cols[5], cols[6], cols[7]);
+        let midstate = MidstateConfig::configure(meta, cols[3], cols[4], cols[5]);
+        let layout = Sha2Layout::configure(meta, max_blocks, mode, bits);
+        
         Self {
             table,
             cols,
+            constants,
+            instance,
             compression_chip,
             message_schedule_chip,
-            spread_table,
+            table_chip,
+            block_state,
+            pad_xor,
+            cond_swap,
+            midstate,
+            spread,
+            layout,
+            mode,
             _marker: PhantomData,
         }
     }
+
+    pub fn layout(&self) -> Sha2Layout {
+        self.layout
+    }
+
+    pub fn mode(&self) -> Sha2Mode {
+        self.mode
+    }
+
+    /// Loads the lookup tables used by the circuit.
+    pub fn load_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.spread.load(layouter)?;
+        self.table_chip.load(layouter)
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct Sha2Witness<F> {
     pub inputs: Vec<Vec<u8>>,
+    /// Inputs hashed with SHA256d in `Sha2Mode::Sha256d`, by index. Inputs
+    /// past the end of `double` are hashed once.
+    pub double: Vec<bool>,
     pub _marker: PhantomData<F>,
 }
 
+/// The state of a message hashed up to a block boundary, with the number of
+/// blocks hashed so far, for hashing a long message in several steps or across
+/// several proofs.
+#[derive(Clone, Debug)]
+pub struct Sha256Midstate<F: FieldExt> {
+    pub state: [AssignedWord<F>; 8],
+    pub blocks: AssignedCell<F, F>,
+}
+
 #[derive(Clone, Debug)]
 pub struct Sha2Chip<F: FieldExt> {
     config: Sha2Config<F>,
@@ -193,96 +448,265 @@ impl<F: FieldExt> Sha2Chip<F> {
         Self { data, config }
     }
 
-    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
-
-        let k = [   
-            0x428a2f98u32, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
-            0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
-            0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
-            0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
-            0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
-            0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
-            0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
-            0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2
-        ].into_iter().fold(vec![], |mut l, elem| {
-            l.push(elem as u16);
-            l.push((elem >> 16) as u16);
-            l
-        });
-
-        let h = [
-            0x6a09e667u32,
-            0xbb67ae85,
-            0x3c6ef372,
-            0xa54ff53a,
-            0x510e527f,
-            0x9b05688c,
-            0x1f83d9ab,
-            0x5be0cd19
-        ].into_iter().fold(vec![], |mut l, elem| {
-            l.push(elem as u16);
-            l.push((elem >> 16) as u16);
-            l
-        });
-        
-        let mut idx = 0;
-        for input in self.data.inputs.iter() {
+    /// Hashes every input and returns, for every block slot, the 32-bit words
+    /// of its digest masked by its `is_final` flag: `H0..H7` for SHA-256, and
+    /// `H0..H6` for SHA-224. A SHA256d call outputs its second digest.
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<Vec<Vec<AssignedCell<F, F>>>, Error> {
 
-            let l: u64 = self.data.inputs[0].len() as u64;
-        
-            let coef = (l+1+8) / 64;
-            let rem = (l+1+8) % 64;
-            let size = coef * 64 + (rem > 0) as u64 * 64;
-            let num_zeros = size - self.data.inputs[0].len() as u64 - 1 - 8;
-            let mut copy = self.data.inputs[0].clone();
-            copy.extend([0x80]);
-            copy.extend(vec![0; num_zeros as usize]);
-            copy.extend((l*8).to_be_bytes());
-            let mut w: Vec<u16> = vec![];
-            for i in 0..copy.len()/4 {
-                w.push( copy[4*i+3] as u16 + (copy[4*i+2] as u16) * ( 1 << 8) );
-                w.push( copy[4*i+1] as u16 + (copy[4*i+0] as u16) * ( 1 << 8) );
+        let iv = self.config.mode.iv();
+        let h: Vec<Word32> = iv.iter().map(|iv| Word32::from(*iv)).collect();
+
+        // (id, tag, input_len, block_index, is_first, is_final, trace, data_len)
+        // of every block slot, followed by dummy blocks up to the capacity of
+        // the circuit. A dummy block is an unterminated block of zero bytes, so
+        // it carries no padding and never outputs a digest. Every dummy block
+        // starts a call of its own, numbered after the inputs.
+        let mut slots = vec![];
+        for (idx, input) in self.data.inputs.iter().enumerate() {
+            let double = self.data.double.get(idx).copied().unwrap_or(false);
+            if double && self.config.mode != Sha2Mode::Sha256d {
+                return Err(Error::Synthesis);
+            }
+            let trace = Sha256Trace::with_iv(input, iv);
+            let num_blocks = trace.blocks.len();
+            for (i, block) in trace.blocks.into_iter().enumerate() {
+                slots.push((
+                    idx as u64 + 1, double as u64, input.len() as u64, i, i == 0, i == num_blocks - 1, block,
+                    input.len().saturating_sub(TABLE_ROWS_PER_BLOCK*i).min(TABLE_ROWS_PER_BLOCK)
+                ));
             }
+        }
+        let max_blocks = self.config.layout.max_blocks;
+        let block_layout: BlockLayout = self.config.layout.block;
+        if slots.len() > max_blocks {
+            return Err(Error::Synthesis);
+        }
+        let mut next_id = self.data.inputs.len() as u64 + 1;
+        while slots.len() < max_blocks {
+            slots.push((next_id, 0, 0, 0, true, false, BlockTrace::new(&[0; TABLE_ROWS_PER_BLOCK], iv), TABLE_ROWS_PER_BLOCK));
+            next_id += 1;
+        }
 
-            layouter.assign_region(
-                || format!("Full SHA256 {}", idx), 
+        let r = layouter.get_challenge(self.config.table.challenge);
+        let mut digests = vec![];
+        let mut prev: Option<Vec<AssignedWord<F>>> = None;
+        let mut prev_row: Option<Vec<AssignedCell<F, F>>> = None;
+        for (s, (id, tag, l, i, is_first, is_final, block, data_len)) in slots.into_iter().enumerate() {
+            let w: Vec<Value<Word32>> = block.w[0..16].iter().map(|w| Value::known(Word32::from(*w))).collect();
+
+            let (h_c, last_row, digest) = layouter.assign_region(
+                || format!("SHA256 block {}", s), 
                 |mut region| {
-                    let k = k.clone();
-                    let w = w.clone();
-                    let h = h.clone();
-
-                    let mut h_val = vec![];
-                    let mut h_c = vec![];
-                    for i in 0..w.len()/32 {
-                        let mut w_p = vec![0; 32];
-                        w_p.copy_from_slice(&w[32*i..32*(i+1)]);
-                        let (w_val, w_c) = self.config.message_schedule_chip.load(&mut region, w_p, 2099 * i)?;
-                        if i == 0 {
-                            (h_val, h_c) = self.config.compression_chip.load(&mut region, w_c, w_val, k.clone(), h.clone(), 2099 * i + 547)?;
-                        } else {
-                            // let (w_val, w_c) = self.config.message_schedule_chip.load(&mut region, w_p, 2099 * (i+1))?;
-                            (h_val, h_c) = self.config.compression_chip.load_steady(
-                                &mut region, w_c, w_val, k.clone(), 
-                                h_c, h_val, 2099 * i + 547
-                            )?;
-                        }
+                    let (is_first_c, h_in) = self.config.block_state.assign_init(
+                        &mut region, Value::known(is_first), h.clone(), prev.clone(), block_layout.init
+                    )?;
+                    if s == 0 {
+                        region.constrain_constant(is_first_c.cell(), F::one())?;
                     }
-                    
-                    Ok(())
+                    let w = self.config.message_schedule_chip.load(&mut region, w.clone(), block_layout.schedule)?;
+                    let last_row = self.config.table_chip.assign_block(
+                        &mut region, id, tag, l, i, &block.block, data_len, is_final,
+                        is_first_c, prev_row.clone(), &w, r, block_layout.table
+                    )?;
+                    let h_c = self.config.compression_chip.load_steady(
+                        &mut region, &w, h_in, block_layout.compression
+                    )?;
+                    let h_out = match block_layout.double {
+                        Some(double) => self.assign_double(&mut region, &last_row[4], &h_c, double)?,
+                        None => h_c.clone(),
+                    };
+                    self.config.table_chip.assign_digest(&mut region, &last_row[0], &h_out, r, block_layout.table)?;
+                    let mut words = self.config.compression_chip.assign_words(&mut region, &h_out, block_layout.words)?;
+                    words.truncate(self.config.mode.digest_words());
+                    let digest = self.config.block_state.assign_out(&mut region, last_row[0].clone(), words, block_layout.words)?;
+
+                    Ok((h_c, last_row, digest))
                 }
             )?;
-            idx += 1;
+            digests.push(digest);
+            prev = Some(h_c);
+            prev_row = Some(last_row);
         }
 
+        Ok(digests)
+    }
+
+    /// Hashes the state `h` of a block again, as a 32-byte message padded to
+    /// one block, and returns the second digest when `tag` is set and `h`
+    /// otherwise. The words of `h` are copied into the message schedule and
+    /// its padding words are constrained to constants.
+    fn assign_double(
+        &self,
+        region: &mut Region<F>,
+        tag: &AssignedCell<F, F>,
+        h: &[AssignedWord<F>],
+        layout: DoubleLayout,
+    ) -> Result<Vec<AssignedWord<F>>, Error> {
+        let padding: Vec<Word32> = padding_words(32).into_iter().map(Word32::from).collect();
+        let w: Vec<Value<Word32>> = h.iter().map(|h| h.value)
+            .chain(padding.iter().map(|word| Value::known(*word)))
+            .collect();
+        let w = self.config.message_schedule_chip.load(region, w, layout.schedule)?;
+        for (w, h) in w.iter().zip(h.iter()) {
+            region.constrain_equal(w.lo.cell(), h.lo.cell())?;
+            region.constrain_equal(w.hi.cell(), h.hi.cell())?;
+        }
+        for (w, word) in w[8..16].iter().zip(padding.iter()) {
+            region.constrain_constant(w.lo.cell(), F::from(word.lo as u64))?;
+            region.constrain_constant(w.hi.cell(), F::from(word.hi as u64))?;
+        }
+
+        let iv = IV.iter().map(|iv| Value::known(Word32::from(*iv))).collect();
+        let h2 = self.config.compression_chip.load(region, &w, iv, layout.compression)?;
+        let (out, _) = self.config.cond_swap.assign(region, tag, h, &h2, layout.select)?;
+        Ok(out)
+    }
+
+    /// Constrains the digest words returned by `load` to the instance column,
+    /// one row per digest word of every block slot.
+    pub fn expose_digests(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        digests: &Vec<Vec<AssignedCell<F, F>>>,
+    ) -> Result<(), Error> {
+        for (i, digest) in digests.iter().enumerate() {
+            for (j, word) in digest.iter().enumerate() {
+                layouter.constrain_instance(word.cell(), self.config.instance, digest.len() * i + j)?;
+            }
+        }
         Ok(())
     }
+
+    /// Hashes a message whose bytes were assigned by another chip, and returns
+    /// the eight words `H0..H7` of its final state, whose first
+    /// `digest_words` words are the digest in the mode of the config.
+    ///
+    /// Every cell of `bytes` is copied into the range checked bytes of the
+    /// padded message, whose padding is constrained to constants. The length
+    /// of the message is fixed at synthesis time by the number of cells: the
+    /// circuit hashes exactly `bytes.len()` bytes, so a caller holding the
+    /// length in a cell constrains it to that constant itself. The message is
+    /// hashed through `Sha256CompressionGadget`, outside of the block slots of
+    /// `load`, so it has no call rows in the `Sha2Table` to look up.
+    pub fn hash_assigned(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        bytes: &[AssignedCell<F, F>],
+    ) -> Result<[AssignedWord<F>; 8], Error> {
+        let gadget = Sha256CompressionGadget::construct(self.config.clone());
+        let iv = gadget.iv(layouter)?;
+        gadget.hash_bytes(layouter, iv, bytes, 0)
+    }
+
+    /// Returns the midstate of the empty message: the IV of the mode, after 0
+    /// blocks.
+    pub fn initial_midstate(&self, layouter: &mut impl Layouter<F>) -> Result<Sha256Midstate<F>, Error> {
+        let gadget = Sha256CompressionGadget::construct(self.config.clone());
+        let state = gadget.iv(layouter)?;
+        let blocks = layouter.assign_region(
+            || "SHA256 initial blocks",
+            |mut region| region.assign_advice_from_constant(|| "blocks", self.config.cols[3], 0, F::zero())
+        )?;
+        Ok(Sha256Midstate { state, blocks })
+    }
+
+    /// Witnesses a midstate computed outside of the circuit, such as the state
+    /// after a fixed prefix or the midstate exposed by a previous proof, to be
+    /// tied to the instance column with `expose_midstate`.
+    ///
+    /// The limbs of the state are range checked to 16 bits on
+    /// `(tag, value, spread)` rows of the spread table lookup.
+    pub fn assign_midstate(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        state: [Value<u32>; 8],
+        blocks: Value<u64>,
+    ) -> Result<Sha256Midstate<F>, Error> {
+        layouter.assign_region(
+            || "SHA256 midstate",
+            |mut region| {
+                let mut words = vec![];
+                for (i, word) in state.iter().enumerate() {
+                    let word = word.map(Word32::from);
+                    let mut limbs = vec![];
+                    for (j, limb) in [word.map(|word| word.lo), word.map(|word| word.hi)].into_iter().enumerate() {
+                        let row = 2 * i + j;
+                        self.config.spread.enable(&mut region, row, limb)?;
+                        region.assign_advice(|| "midstate tag", self.config.cols[0], row, || limb.map(|limb| F::from(create_tag(limb))))?;
+                        limbs.push(region.assign_advice(|| "midstate limb", self.config.cols[1], row, || limb.map(|limb| F::from(limb as u64)))?);
+                        region.assign_advice(
+                            || "midstate spread", self.config.cols[2], row,
+                            || limb.map(|limb| F::from(create_interleave_num(limb as u32) as u64))
+                        )?;
+                    }
+                    words.push(AssignedWord::new(limbs[0].clone(), limbs[1].clone(), word));
+                }
+                let blocks = region.assign_advice(|| "midstate blocks", self.config.cols[3], 0, || blocks.map(F::from))?;
+                let state = words.try_into().map_err(|_| Error::Synthesis)?;
+                Ok(Sha256Midstate { state, blocks })
+            }
+        )
+    }
+
+    /// Constrains the eight 32-bit words of the state of `midstate` to the
+    /// instance rows `row..row + 8`, and its block counter to row `row + 8`.
+    pub fn expose_midstate(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        midstate: &Sha256Midstate<F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        let words = layouter.assign_region(
+            || "SHA256 midstate words",
+            |mut region| self.config.compression_chip.assign_words(&mut region, &midstate.state, 0)
+        )?;
+        for (i, word) in words.iter().enumerate() {
+            layouter.constrain_instance(word.cell(), self.config.instance, row + i)?;
+        }
+        layouter.constrain_instance(midstate.blocks.cell(), self.config.instance, row + 8)
+    }
+
+    /// Hashes whole blocks of a message, whose bytes were assigned by another
+    /// chip, into `midstate` without finalizing it, and returns the new
+    /// midstate. The number of cells must be a multiple of the block size.
+    pub fn update(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        midstate: Sha256Midstate<F>,
+        bytes: &[AssignedCell<F, F>],
+    ) -> Result<Sha256Midstate<F>, Error> {
+        let gadget = Sha256CompressionGadget::construct(self.config.clone());
+        let state = gadget.compress_bytes(layouter, midstate.state, bytes)?;
+        let blocks = layouter.assign_region(
+            || "SHA256 midstate blocks",
+            |mut region| self.config.midstate.assign_count(
+                &mut region, &midstate.blocks, (bytes.len() / TABLE_ROWS_PER_BLOCK) as u64, 0
+            )
+        )?;
+        Ok(Sha256Midstate { state, blocks })
+    }
+
+    /// Hashes the last bytes of a message, assigned by another chip, after
+    /// `midstate` and returns the eight words `H0..H7` of its final state. The
+    /// length in the padding covers the blocks counted by `midstate`.
+    ///
+    /// Like `hash_assigned`, the message is hashed once whatever the mode.
+    pub fn finalize(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        midstate: Sha256Midstate<F>,
+        bytes: &[AssignedCell<F, F>],
+    ) -> Result<[AssignedWord<F>; 8], Error> {
+        let gadget = Sha256CompressionGadget::construct(self.config.clone());
+        gadget.hash_bytes_after(layouter, midstate.state, bytes, &midstate.blocks)
+    }
 }
 
 #[cfg(any(feature = "test", test))]
 pub mod dev {
     use super::*;
 
-    use ethers_core::types::H256;
+    use ethers_core::{types::H256, utils::hex};
     use halo2_proofs::{circuit::SimpleFloorPlanner, plonk::Circuit};
     use std::str::FromStr;
 
@@ -317,6 +741,41 @@ pub mod dev {
         };
     }
 
+    lazy_static::lazy_static! {
+        /// SHA-224 test vectors of FIPS 180-2.
+        pub static ref INPUTS_OUTPUTS_224: (Vec<Vec<u8>>, Vec<Vec<u8>>) = {
+        [
+            (
+                "",
+                "d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f",
+            ),
+            (
+                "abc",
+                "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7",
+            ),
+            (
+                "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                "75388b16512776cc5dba5da1fd890150b0c6455cb4f58b1952522525",
+            ),
+            (
+                "abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+                "c97ca9a559850ce97a04a96def6d99a9e0e0e2ab14e6b8df265fc0b3",
+            ),
+        ]
+            .iter()
+            .map(|(input, output)| {
+                (
+                    input.as_bytes().to_vec(),
+                    hex::decode(output).expect("SHA-224 hash is hex"),
+                )
+            })
+            .unzip()
+        };
+    }
+
+    /// Block capacity of `Sha2TestCircuit`.
+    pub const MAX_BLOCKS: usize = 8;
+
     #[derive(Default)]
     pub struct Sha2TestCircuit<F> {
         pub inputs: Vec<Vec<u8>>,
@@ -334,7 +793,7 @@ pub mod dev {
 
         fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
             let sha2_table = Sha2Table::construct(meta);
-            Sha2Config::configure(meta, sha2_table)
+            Sha2Config::configure(meta, sha2_table, MAX_BLOCKS)
         }
 
         fn synthesize(
@@ -342,30 +801,450 @@ pub mod dev {
             config: Self::Config,
             mut layouter: impl Layouter<F>,
         ) -> Result<(), Error> {
-            config.spread_table.load(&mut layouter)?;
+            config.load_tables(&mut layouter)?;
             let chip = Sha2Chip::construct(
                 config,
                 Sha2Witness {
                     inputs: self.inputs.clone(),
+                    double: vec![],
                     _marker: PhantomData,
                 },
             );
-            chip.load(&mut layouter)?;
+            let digests = chip.load(&mut layouter)?;
+            chip.expose_digests(&mut layouter, &digests)?;
+            Ok(())
+        }
+    }
+
+    /// `Sha2TestCircuit` in SHA-224 mode, checking `outputs` of 28 bytes.
+    #[derive(Default)]
+    pub struct Sha224TestCircuit<F> {
+        pub inputs: Vec<Vec<u8>>,
+        pub outputs: Vec<Vec<u8>>,
+        pub _marker: PhantomData<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for Sha224TestCircuit<F> {
+        type Config = Sha2Config<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let sha2_table = Sha2Table::construct(meta);
+            Sha2Config::configure_with_mode(meta, sha2_table, MAX_BLOCKS, Sha2Mode::Sha224)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.load_tables(&mut layouter)?;
+            let chip = Sha2Chip::construct(
+                config,
+                Sha2Witness {
+                    inputs: self.inputs.clone(),
+                    double: vec![],
+                    _marker: PhantomData,
+                },
+            );
+            let digests = chip.load(&mut layouter)?;
+            chip.expose_digests(&mut layouter, &digests)?;
+            Ok(())
+        }
+    }
+
+    /// `Sha2TestCircuit` in SHA256d mode, hashing the inputs flagged in
+    /// `double` twice.
+    #[derive(Default)]
+    pub struct Sha256dTestCircuit<F> {
+        pub inputs: Vec<Vec<u8>>,
+        pub double: Vec<bool>,
+        pub outputs: Vec<H256>,
+        pub _marker: PhantomData<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for Sha256dTestCircuit<F> {
+        type Config = Sha2Config<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let sha2_table = Sha2Table::construct(meta);
+            Sha2Config::configure_with_mode(meta, sha2_table, MAX_BLOCKS, Sha2Mode::Sha256d)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.load_tables(&mut layouter)?;
+            let chip = Sha2Chip::construct(
+                config,
+                Sha2Witness {
+                    inputs: self.inputs.clone(),
+                    double: self.double.clone(),
+                    _marker: PhantomData,
+                },
+            );
+            let digests = chip.load(&mut layouter)?;
+            chip.expose_digests(&mut layouter, &digests)?;
             Ok(())
         }
     }
+
+    /// Instance column values exposing `outputs` of `inputs` in a circuit of
+    /// `max_blocks` blocks, as expected by `Sha2Chip::expose_digests`.
+    pub fn digest_instances<F: FieldExt>(inputs: &[Vec<u8>], outputs: &[H256], max_blocks: usize) -> Vec<F> {
+        let outputs: Vec<&[u8]> = outputs.iter().map(|output| output.as_bytes()).collect();
+        mode_instances(Sha2Mode::Sha256, inputs, &outputs, max_blocks)
+    }
+
+    /// Instance column values exposing the SHA-224 `outputs` of `inputs`.
+    pub fn digest_instances_224<F: FieldExt>(inputs: &[Vec<u8>], outputs: &[Vec<u8>], max_blocks: usize) -> Vec<F> {
+        let outputs: Vec<&[u8]> = outputs.iter().map(|output| output.as_slice()).collect();
+        mode_instances(Sha2Mode::Sha224, inputs, &outputs, max_blocks)
+    }
+
+    fn mode_instances<F: FieldExt>(mode: Sha2Mode, inputs: &[Vec<u8>], outputs: &[&[u8]], max_blocks: usize) -> Vec<F> {
+        let digest_words = mode.digest_words();
+        let mut instances = vec![];
+        for (input, output) in inputs.iter().zip(outputs.iter()) {
+            let num_blocks = Sha256Trace::new(input).blocks.len();
+            instances.extend(vec![F::zero(); digest_words * (num_blocks - 1)]);
+            instances.extend(
+                output
+                    .chunks(4)
+                    .map(|word| F::from(u32::from_be_bytes(word.try_into().expect("SHA-2 word is 4-bytes")) as u64))
+            );
+        }
+        instances.resize(digest_words * max_blocks, F::zero());
+        instances
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+    use halo2_proofs::{dev::{MockProver, VerifyFailure}, halo2curves::bn256::Fr};
     use std::marker::PhantomData;
+    use std::str::FromStr;
+
+    use ethers_core::types::H256;
+
+    use crate::dev::{
+        digest_instances, digest_instances_224, Sha224TestCircuit, Sha256dTestCircuit, Sha2TestCircuit,
+        INPUTS_OUTPUTS, INPUTS_OUTPUTS_224, MAX_BLOCKS,
+    };
+    use crate::{Sha2Layout, Sha256Trace};
+    use crate::utils::rlc;
+    use super::*;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, plonk::Circuit};
+    use halo2_proofs::halo2curves::bn256::Bn256;
+    use halo2_proofs::plonk::{keygen_vk, Selector};
+    use halo2_proofs::poly::Rotation;
+    use halo2_proofs::poly::{commitment::ParamsProver, kzg::commitment::ParamsKZG};
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    /// Hashes `input` from byte cells assigned outside of the SHA-256 chips.
+    /// With `garbage`, the region of the bytes also fills the spread columns
+    /// `a0..a2` with a row that is not in the spread table.
+    #[derive(Default, Clone)]
+    struct AssignedBytesCircuit<F: FieldExt> {
+        input: Vec<u8>,
+        garbage: bool,
+        _marker: PhantomData<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for AssignedBytesCircuit<F> {
+        type Config = Sha2Config<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = Sha2Table::construct(meta);
+            Sha2Config::configure(meta, table, 1)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            config.load_tables(&mut layouter)?;
+            let bytes = layouter.assign_region(
+                || "message bytes",
+                |mut region| {
+                    let mut bytes = vec![];
+                    for (i, byte) in self.input.iter().enumerate() {
+                        bytes.push(region.assign_advice(
+                            || "byte", config.cols[3], i, || Value::known(F::from(*byte as u64))
+                        )?);
+                    }
+                    if self.garbage {
+                        for (col, value) in config.cols[0..3].iter().zip([7u64, 3, 1]) {
+                            region.assign_advice(|| "garbage", *col, 0, || Value::known(F::from(value)))?;
+                        }
+                    }
+                    Ok(bytes)
+                }
+            )?;
+
+            let chip = Sha2Chip::construct(config.clone(), Sha2Witness { inputs: vec![], double: vec![], _marker: PhantomData });
+            let state = chip.hash_assigned(&mut layouter, &bytes)?;
+            let digest = layouter.assign_region(
+                || "digest words",
+                |mut region| config.compression_chip.assign_words(&mut region, &state, 0)
+            )?;
+            for (i, word) in digest.iter().enumerate() {
+                layouter.constrain_instance(word.cell(), config.instance, i)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Block capacity of `SplitSpreadCircuit`.
+    const SPLIT_MAX_BLOCKS: usize = 2;
+
+    /// `Sha2TestCircuit` whose gates look their values up as bytes in a spread
+    /// table of `2^8` rows.
+    #[derive(Default, Clone)]
+    struct SplitSpreadCircuit<F: FieldExt> {
+        inputs: Vec<Vec<u8>>,
+        _marker: PhantomData<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for SplitSpreadCircuit<F> {
+        type Config = Sha2Config<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = Sha2Table::construct(meta);
+            Sha2Config::configure_with_spread(meta, table, SPLIT_MAX_BLOCKS, Sha2Mode::Sha256, SpreadTableBits::Eight)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            config.load_tables(&mut layouter)?;
+            let chip = Sha2Chip::construct(
+                config,
+                Sha2Witness { inputs: self.inputs.clone(), double: vec![], _marker: PhantomData },
+            );
+            let digests = chip.load(&mut layouter)?;
+            chip.expose_digests(&mut layouter, &digests)?;
+            Ok(())
+        }
+    }
+
+    /// Hashes `prefix || suffix`, exposing the midstate after `prefix` at the
+    /// instance rows `8..17`. The prefix is hashed in the circuit, or with
+    /// `precomputed` only its midstate is witnessed, as in a second proof.
+    #[derive(Default, Clone)]
+    struct MidstateCircuit<F: FieldExt> {
+        prefix: Vec<u8>,
+        suffix: Vec<u8>,
+        precomputed: bool,
+        _marker: PhantomData<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MidstateCircuit<F> {
+        type Config = Sha2Config<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = Sha2Table::construct(meta);
+            Sha2Config::configure(meta, table, 1)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            config.load_tables(&mut layouter)?;
+            let (prefix, suffix) = layouter.assign_region(
+                || "message bytes",
+                |mut region| {
+                    let mut prefix = vec![];
+                    for (i, byte) in self.prefix.iter().enumerate() {
+                        prefix.push(region.assign_advice(
+                            || "prefix byte", config.cols[3], i, || Value::known(F::from(*byte as u64))
+                        )?);
+                    }
+                    let mut suffix = vec![];
+                    for (i, byte) in self.suffix.iter().enumerate() {
+                        suffix.push(region.assign_advice(
+                            || "suffix byte", config.cols[4], i, || Value::known(F::from(*byte as u64))
+                        )?);
+                    }
+                    Ok((prefix, suffix))
+                }
+            )?;
+
+            let chip = Sha2Chip::construct(config.clone(), Sha2Witness { inputs: vec![], double: vec![], _marker: PhantomData });
+            let midstate = if self.precomputed {
+                let h = prefix_state(&self.prefix);
+                let blocks = (self.prefix.len() / TABLE_ROWS_PER_BLOCK) as u64;
+                chip.assign_midstate(&mut layouter, h.map(Value::known), Value::known(blocks))?
+            } else {
+                let midstate = chip.initial_midstate(&mut layouter)?;
+                chip.update(&mut layouter, midstate, &prefix)?
+            };
+            chip.expose_midstate(&mut layouter, &midstate, 8)?;
+            let state = chip.finalize(&mut layouter, midstate, &suffix)?;
+            let digest = layouter.assign_region(
+                || "digest words",
+                |mut region| config.compression_chip.assign_words(&mut region, &state, 0)
+            )?;
+            for (i, word) in digest.iter().enumerate() {
+                layouter.constrain_instance(word.cell(), config.instance, i)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// The state after the whole blocks of `prefix`.
+    fn prefix_state(prefix: &[u8]) -> [u32; 8] {
+        prefix.chunks(TABLE_ROWS_PER_BLOCK).fold(IV, |h, block| BlockTrace::new(block, h).h_out)
+    }
+
+    /// Instances of `MidstateCircuit`: the digest of `prefix || suffix`, then
+    /// the midstate after `prefix` and its block counter.
+    fn midstate_instances(prefix: &[u8], suffix: &[u8]) -> Vec<Fr> {
+        let mut instances = digest_words(&[prefix, suffix].concat());
+        instances.extend(prefix_state(prefix).iter().map(|word| Fr::from(*word as u64)));
+        instances.push(Fr::from((prefix.len() / TABLE_ROWS_PER_BLOCK) as u64));
+        instances
+    }
+
+    #[derive(Clone, Debug)]
+    struct RlcLookupConfig<F: FieldExt> {
+        sha2: Sha2Config<F>,
+        q_lookup: Selector,
+        input_rlc: Column<Advice>,
+        input_len: Column<Advice>,
+        output_rlc: Column<Advice>,
+        gas: Column<Advice>,
+    }
+
+    /// Hashes `inputs` and looks up the `(input_rlc, input_len, output_rlc,
+    /// gas)` of every `(input, output, gas)` of `lookups` in the table, as a
+    /// consumer circuit would.
+    #[derive(Default, Clone)]
+    struct RlcLookupCircuit<F: FieldExt> {
+        inputs: Vec<Vec<u8>>,
+        lookups: Vec<(Vec<u8>, Vec<u8>, u64)>,
+        _marker: PhantomData<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for RlcLookupCircuit<F> {
+        type Config = RlcLookupConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = Sha2Table::construct(meta);
+            let sha2 = Sha2Config::configure(meta, table.clone(), MAX_BLOCKS);
+            let q_lookup = meta.complex_selector();
+            let input_len = meta.advice_column();
+            let input_rlc = meta.advice_column_in(SecondPhase);
+            let output_rlc = meta.advice_column_in(SecondPhase);
+            let gas = meta.advice_column();
+
+            meta.lookup_any("Sha2 RLC Lookup", |meta| {
+                let q_lookup = meta.query_selector(q_lookup);
+                let input_rlc = meta.query_advice(input_rlc, Rotation::cur());
+                let input_len = meta.query_advice(input_len, Rotation::cur());
+                let output_rlc = meta.query_advice(output_rlc, Rotation::cur());
+                let gas = meta.query_advice(gas, Rotation::cur());
+
+                vec![
+                    (q_lookup.clone(), meta.query_advice(table.is_final, Rotation::cur())),
+                    (q_lookup.clone() * input_rlc, meta.query_advice(table.input_rlc, Rotation::cur())),
+                    (q_lookup.clone() * input_len, meta.query_advice(table.input_len, Rotation::cur())),
+                    (q_lookup.clone() * output_rlc, meta.query_advice(table.output_rlc, Rotation::cur())),
+                    (q_lookup * gas, meta.query_advice(table.gas, Rotation::cur())),
+                ]
+            });
+
+            RlcLookupConfig { sha2, q_lookup, input_rlc, input_len, output_rlc, gas }
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            config.sha2.load_tables(&mut layouter)?;
+            let chip = Sha2Chip::construct(
+                config.sha2.clone(),
+                Sha2Witness { inputs: self.inputs.clone(), double: vec![], _marker: PhantomData },
+            );
+            chip.load(&mut layouter)?;
+
+            let r = layouter.get_challenge(config.sha2.table.challenge);
+            layouter.assign_region(
+                || "RLC lookups",
+                |mut region| {
+                    for (i, (input, output, gas)) in self.lookups.iter().enumerate() {
+                        config.q_lookup.enable(&mut region, i)?;
+                        region.assign_advice(
+                            || "input_len", config.input_len, i, || Value::known(F::from(input.len() as u64))
+                        )?;
+                        region.assign_advice(|| "input_rlc", config.input_rlc, i, || r.map(|r| rlc(input, r)))?;
+                        region.assign_advice(|| "output_rlc", config.output_rlc, i, || r.map(|r| rlc(output, r)))?;
+                        region.assign_advice(|| "gas", config.gas, i, || Value::known(F::from(*gas)))?;
+                    }
+                    Ok(())
+                }
+            )
+        }
+    }
+
+    /// Gas of the SHA256 precompile for an input of `len` bytes.
+    fn precompile_gas(len: usize) -> u64 {
+        60 + 12 * ((len as u64 + 31) / 32)
+    }
+
+    fn digest_words(input: &[u8]) -> Vec<Fr> {
+        let h_out = Sha256Trace::new(input).blocks.last().expect("padded message has a block").h_out;
+        h_out.iter().map(|word| Fr::from(*word as u64)).collect()
+    }
+
+    /// Proves the digests of `lens` distinct inputs of the given lengths
+    /// against the native reference.
+    fn verify_lengths(lens: &[usize]) {
+        let inputs: Vec<Vec<u8>> = lens
+            .iter()
+            .map(|len| (0..*len).map(|i| (i * 7 + len) as u8).collect())
+            .collect();
+        let outputs: Vec<H256> = inputs.iter().map(|input| H256::from(Sha256Trace::new(input).digest())).collect();
+        let instances = digest_instances(&inputs, &outputs, MAX_BLOCKS);
+
+        let circuit: Sha2TestCircuit<Fr> = Sha2TestCircuit {
+            inputs,
+            outputs,
+            _marker: PhantomData,
+        };
+
+        let k = 17;
+        let prover = MockProver::run(k, &circuit, vec![instances]).unwrap();
+        assert_eq!(prover.verify(), Ok(()), "inputs of {:?} bytes", lens);
+    }
 
-    use crate::dev::{Sha2TestCircuit, INPUTS_OUTPUTS};
 
     #[test]
     fn test_sha2_circuit() {
         let (inputs, outputs) = INPUTS_OUTPUTS.clone();
+        let instances = digest_instances(&inputs, &outputs, MAX_BLOCKS);
 
         let circuit: Sha2TestCircuit<Fr> = Sha2TestCircuit {
             inputs,
@@ -374,8 +1253,300 @@ mod tests {
         };
 
         let k = 17;
-        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        let prover = MockProver::run(k, &circuit, vec![instances]).unwrap();
         // prover.assert_satisfied();
         assert_eq!(prover.verify(), Ok(()));
     }
+
+    #[test]
+    fn test_sha224_circuit() {
+        let (inputs, outputs) = INPUTS_OUTPUTS_224.clone();
+        let instances = digest_instances_224(&inputs, &outputs, MAX_BLOCKS);
+
+        let circuit: Sha224TestCircuit<Fr> = Sha224TestCircuit {
+            inputs,
+            outputs,
+            _marker: PhantomData,
+        };
+
+        let k = 17;
+        let prover = MockProver::run(k, &circuit, vec![instances]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_sha224_circuit_sha256_digest() {
+        // The SHA-256 digest truncated to 224 bits does not verify in SHA-224
+        // mode, as the IV differs.
+        let (inputs, outputs) = INPUTS_OUTPUTS.clone();
+        let outputs: Vec<Vec<u8>> = outputs.iter().map(|output| output.as_bytes()[..28].to_vec()).collect();
+        let instances = digest_instances_224(&inputs, &outputs, MAX_BLOCKS);
+
+        let circuit: Sha224TestCircuit<Fr> = Sha224TestCircuit {
+            inputs,
+            outputs,
+            _marker: PhantomData,
+        };
+
+        let k = 17;
+        let prover = MockProver::run(k, &circuit, vec![instances]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    /// Proves `inputs`, the ones flagged in `double` hashed with SHA256d, in
+    /// a circuit in `Sha2Mode::Sha256d` exposing `outputs`.
+    fn verify_sha256d(inputs: Vec<Vec<u8>>, double: Vec<bool>, outputs: Vec<H256>) -> Result<(), Vec<VerifyFailure>> {
+        let instances = digest_instances(&inputs, &outputs, MAX_BLOCKS);
+        let circuit: Sha256dTestCircuit<Fr> = Sha256dTestCircuit {
+            inputs,
+            double,
+            outputs,
+            _marker: PhantomData,
+        };
+
+        let k = 17;
+        let prover = MockProver::run(k, &circuit, vec![instances]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn test_sha256d_circuit() {
+        let (inputs, _) = INPUTS_OUTPUTS.clone();
+        let double = vec![true, false, true, false];
+        let outputs: Vec<H256> = inputs.iter().zip(double.iter()).map(|(input, double)| {
+            let digest = Sha256Trace::new(input).digest();
+            H256::from(if *double { Sha256Trace::new(&digest).digest() } else { digest })
+        }).collect();
+        assert_eq!(verify_sha256d(inputs, double, outputs), Ok(()));
+
+        let output = H256::from_str("9595c9df90075148eb06860365df33584b75bff782a510c6cd4883a419833d50")
+            .expect("SHA-256 hash is 32-bytes");
+        assert_eq!(verify_sha256d(vec![b"hello".to_vec()], vec![true], vec![output]), Ok(()));
+    }
+
+    #[test]
+    fn test_sha256d_circuit_single_digest() {
+        // A call tagged for SHA256d does not output its first digest.
+        let (inputs, outputs) = INPUTS_OUTPUTS.clone();
+        assert!(verify_sha256d(inputs, vec![false, true], outputs).is_err());
+    }
+
+    #[test]
+    fn test_sha2_circuit_short_inputs() {
+        verify_lengths(&[0, 1, 2, 3, 4, 5, 31, 32]);
+    }
+
+    #[test]
+    fn test_sha2_circuit_block_boundaries() {
+        verify_lengths(&[55, 56, 63, 64]);
+    }
+
+    #[test]
+    fn test_sha2_circuit_multi_block() {
+        verify_lengths(&[119, 120, 183]);
+    }
+
+    #[test]
+    fn test_sha2_circuit_max_blocks() {
+        verify_lengths(&[64 * MAX_BLOCKS - 9]);
+    }
+
+    #[test]
+    fn test_sha2_circuit_wrong_digest() {
+        let (inputs, mut outputs) = INPUTS_OUTPUTS.clone();
+        outputs.swap(0, 1);
+        let instances = digest_instances(&inputs, &outputs, MAX_BLOCKS);
+
+        let circuit: Sha2TestCircuit<Fr> = Sha2TestCircuit {
+            inputs,
+            outputs,
+            _marker: PhantomData,
+        };
+
+        let k = 17;
+        let prover = MockProver::run(k, &circuit, vec![instances]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_sha2_circuit_fixed_shape() {
+        let (inputs, outputs) = INPUTS_OUTPUTS.clone();
+        let k = 17;
+
+        for n in 0..=inputs.len() {
+            let instances = digest_instances(&inputs[..n], &outputs[..n], MAX_BLOCKS);
+            let circuit: Sha2TestCircuit<Fr> = Sha2TestCircuit {
+                inputs: inputs[..n].to_vec(),
+                outputs: outputs[..n].to_vec(),
+                _marker: PhantomData,
+            };
+            let prover = MockProver::run(k, &circuit, vec![instances]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_sha2_circuit_fixed_vk() {
+        // The verifying key of the circuit without witnesses, as used for
+        // keygen, is the one of circuits hashing any inputs.
+        let (inputs, outputs) = INPUTS_OUTPUTS.clone();
+        let rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+        let params = ParamsKZG::<Bn256>::setup(17, rng);
+
+        let keygen_circuit = Sha2TestCircuit::<Fr>::default();
+        let vk = keygen_vk(&params, &keygen_circuit).unwrap();
+        for n in [1, inputs.len()] {
+            let circuit: Sha2TestCircuit<Fr> = Sha2TestCircuit {
+                inputs: inputs[..n].to_vec(),
+                outputs: outputs[..n].to_vec(),
+                _marker: PhantomData,
+            };
+            let vk_n = keygen_vk(&params, &circuit).unwrap();
+            assert_eq!(format!("{:?}", vk.pinned()), format!("{:?}", vk_n.pinned()), "{} inputs", n);
+        }
+    }
+
+    #[test]
+    fn test_sha2_layout() {
+        let (inputs, _) = INPUTS_OUTPUTS.clone();
+        let layout = Sha2Layout::new::<Fr>(MAX_BLOCKS);
+
+        assert_eq!(layout.min_k(), 17);
+        assert!(layout.check(&inputs, 17).is_ok());
+        assert!(layout.check(&inputs, 16).is_err());
+        assert!(layout.check(&vec![vec![0u8; 64 * MAX_BLOCKS]], 17).is_err());
+
+        let layout = Sha2Layout::with_spread::<Fr>(MAX_BLOCKS, Sha2Mode::Sha256, SpreadTableBits::Eight);
+        assert_eq!(layout.min_k(), 15);
+        assert!(layout.check(&inputs, 15).is_ok());
+        assert!(layout.check(&inputs, 14).is_err());
+    }
+
+    #[test]
+    fn test_sha2_circuit_8_bit_spread() {
+        let (inputs, outputs) = INPUTS_OUTPUTS.clone();
+        let (inputs, outputs) = (inputs[..2].to_vec(), outputs[..2].to_vec());
+        let instances = digest_instances(&inputs, &outputs, SPLIT_MAX_BLOCKS);
+
+        let k = Sha2Layout::with_spread::<Fr>(SPLIT_MAX_BLOCKS, Sha2Mode::Sha256, SpreadTableBits::Eight).min_k();
+        assert_eq!(k, 13);
+        let circuit = SplitSpreadCircuit::<Fr> { inputs, _marker: PhantomData };
+        let prover = MockProver::run(k, &circuit, vec![instances.clone()]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        let mut wrong = instances;
+        wrong[0] += Fr::from(1);
+        let prover = MockProver::run(k, &circuit, vec![wrong]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_sha2_circuit_over_capacity() {
+        let circuit: Sha2TestCircuit<Fr> = Sha2TestCircuit {
+            inputs: vec![vec![0u8; 64 * MAX_BLOCKS]],
+            outputs: vec![],
+            _marker: PhantomData,
+        };
+
+        let k = 17;
+        assert!(MockProver::run(k, &circuit, vec![vec![Fr::from(0); 8 * MAX_BLOCKS]]).is_err());
+    }
+
+    #[test]
+    fn test_sha2_hash_assigned() {
+        for input in [vec![], b"abc".to_vec(), vec![b'a'; 100]] {
+            let circuit = AssignedBytesCircuit::<Fr> { input: input.clone(), garbage: false, _marker: PhantomData };
+            let prover = MockProver::run(17, &circuit, vec![digest_words(&input)]).unwrap();
+            assert_eq!(prover.verify(), Ok(()), "input of {} bytes", input.len());
+        }
+    }
+
+    #[test]
+    fn test_sha2_hash_assigned_wrong_digest() {
+        let circuit = AssignedBytesCircuit::<Fr> { input: b"abc".to_vec(), garbage: false, _marker: PhantomData };
+        let prover = MockProver::run(17, &circuit, vec![digest_words(b"abd")]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_sha2_spread_lookup_garbage_row() {
+        // The spread table lookup only ranges over the rows of the
+        // decomposition gates, so other regions can use `a0..a2` freely.
+        let circuit = AssignedBytesCircuit::<Fr> { input: b"abc".to_vec(), garbage: true, _marker: PhantomData };
+        let prover = MockProver::run(17, &circuit, vec![digest_words(b"abc")]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_sha2_midstate() {
+        let cases = [
+            (vec![], b"abc".to_vec()),
+            (vec![b'a'; 64], vec![]),
+            (vec![b'a'; 128], vec![b'b'; 60]),
+        ];
+        for (prefix, suffix) in cases {
+            for precomputed in [false, true] {
+                let circuit = MidstateCircuit::<Fr> {
+                    prefix: prefix.clone(), suffix: suffix.clone(), precomputed, _marker: PhantomData
+                };
+                let prover = MockProver::run(17, &circuit, vec![midstate_instances(&prefix, &suffix)]).unwrap();
+                assert_eq!(prover.verify(), Ok(()), "prefix of {} bytes, precomputed {}", prefix.len(), precomputed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sha2_midstate_wrong_blocks() {
+        let prefix = vec![b'a'; 64];
+        let suffix = b"abc".to_vec();
+        let mut instances = midstate_instances(&prefix, &suffix);
+        instances[16] = Fr::from(2);
+        let circuit = MidstateCircuit::<Fr> { prefix, suffix, precomputed: true, _marker: PhantomData };
+        let prover = MockProver::run(17, &circuit, vec![instances]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_sha2_rlc_lookup() {
+        let (inputs, outputs) = INPUTS_OUTPUTS.clone();
+        let lookups = inputs.iter().zip(outputs.iter())
+            .map(|(input, output)| (input.clone(), output.as_bytes().to_vec(), precompile_gas(input.len())))
+            .collect();
+        let circuit = RlcLookupCircuit::<Fr> { inputs, lookups, _marker: PhantomData };
+        let prover = MockProver::run(17, &circuit, vec![vec![]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_sha2_rlc_lookup_wrong_output() {
+        let (inputs, outputs) = INPUTS_OUTPUTS.clone();
+        let lookups = vec![(inputs[1].clone(), outputs[0].as_bytes().to_vec(), precompile_gas(inputs[1].len()))];
+        let circuit = RlcLookupCircuit::<Fr> { inputs, lookups, _marker: PhantomData };
+        let prover = MockProver::run(17, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_sha2_gas_lookup() {
+        // Lengths on both sides of word boundaries, over several blocks.
+        let inputs: Vec<Vec<u8>> = [0, 1, 32, 33, 64, 100].iter().map(|len| vec![b'a'; *len]).collect();
+        let lookups = inputs.iter()
+            .map(|input| (input.clone(), Sha256Trace::new(input).digest().to_vec(), precompile_gas(input.len())))
+            .collect();
+        let circuit = RlcLookupCircuit::<Fr> { inputs, lookups, _marker: PhantomData };
+        let prover = MockProver::run(17, &circuit, vec![vec![]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_sha2_gas_lookup_wrong_gas() {
+        let (inputs, outputs) = INPUTS_OUTPUTS.clone();
+        let lookups = vec![(inputs[2].clone(), outputs[2].as_bytes().to_vec(), precompile_gas(inputs[2].len()) + 12)];
+        let circuit = RlcLookupCircuit::<Fr> { inputs, lookups, _marker: PhantomData };
+        let prover = MockProver::run(17, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
 }
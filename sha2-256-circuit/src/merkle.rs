@@ -0,0 +1,302 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter},
+    plonk::Error,
+};
+
+use crate::{Sha2Config, Sha256CompressionGadget};
+use crate::regions::table::TABLE_ROWS_PER_BLOCK;
+use crate::utils::{padding_words, IV};
+use crate::word::AssignedWord;
+
+/// How the two 32-byte children of a node are hashed into their parent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MerkleHashing {
+    /// Ethereum SSZ style: the 64-byte node is a single block, compressed from
+    /// the IV without the padding block.
+    Ssz,
+    /// Bitcoin style: `sha256(sha256(left || right))`, padding included.
+    Bitcoin,
+}
+
+/// Verifies Merkle inclusion proofs over SHA-256 nodes, compressing the
+/// concatenated children of every node with `Sha256CompressionGadget`.
+///
+/// The gadget runs on the columns, gates and lookups of a `Sha2Config`, whose
+/// tables must be loaded with `Sha2Config::load_tables`. Nodes are the eight
+/// words of a digest, as returned by `Sha256CompressionGadget::compress`, and
+/// are always hashed from the SHA-256 IV, whatever the mode of the config.
+#[derive(Clone, Debug)]
+pub struct MerklePathGadget<F: FieldExt> {
+    config: Sha2Config<F>,
+    hashing: MerkleHashing,
+}
+
+impl<F: FieldExt> MerklePathGadget<F> {
+    pub fn construct(config: Sha2Config<F>, hashing: MerkleHashing) -> Self {
+        Self { config, hashing }
+    }
+
+    /// Computes the root of the path from `leaf` through `siblings`, from the
+    /// leaf level up. The current node is the left child of its parent when
+    /// the matching cell of `directions` is 0, and the right child when it is
+    /// 1, which is constrained to be boolean.
+    pub fn root(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        leaf: [AssignedWord<F>; 8],
+        siblings: &[[AssignedWord<F>; 8]],
+        directions: &[AssignedCell<F, F>],
+    ) -> Result<[AssignedWord<F>; 8], Error> {
+        if siblings.len() != directions.len() {
+            return Err(Error::Synthesis);
+        }
+        let gadget = Sha256CompressionGadget::construct(self.config.clone());
+
+        let mut node = leaf;
+        for (level, (sibling, direction)) in siblings.iter().zip(directions.iter()).enumerate() {
+            let (left, right) = layouter.assign_region(
+                || format!("Merkle level {}", level),
+                |mut region| self.config.cond_swap.assign(&mut region, direction, &node, sibling, 0)
+            )?;
+            let mut block = left;
+            block.extend(right);
+            node = self.hash_node(layouter, &gadget, block)?;
+        }
+        Ok(node)
+    }
+
+    /// Computes the root of the path and constrains it to `root`.
+    pub fn verify(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        leaf: [AssignedWord<F>; 8],
+        siblings: &[[AssignedWord<F>; 8]],
+        directions: &[AssignedCell<F, F>],
+        root: &[AssignedWord<F>; 8],
+    ) -> Result<(), Error> {
+        let computed = self.root(layouter, leaf, siblings, directions)?;
+        layouter.assign_region(
+            || "Merkle root",
+            |mut region| {
+                for (computed, root) in computed.iter().zip(root.iter()) {
+                    region.constrain_equal(computed.lo.cell(), root.lo.cell())?;
+                    region.constrain_equal(computed.hi.cell(), root.hi.cell())?;
+                }
+                Ok(())
+            }
+        )
+    }
+
+    /// Assigns the SHA-256 IV, rather than the IV of the mode of the config.
+    fn iv(
+        layouter: &mut impl Layouter<F>,
+        gadget: &Sha256CompressionGadget<F>,
+    ) -> Result<[AssignedWord<F>; 8], Error> {
+        let iv = gadget.constant_words(layouter, &IV)?;
+        iv.try_into().map_err(|_| Error::Synthesis)
+    }
+
+    /// Hashes the 16 words of a node into its parent.
+    fn hash_node(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        gadget: &Sha256CompressionGadget<F>,
+        block: Vec<AssignedWord<F>>,
+    ) -> Result<[AssignedWord<F>; 8], Error> {
+        let block = block.try_into().map_err(|_| Error::Synthesis)?;
+        let iv = Self::iv(layouter, gadget)?;
+        let state = gadget.compress(layouter, iv, block)?;
+        match self.hashing {
+            MerkleHashing::Ssz => Ok(state),
+            MerkleHashing::Bitcoin => {
+                let padding = padding_words(TABLE_ROWS_PER_BLOCK);
+                let padding = gadget.constant_words(layouter, &padding)?;
+                let state = gadget.compress(layouter, state, padding.try_into().map_err(|_| Error::Synthesis)?)?;
+
+                let mut block = state.to_vec();
+                block.extend(gadget.constant_words(layouter, &padding_words(32))?);
+                let iv = Self::iv(layouter, gadget)?;
+                gadget.compress(layouter, iv, block.try_into().map_err(|_| Error::Synthesis)?)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::marker::PhantomData;
+    use crate::{BlockTrace, Sha2Mode, Sha2Table, Sha256Trace, Word32};
+    use halo2_proofs::circuit::{Region, SimpleFloorPlanner, Value};
+    use halo2_proofs::plonk::{Advice, Circuit, Column, ConstraintSystem};
+    use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr as F};
+
+    #[derive(Clone)]
+    struct MerkleCircuit<F: FieldExt> {
+        hashing: MerkleHashing,
+        leaf: [u32; 8],
+        siblings: Vec<[u32; 8]>,
+        directions: Vec<bool>,
+        root: [u32; 8],
+        _marker: PhantomData<F>,
+    }
+
+    /// Assigns the words of a node on the 16 rows of `column` from `offset`.
+    fn assign_node<F: FieldExt>(
+        region: &mut Region<F>,
+        column: Column<Advice>,
+        node: &[u32; 8],
+        offset: usize,
+    ) -> Result<[AssignedWord<F>; 8], Error> {
+        let mut words = vec![];
+        for (i, word) in node.iter().enumerate() {
+            let word = Word32::from(*word);
+            let lo = region.assign_advice(|| "node lo", column, offset + 2 * i, || Value::known(F::from(word.lo as u64)))?;
+            let hi = region.assign_advice(|| "node hi", column, offset + 2 * i + 1, || Value::known(F::from(word.hi as u64)))?;
+            words.push(AssignedWord::new(lo, hi, Value::known(word)));
+        }
+        words.try_into().map_err(|_| Error::Synthesis)
+    }
+
+    impl<F: FieldExt> Circuit<F> for MerkleCircuit<F> {
+        type Config = Sha2Config<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = Sha2Table::construct(meta);
+            Sha2Config::configure(meta, table, 1)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            config.load_tables(&mut layouter)?;
+            let (leaf, siblings, directions, root) = layouter.assign_region(
+                || "Merkle path",
+                |mut region| {
+                    let leaf = assign_node(&mut region, config.cols[3], &self.leaf, 0)?;
+                    let root = assign_node(&mut region, config.cols[3], &self.root, 16)?;
+                    let mut siblings = vec![];
+                    let mut directions = vec![];
+                    for (i, (sibling, direction)) in self.siblings.iter().zip(self.directions.iter()).enumerate() {
+                        siblings.push(assign_node(&mut region, config.cols[3], sibling, 32 + 16 * i)?);
+                        directions.push(region.assign_advice(
+                            || "direction", config.cols[4], i, || Value::known(F::from(*direction as u64))
+                        )?);
+                    }
+                    Ok((leaf, siblings, directions, root))
+                }
+            )?;
+
+            let gadget = MerklePathGadget::construct(config, self.hashing);
+            gadget.verify(&mut layouter, leaf, &siblings, &directions, &root)
+        }
+    }
+
+    /// A `MerkleCircuit` on a config set up with `Sha2Mode::Sha224`.
+    #[derive(Clone)]
+    struct Sha224MerkleCircuit<F: FieldExt>(MerkleCircuit<F>);
+
+    impl<F: FieldExt> Circuit<F> for Sha224MerkleCircuit<F> {
+        type Config = Sha2Config<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = Sha2Table::construct(meta);
+            Sha2Config::configure_with_mode(meta, table, 1, Sha2Mode::Sha224)
+        }
+
+        fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
+            self.0.synthesize(config, layouter)
+        }
+    }
+
+    fn digest_words(digest: [u8; 32]) -> [u32; 8] {
+        let mut words = [0; 8];
+        for (word, bytes) in words.iter_mut().zip(digest.chunks(4)) {
+            *word = u32::from_be_bytes(bytes.try_into().expect("SHA-256 word is 4-bytes"));
+        }
+        words
+    }
+
+    fn node_bytes(left: &[u32; 8], right: &[u32; 8]) -> Vec<u8> {
+        left.iter().chain(right.iter()).flat_map(|word| word.to_be_bytes()).collect()
+    }
+
+    fn parent(hashing: MerkleHashing, left: &[u32; 8], right: &[u32; 8]) -> [u32; 8] {
+        let node = node_bytes(left, right);
+        match hashing {
+            MerkleHashing::Ssz => BlockTrace::new(&node, IV).h_out,
+            MerkleHashing::Bitcoin => {
+                let inner = Sha256Trace::new(&node).digest();
+                digest_words(Sha256Trace::new(&inner).digest())
+            }
+        }
+    }
+
+    /// A path of `directions.len()` levels from a leaf, with its root.
+    fn path(hashing: MerkleHashing, directions: &[bool]) -> MerkleCircuit<F> {
+        let leaf = digest_words(Sha256Trace::new(b"leaf").digest());
+        let mut node = leaf;
+        let mut siblings = vec![];
+        for (level, direction) in directions.iter().enumerate() {
+            let sibling = digest_words(Sha256Trace::new(&[level as u8]).digest());
+            node = if *direction {
+                parent(hashing, &sibling, &node)
+            } else {
+                parent(hashing, &node, &sibling)
+            };
+            siblings.push(sibling);
+        }
+        MerkleCircuit {
+            hashing,
+            leaf,
+            siblings,
+            directions: directions.to_vec(),
+            root: node,
+            _marker: PhantomData,
+        }
+    }
+
+    #[test]
+    fn test_merkle_path() {
+        for hashing in [MerkleHashing::Ssz, MerkleHashing::Bitcoin] {
+            let circuit = path(hashing, &[false, true, true]);
+            let prover = MockProver::run(17, &circuit, vec![vec![]]).unwrap();
+            assert_eq!(prover.verify(), Ok(()), "{:?}", hashing);
+        }
+    }
+
+    #[test]
+    fn test_merkle_path_wrong_direction() {
+        let mut circuit = path(MerkleHashing::Ssz, &[false, true, true]);
+        circuit.directions[1] = false;
+        let prover = MockProver::run(17, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_merkle_path_wrong_hashing() {
+        let mut circuit = path(MerkleHashing::Ssz, &[true, false]);
+        circuit.hashing = MerkleHashing::Bitcoin;
+        let prover = MockProver::run(17, &circuit, vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_merkle_path_sha224_config() {
+        for hashing in [MerkleHashing::Ssz, MerkleHashing::Bitcoin] {
+            let circuit = Sha224MerkleCircuit(path(hashing, &[true, false]));
+            let prover = MockProver::run(17, &circuit, vec![vec![]]).unwrap();
+            assert_eq!(prover.verify(), Ok(()), "{:?}", hashing);
+        }
+    }
+}
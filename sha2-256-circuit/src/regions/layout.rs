@@ -0,0 +1,161 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    plonk::{ConstraintSystem, Error},
+};
+
+use crate::{Sha2Config, Sha2Mode, Sha2Table};
+use crate::gates::block_state::BlockStateConfig;
+use crate::regions::{
+    compression::CompressionChip,
+    message_schedule::MessageScheduleChip,
+    table::TABLE_ROWS_PER_BLOCK,
+};
+use crate::spread_table::SpreadTableBits;
+use crate::utils::pad_message;
+
+/// Offsets of the second hash of SHA256d within the region of a block: its
+/// message schedule, its compression followed by the IV it starts from, and
+/// the selection of the digest of the block.
+#[derive(Debug, Clone, Copy)]
+pub struct DoubleLayout {
+    pub schedule: usize,
+    pub compression: usize,
+    pub select: usize,
+    pub rows: usize,
+}
+
+impl DoubleLayout {
+    pub fn new<F: FieldExt>(offset: usize) -> Self {
+        let schedule = offset;
+        let compression = schedule + MessageScheduleChip::<F>::rows();
+        let select = compression + CompressionChip::<F>::rows() + 16;
+        let rows = select + 16 - offset;
+
+        Self {
+            schedule,
+            compression,
+            select,
+            rows,
+        }
+    }
+}
+
+/// Offsets of the chips within the region of a block, computed from their
+/// row footprints.
+///
+/// The message schedule comes first, followed by the compression, the
+/// selection of the initial state, the second hash in `Sha2Mode::Sha256d`
+/// and the digest words. The table rows of the block live in their own
+/// columns and start at the first row of the region.
+#[derive(Debug, Clone, Copy)]
+pub struct BlockLayout {
+    pub schedule: usize,
+    pub compression: usize,
+    pub init: usize,
+    pub double: Option<DoubleLayout>,
+    pub words: usize,
+    pub table: usize,
+    pub rows: usize,
+}
+
+impl BlockLayout {
+    pub fn new<F: FieldExt>(mode: Sha2Mode) -> Self {
+        let schedule = 0;
+        let compression = schedule + MessageScheduleChip::<F>::rows();
+        let init = compression + CompressionChip::<F>::rows();
+        let mut words = init + BlockStateConfig::<F>::init_rows();
+        let mut double = None;
+        if mode == Sha2Mode::Sha256d {
+            let layout = DoubleLayout::new::<F>(words);
+            words += layout.rows;
+            double = Some(layout);
+        }
+        let rows = words + BlockStateConfig::<F>::out_rows();
+        debug_assert!(TABLE_ROWS_PER_BLOCK <= rows);
+
+        Self {
+            schedule,
+            compression,
+            init,
+            double,
+            words,
+            table: 0,
+            rows,
+        }
+    }
+}
+
+/// Layout of a circuit hashing up to `max_blocks` blocks, whose spread table
+/// has to fit in the circuit as well.
+///
+/// `unusable_rows` are the rows halo2 reserves at the end of every column for
+/// blinding, derived from the constraint system of the config.
+#[derive(Debug, Clone, Copy)]
+pub struct Sha2Layout {
+    pub block: BlockLayout,
+    pub max_blocks: usize,
+    pub spread: SpreadTableBits,
+    pub unusable_rows: usize,
+}
+
+impl Sha2Layout {
+    pub fn new<F: FieldExt>(max_blocks: usize) -> Self {
+        Self::with_mode::<F>(max_blocks, Sha2Mode::Sha256)
+    }
+
+    pub fn with_mode<F: FieldExt>(max_blocks: usize, mode: Sha2Mode) -> Self {
+        Self::with_spread::<F>(max_blocks, mode, SpreadTableBits::Sixteen)
+    }
+
+    /// Layout of the config `Sha2Config::configure_with_spread` sets up with
+    /// the same arguments.
+    pub fn with_spread<F: FieldExt>(max_blocks: usize, mode: Sha2Mode, spread: SpreadTableBits) -> Self {
+        let mut meta = ConstraintSystem::<F>::default();
+        let table = Sha2Table::construct(&mut meta);
+        Sha2Config::configure_with_spread(&mut meta, table, max_blocks, mode, spread).layout()
+    }
+
+    /// Layout of a config whose gates and lookups are all configured in
+    /// `meta`.
+    pub fn configure<F: FieldExt>(
+        meta: &ConstraintSystem<F>,
+        max_blocks: usize,
+        mode: Sha2Mode,
+        spread: SpreadTableBits,
+    ) -> Self {
+        Self {
+            block: BlockLayout::new::<F>(mode),
+            max_blocks,
+            spread,
+            unusable_rows: meta.blinding_factors() + 1,
+        }
+    }
+
+    /// Number of rows used by the circuit, the spread table included.
+    pub fn rows(&self) -> usize {
+        (self.block.rows * self.max_blocks).max(self.spread.rows())
+    }
+
+    /// Smallest `k` the circuit fits in.
+    pub fn min_k(&self) -> u32 {
+        let mut k = 0;
+        while (1 << k) < self.rows() + self.unusable_rows {
+            k += 1;
+        }
+        k
+    }
+
+    /// Number of blocks needed to hash `inputs`.
+    pub fn num_blocks(inputs: &[Vec<u8>]) -> usize {
+        inputs.iter().map(|input| pad_message(input).len() / TABLE_ROWS_PER_BLOCK).sum()
+    }
+
+    /// Checks that `inputs` fit in the capacity of the circuit and that the
+    /// circuit fits in `2^k` rows.
+    pub fn check(&self, inputs: &[Vec<u8>], k: u32) -> Result<(), Error> {
+        if Self::num_blocks(inputs) > self.max_blocks || k < self.min_k() {
+            return Err(Error::NotEnoughRowsAvailable { current_k: k });
+        }
+        Ok(())
+    }
+}
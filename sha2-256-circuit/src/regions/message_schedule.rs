@@ -1,6 +1,6 @@
 use halo2_proofs::{
     arithmetic::FieldExt,
-    circuit::{AssignedCell, Region},
+    circuit::{AssignedCell, Region, Value},
     plonk::{Advice, Column, ConstraintSystem, Error},
 };
 
@@ -15,7 +15,9 @@ use crate::gates::{
     sigma_one_v_one::Sigma1V1Config,
     sigma_one_v_two::Sigma1V2Config,
 };
+use crate::spread_table::SpreadLookupConfig;
 use crate::utils::{sigma0_r, sigma1_r};
+use crate::word::{AssignedWord, Word32};
 
 #[derive(Debug, Clone)]
 pub struct MessageScheduleChip<F: FieldExt> {
@@ -44,16 +46,17 @@ impl<F: FieldExt> MessageScheduleChip<F> {
         a7: Column<Advice>,
         a8: Column<Advice>,
         a9: Column<Advice>,
+        spread: SpreadLookupConfig<F>,
     ) -> Self {
-        let sd0 = DecomposeZeroConfig::configure(meta, a0, a1, a2, a3, a4, a5);
-        let sd1 = DecomposeOneConfig::configure(meta, a0, a1, a2, a3, a4, a5);
-        let sd2 = DecomposeTwoConfig::configure(meta, a0, a1, a2, a3, a4, a5);
-        let sd3 = DecomposeThreeConfig::configure(meta, a0, a1, a2, a3, a4, a5);
+        let sd0 = DecomposeZeroConfig::configure(meta, a0, a1, a2, a3, a4, a5, spread);
+        let sd1 = DecomposeOneConfig::configure(meta, a0, a1, a2, a3, a4, a5, spread);
+        let sd2 = DecomposeTwoConfig::configure(meta, a0, a1, a2, a3, a4, a5, spread);
+        let sd3 = DecomposeThreeConfig::configure(meta, a0, a1, a2, a3, a4, a5, spread);
         let sw = WNewConfig::configure(meta, a5, a6, a7, a8, a9);
-        let ss0v1 = Sigma0V1Config::configure(meta, a0, a1, a2, a3, a4, a5, a6);
-        let ss0v2 = Sigma0V2Config::configure(meta, a0, a1, a2, a3, a4, a5, a6, a7);
-        let ss1v1 = Sigma1V1Config::configure(meta, a0, a1, a2, a3, a4, a5, a6);
-        let ss1v2 = Sigma1V2Config::configure(meta, a0, a1, a2, a3, a4, a5, a6, a7);
+        let ss0v1 = Sigma0V1Config::configure(meta, a0, a1, a2, a3, a4, a5, a6, spread);
+        let ss0v2 = Sigma0V2Config::configure(meta, a0, a1, a2, a3, a4, a5, a6, a7, spread);
+        let ss1v1 = Sigma1V1Config::configure(meta, a0, a1, a2, a3, a4, a5, a6, spread);
+        let ss1v2 = Sigma1V2Config::configure(meta, a0, a1, a2, a3, a4, a5, a6, a7, spread);
 
         Self {
             sd0,
@@ -68,50 +71,56 @@ impl<F: FieldExt> MessageScheduleChip<F> {
         }
     }
 
+    /// Number of rows used by `load`.
+    pub fn rows() -> usize {
+        547
+    }
+
+    /// Expands the 16 words of a block into the 64 words `W0..W63` of its
+    /// message schedule.
     pub fn load(
         &self,
         region: &mut Region<F>,
-        w: Vec<u16>,
+        w: Vec<Value<Word32>>,
         offset: usize,
-    ) -> Result<(Vec<u16>, Vec<AssignedCell<F, F>>), Error> {
+    ) -> Result<Vec<AssignedWord<F>>, Error> {
         let mut res = vec![];
-        let mut w_all: Vec<u16> = w.clone();
+        let mut w_all: Vec<Value<Word32>> = w.clone();
         let mut w_i: Vec<AssignedCell<F, F>> = vec![];
         let mut s0_w_i_15: Vec<AssignedCell<F, F>> = vec![];
         let mut s1_w_i_2: Vec<AssignedCell<F, F>> = vec![];
         // Input W0 and W16 Computation
-        let mut w_cell = self.sd0.assign_special_0(region, w_all[0], w_all[1], offset)?;
-        res.extend_from_slice(&w_cell);
+        let mut w_cell = self.sd0.assign_special_0(region, w_all[0], offset)?;
+        res.push(w_cell);
         let (r1, w16) = self.sw.assign(
-            region, w_all[0], w_all[1], w_all[2], w_all[3], 
-            w_all[18], w_all[19], w_all[28], w_all[29], offset+1
+            region, w_all[0], w_all[1], 
+            w_all[9], w_all[14], offset+1
         )?;
         w_i.push(r1[0].clone());
         s0_w_i_15.push(r1[1].clone());
         s0_w_i_15.push(r1[2].clone());
         s1_w_i_2.push(r1[3].clone());
         s1_w_i_2.push(r1[4].clone());
-        w_all.push((w16 & 0xFFFF) as u16);
-        w_all.push((w16 >> 16) as u16);
+        w_all.push(w16);
         // Input W1..13 and W17..29 Computation
         for i in 0..13 {
-            w_cell = self.sd0.assign(region, w_all[2*(i+1)], w_all[2*(i+1)+1], offset+6*i+2)?;
-            res.extend_from_slice(&w_cell);
-            let (temp, a, b) = self.sd1.assign(region, w_all[2*(i+1)], w_all[2*(i+1)+1], offset+6*i+2)?;
-            let (r_lo, r_hi) = sigma0_r(w_all[2*(i+1)], w_all[2*(i+1)+1]);
+            w_cell = self.sd0.assign(region, w_all[i+1], offset+6*i+2)?;
+            res.push(w_cell);
+            let (temp, a, b) = self.sd1.assign(region, w_all[i+1], offset+6*i+2)?;
+            let (r_lo, r_hi) = w_all[i+1].map(|w| sigma0_r(w.lo, w.hi)).unzip();
             self.ss0v1.assign(region, r_lo, r_hi, 
                 temp[1].clone(), temp[0].clone(), 
                 temp[2].clone(), temp[3].clone(), 
                 s0_w_i_15[2*i].clone(), 
                 s0_w_i_15[2*i+1].clone(), 
-                b as u16, a as u16, offset+6*i+2+3
+                b, a, offset+6*i+2+3
             )?;
             let (res, w_n) = self.sw.assign(
                 region, 
-                w_all[2*(i+1)], w_all[2*(i+1)+1], 
-                w_all[2*(i+2)], w_all[2*(i+2)+1], 
-                w_all[2*(i+10)], w_all[2*(i+10)+1], 
-                w_all[2*(i+15)], w_all[2*(i+15)+1],
+                w_all[i+1], 
+                w_all[i+2], 
+                w_all[i+10], 
+                w_all[i+15],
                 offset+6*i+2+1
             )?;
             w_i.push(res[0].clone());
@@ -119,50 +128,50 @@ impl<F: FieldExt> MessageScheduleChip<F> {
             s0_w_i_15.push(res[2].clone());
             s1_w_i_2.push(res[3].clone());
             s1_w_i_2.push(res[4].clone());
-            w_all.push((w_n & 0xFFFF) as u16);
-            w_all.push((w_n >> 16) as u16);
+            w_all.push(w_n);
         }
         // Input W14..48 and W30..64
         for i in 0..35 {
             if i < 2 {
                 w_cell = self.sd0.assign(
                     region, 
-                    w_all[2*(i+14)], w_all[2*(i+14)+1],
+                    w_all[i+14],
                     offset+11*i + 80 + 1
                 )?;
-                res.extend_from_slice(&w_cell);
+                res.push(w_cell);
             } else {
                 w_cell = self.sd0.assign_special_3(
-                    region, w_all[2*(i+14)], w_all[2*(i+14)+1], 
+                    region, w_all[i+14], 
                     w_i[i-2].clone(),
                     offset+11*i + 80 + 1
                 )?;
-                res.extend_from_slice(&w_cell);
+                res.push(w_cell);
             }
 
-            let (temp, a, b, c, e, f) = self.sd2.assign(region, w_all[2*(i+14)], w_all[2*(i+14)+1], offset+11*i + 80 + 1)?;
-            let (r_lo, r_hi) = sigma0_r(w_all[2*(i+14)], w_all[2*(i+14)+1]);
-            let (r_lo_1, r_hi_1) = sigma1_r(w_all[2*(i+14)], w_all[2*(i+14)+1]);
+            let (temp, a, b, c, e, f) = self.sd2.assign(region, w_all[i+14], offset+11*i + 80 + 1)?;
+            let w_val = w_all[i+14];
+            let (r_lo, r_hi) = w_val.map(|w| sigma0_r(w.lo, w.hi)).unzip();
+            let (r_lo_1, r_hi_1) = w_val.map(|w| sigma1_r(w.lo, w.hi)).unzip();
             let temp1 = self.ss0v2.assign(
                 region, r_lo, r_hi, 
-                temp[1].clone(), temp[0].clone(), temp[2].clone(), b as u16, temp[3].clone(), a as u16, temp[4].clone(), 
-                c as u16, e as u16, f as u16, 
+                temp[1].clone(), temp[0].clone(), temp[2].clone(), b, temp[3].clone(), a, temp[4].clone(), 
+                c, e, f, 
                 s0_w_i_15[2*(i+13)].clone(), s0_w_i_15[2*(i+13)+1].clone(), 
                 offset+11*i + 80 + 4
             )?;
             self.ss1v2.assign(
                 region, r_lo_1, r_hi_1, 
-                temp1[0].clone(), temp1[1].clone(), temp[2].clone(), b as u16, temp[3].clone(), a as u16, temp[4].clone(),
-                c as u16, e as u16, f as u16, 
+                temp1[0].clone(), temp1[1].clone(), temp[2].clone(), b, temp[3].clone(), a, temp[4].clone(),
+                c, e, f, 
                 s1_w_i_2[2*i].clone(), s1_w_i_2[2*i+1].clone(),
                 offset+11*i + 80 + 8
             )?;
             let (res, w_n) = self.sw.assign(
                 region, 
-                w_all[2*(i+14)], w_all[2*(i+14)+1], 
-                w_all[2*(i+15)], w_all[2*(i+15)+1], 
-                w_all[2*(i+23)], w_all[2*(i+23)+1], 
-                w_all[2*(i+28)], w_all[2*(i+28)+1],
+                w_all[i+14], 
+                w_all[i+15], 
+                w_all[i+23], 
+                w_all[i+28],
                 offset+11*i + 80 + 2
             )?;
             w_i.push(res[0].clone());
@@ -170,28 +179,27 @@ impl<F: FieldExt> MessageScheduleChip<F> {
             s0_w_i_15.push(res[2].clone());
             s1_w_i_2.push(res[3].clone());
             s1_w_i_2.push(res[4].clone());
-            w_all.push((w_n & 0xFFFF) as u16);
-            w_all.push((w_n >> 16) as u16);
+            w_all.push(w_n);
         }
 
         // Input W49..61
         for i in 0..13 {
             w_cell = self.sd0.assign_special_3(
-                region, w_all[2*(i+49)], w_all[2*(i+49)+1],
+                region, w_all[i+49],
                 w_i[i+33].clone(),
                 offset+6*i + 465
             )?;
-            res.extend_from_slice(&w_cell);
-            let (r_lo, r_hi) = sigma1_r(w_all[2*(i+49)], w_all[2*(i+49)+1]);
+            res.push(w_cell);
+            let (r_lo, r_hi) = w_all[i+49].map(|w| sigma1_r(w.lo, w.hi)).unzip();
             let (temp, b, c) = self.sd3.assign(
                 region, 
-                w_all[2*(i + 49)], w_all[2*(i+49)+1],
+                w_all[i+49],
                 offset+6*i + 465
             )?;
             self.ss1v1.assign(
                 region, 
                 r_lo, r_hi, 
-                temp[1].clone(), temp[0].clone(), temp[3].clone(), b as u16, c as u16,
+                temp[1].clone(), temp[0].clone(), temp[3].clone(), b, c,
                 temp[2].clone(), s1_w_i_2[2*(i+35)].clone(), s1_w_i_2[2*(i+35)+1].clone(), 
                 offset+6*i + 465 + 3
             )?;
@@ -199,19 +207,19 @@ impl<F: FieldExt> MessageScheduleChip<F> {
 
         // Input W62..63
         w_cell = self.sd0.assign_special_3(
-            region, w_all[2*62], w_all[2*62+1],
+            region, w_all[62],
             w_i[46].clone(),
             offset+543
         )?;
-        res.extend_from_slice(&w_cell);
+        res.push(w_cell);
         w_cell = self.sd0.assign_special_3(
-            region, w_all[2*63], w_all[2*63+1], 
+            region, w_all[63], 
             w_i[47].clone(),
             offset+545
         )?;
-        res.extend_from_slice(&w_cell);
+        res.push(w_cell);
 
-        Ok((w_all, res))
+        Ok(res)
     }
 
 }
@@ -221,17 +229,16 @@ mod tests {
 
     use super::*;
     use std::marker::PhantomData;
-    use crate::spread_table::SpreadTableConfig;
+    use crate::spread_table::SpreadTableBits;
     use halo2_proofs::circuit::{Layouter, SimpleFloorPlanner};
     use halo2_proofs::plonk::{Column, Advice, Circuit};
-    use halo2_proofs::poly::Rotation;
     use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr as F};
 
 
     #[derive(Debug, Clone)]
     struct MessageScheduleConfig<F: FieldExt, const NUM_BITS: usize> {
         message_schedule_chip: MessageScheduleChip<F>,
-        table_config: SpreadTableConfig<F, NUM_BITS>,
+        spread: SpreadLookupConfig<F>,
     }
     
     impl<F: FieldExt, const NUM_BITS: usize> MessageScheduleConfig<F, NUM_BITS> {
@@ -243,28 +250,16 @@ mod tests {
                 cols.push(meta.advice_column());
                 meta.enable_equality(cols[i]);
             }
-            let table_config = SpreadTableConfig::configure(meta);
+            let bits = if NUM_BITS == 8 { SpreadTableBits::Eight } else { SpreadTableBits::Sixteen };
+            let spread = SpreadLookupConfig::configure(meta, cols[0], cols[1], cols[2], bits);
             let message_schedule_chip = MessageScheduleChip::configure(
                 meta, 
                 cols[0], cols[1], cols[2], cols[3], cols[4], 
-                cols[5], cols[6], cols[7], cols[8], cols[9]
+                cols[5], cols[6], cols[7], cols[8], cols[9], spread
             );
 
-            meta.lookup("Spread Table", |meta| {
-                let it = meta.query_advice(cols[0], Rotation::cur());
-                let ita = meta.query_advice(cols[1], Rotation::cur());
-                let is = meta.query_advice(cols[2], Rotation::cur());
-
-                vec![
-                    (it, table_config.tag),
-                    (ita, table_config.table),
-                    (is, table_config.spread),
-                ]
-            });
-
-
             Self {
-                table_config,
+                spread,
                 message_schedule_chip
             }
 
@@ -273,7 +268,7 @@ mod tests {
         pub fn assign(
             &self,
             mut layouter: impl Layouter<F>,
-            w: Vec<u16>,
+            w: Vec<Value<Word32>>,
         ) -> Result<(), Error> {
             layouter.assign_region(
                 || "Message Schedule", 
@@ -289,7 +284,7 @@ mod tests {
 
     #[derive(Default, Clone)]
     struct MessageScheduleCircuit<F: FieldExt, const NUM_BITS: usize> {
-        w: Vec<u16>,
+        w: Vec<Value<Word32>>,
         _marker: PhantomData<F>,
     }
 
@@ -298,7 +293,10 @@ mod tests {
         type FloorPlanner = SimpleFloorPlanner;
         
         fn without_witnesses(&self) -> Self {
-            Self::default()
+            Self {
+                w: vec![Value::unknown(); self.w.len()],
+                _marker: PhantomData,
+            }
         }
 
         fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
@@ -306,7 +304,7 @@ mod tests {
         }
 
         fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
-            config.table_config.load(&mut layouter)?;
+            config.spread.load(&mut layouter)?;
             config.assign(
                 layouter.namespace(|| "Load Message Schedules"), 
                 self.w.clone(),
@@ -333,7 +331,7 @@ mod tests {
         }
 
         let circuit = MessageScheduleCircuit::<F, 16> {
-            w,
+            w: w.chunks(2).map(|w| Value::known(Word32::new(w[0], w[1]))).collect(),
             _marker: PhantomData
         };
         
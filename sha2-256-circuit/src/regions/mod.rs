@@ -1,2 +1,4 @@
 pub mod message_schedule;
-pub mod compression;
\ No newline at end of file
+pub mod compression;
+pub mod table;
+pub mod layout;
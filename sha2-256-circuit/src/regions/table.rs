@@ -0,0 +1,784 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Expression, SecondPhase, Selector, Error},
+    poly::Rotation,
+};
+
+use crate::{Sha2Mode, Sha2Table};
+use crate::gates::byte_decompose::ByteDecomposeConfig;
+use crate::utils::create_value_2_check;
+use crate::word::{AssignedWord, Word32};
+
+/// Number of table rows reserved for every 512-bit block, one per message byte.
+pub const TABLE_ROWS_PER_BLOCK: usize = 64;
+
+/// First row of the length field of a block, whose 8 bytes end the block.
+const LENGTH_FIELD_ROW: usize = TABLE_ROWS_PER_BLOCK - 8;
+
+/// Number of bytes in a word of the EVM, the unit the precompile gas is
+/// charged by.
+const GAS_WORD_BYTES: usize = 32;
+
+/// Base and per word gas of the SHA256 precompile.
+const GAS_BASE: u64 = 60;
+const GAS_PER_WORD: u64 = 12;
+
+/// Assigns the table rows of the padded message blocks and binds them to the
+/// message words of the message schedule.
+///
+/// Every row carries `is_padding`, set from the first padding byte on, and
+/// `pad_prev`, the flag of the previous byte of the message. The first rows of
+/// a block also hold in `carry` whether the block starts a message, followed by
+/// `index`, `input_len`, `is_padding`, `tag`, `is_final`, `words` and `id` of
+/// the last row of the previous block, which chains the rows of a message
+/// across blocks: a block continuing a message follows a block that is not
+/// final. The `tag` of a call is boolean, and can only be set in
+/// `Sha2Mode::Sha256d`.
+///
+/// The `id` of a call is held on all of its rows, and a block starting a call
+/// increments the `id` of the previous block, from 0 before the first block,
+/// so the calls are numbered `1, 2, ...` and no two calls share an `id`.
+///
+/// Only the final block of a message publishes its digest: `digest_hi` and
+/// `digest_lo` are zero on the last row of any other block.
+///
+/// `input_rlc` accumulates the message bytes of `input` row by row, from the
+/// value of the last row of the previous block, held in `rlc_carry`, when the
+/// block continues a message. The digest is decomposed into bytes on the last
+/// 32 rows of every block, accumulated in `output_acc`, and the accumulator is
+/// published to `output_rlc` by the final block, so that the final row holds
+/// `(input_rlc, input_len, output_rlc)` of the call.
+///
+/// `words` counts the 32-byte words of the message holding at least one
+/// message byte, incremented on the first row of every word, rows 0 and 32 of
+/// a block, and carried across blocks like `index`. `gas` is derived from it
+/// on every row as `60 + 12 * words`.
+#[derive(Debug, Clone)]
+pub struct TableChip<F: FieldExt> {
+    table: Sha2Table,
+    is_padding: Column<Advice>,
+    pad_prev: Column<Advice>,
+    carry: Column<Advice>,
+    digest_limb: Column<Advice>,
+    rlc_carry: Column<Advice>,
+    output_acc: Column<Advice>,
+    words: Column<Advice>,
+    s_table: Selector,
+    s_start: Selector,
+    s_link: Selector,
+    s_body: Selector,
+    s_length: Selector,
+    s_digest: Selector,
+    s_output_first: Selector,
+    s_output_step: Selector,
+    s_word: Selector,
+    s_word_hold: Selector,
+    bytes: ByteDecomposeConfig<F>,
+    digest_bytes: ByteDecomposeConfig<F>,
+    digest_words: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> TableChip<F> {
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        table: Sha2Table,
+        mode: Sha2Mode,
+    ) -> Self {
+        let digest_words = mode.digest_words();
+        let is_padding = meta.advice_column();
+        let pad_prev = meta.advice_column();
+        let carry = meta.advice_column();
+        let word_limb = meta.advice_column();
+        let digest_limb = meta.advice_column();
+        let words = meta.advice_column();
+        let digest_byte = meta.advice_column();
+        let digest_byte_limb = meta.advice_column();
+        let rlc_carry = meta.advice_column_in(SecondPhase);
+        let output_acc = meta.advice_column_in(SecondPhase);
+        meta.enable_equality(table.id);
+        meta.enable_equality(table.input);
+        meta.enable_equality(table.tag);
+        meta.enable_equality(table.index);
+        meta.enable_equality(table.input_len);
+        meta.enable_equality(is_padding);
+        meta.enable_equality(carry);
+        meta.enable_equality(word_limb);
+        meta.enable_equality(digest_limb);
+        meta.enable_equality(digest_byte_limb);
+        meta.enable_equality(rlc_carry);
+        meta.enable_equality(words);
+
+        let s_table = meta.selector();
+        let s_start = meta.selector();
+        let s_link = meta.selector();
+        let s_body = meta.selector();
+        let s_length = meta.selector();
+        let s_digest = meta.selector();
+        let s_output_first = meta.selector();
+        let s_output_step = meta.selector();
+        let s_word = meta.selector();
+        let s_word_hold = meta.selector();
+
+        meta.create_gate(
+            "Table Row",
+            |meta| {
+                let s_table = meta.query_selector(s_table);
+                let tag = meta.query_advice(table.tag, Rotation::cur());
+                let is_final = meta.query_advice(table.is_final, Rotation::cur());
+                let is_padding = meta.query_advice(is_padding, Rotation::cur());
+                let pad_prev = meta.query_advice(pad_prev, Rotation::cur());
+                let index = meta.query_advice(table.index, Rotation::cur());
+                let input_len = meta.query_advice(table.input_len, Rotation::cur());
+                let input = meta.query_advice(table.input, Rotation::cur());
+
+                // Padding starts at most once, at byte `input_len`, with 0x80.
+                let pad_start = is_padding.clone() - pad_prev;
+
+                let tag_constraint = match mode {
+                    Sha2Mode::Sha256d => create_value_2_check(tag),
+                    _ => tag,
+                };
+
+                vec![
+                    s_table.clone() * tag_constraint,
+                    s_table.clone() * create_value_2_check(is_final),
+                    s_table.clone() * create_value_2_check(is_padding),
+                    s_table.clone() * create_value_2_check(pad_start.clone()),
+                    s_table.clone() * pad_start.clone() * (input - Expression::Constant(F::from(0x80))),
+                    s_table * pad_start * (index - input_len),
+                ]
+            }
+        );
+
+        meta.create_gate(
+            "Table Block Start",
+            |meta| {
+                let s_start = meta.query_selector(s_start);
+                let is_first = meta.query_advice(carry, Rotation::cur());
+                let prev_index = meta.query_advice(carry, Rotation(1));
+                let prev_input_len = meta.query_advice(carry, Rotation(2));
+                let prev_is_padding = meta.query_advice(carry, Rotation(3));
+                let prev_tag = meta.query_advice(carry, Rotation(4));
+                let prev_is_final = meta.query_advice(carry, Rotation(5));
+                let prev_id = meta.query_advice(carry, Rotation(7));
+                let pad_prev = meta.query_advice(pad_prev, Rotation::cur());
+                let index = meta.query_advice(table.index, Rotation::cur());
+                let input_len = meta.query_advice(table.input_len, Rotation::cur());
+                let tag = meta.query_advice(table.tag, Rotation::cur());
+                let id = meta.query_advice(table.id, Rotation::cur());
+
+                let not_first = Expression::Constant(F::one()) - is_first.clone();
+
+                vec![
+                    s_start.clone() * (id - prev_id - is_first),
+                    s_start.clone() * (pad_prev - not_first.clone() * prev_is_padding),
+                    s_start.clone() * (index - not_first.clone() * (prev_index + Expression::Constant(F::one()))),
+                    s_start.clone() * not_first.clone() * (input_len - prev_input_len),
+                    s_start.clone() * not_first.clone() * (tag - prev_tag),
+                    s_start * not_first * prev_is_final,
+                ]
+            }
+        );
+
+        meta.create_gate(
+            "Table Row Link",
+            |meta| {
+                let s_link = meta.query_selector(s_link);
+                let pad_prev = meta.query_advice(pad_prev, Rotation::cur());
+                let is_padding_prev = meta.query_advice(is_padding, Rotation::prev());
+                let index = meta.query_advice(table.index, Rotation::cur());
+                let index_prev = meta.query_advice(table.index, Rotation::prev());
+                let input_len = meta.query_advice(table.input_len, Rotation::cur());
+                let input_len_prev = meta.query_advice(table.input_len, Rotation::prev());
+                let tag = meta.query_advice(table.tag, Rotation::cur());
+                let tag_prev = meta.query_advice(table.tag, Rotation::prev());
+                let is_final_prev = meta.query_advice(table.is_final, Rotation::prev());
+                let id = meta.query_advice(table.id, Rotation::cur());
+                let id_prev = meta.query_advice(table.id, Rotation::prev());
+
+                vec![
+                    s_link.clone() * (id - id_prev),
+                    s_link.clone() * (pad_prev - is_padding_prev),
+                    s_link.clone() * (index - index_prev - Expression::Constant(F::one())),
+                    s_link.clone() * (input_len - input_len_prev),
+                    s_link.clone() * (tag - tag_prev),
+                    s_link * is_final_prev,
+                ]
+            }
+        );
+
+        meta.create_gate(
+            "Table Input RLC",
+            |meta| {
+                let s_start = meta.query_selector(s_start);
+                let s_link = meta.query_selector(s_link);
+                let is_first = meta.query_advice(carry, Rotation::cur());
+                let rlc_carry = meta.query_advice(rlc_carry, Rotation::cur());
+                let input_rlc_prev = meta.query_advice(table.input_rlc, Rotation::prev());
+                let input_rlc = meta.query_advice(table.input_rlc, Rotation::cur());
+                let is_padding = meta.query_advice(is_padding, Rotation::cur());
+                let input = meta.query_advice(table.input, Rotation::cur());
+                let r = meta.query_challenge(table.challenge);
+
+                // A message byte is accumulated, a padding byte is skipped.
+                let next_rlc = |prev: Expression<F>| {
+                    is_padding.clone() * prev.clone() +
+                    (Expression::Constant(F::one()) - is_padding.clone()) * (prev * r.clone() + input.clone())
+                };
+                let not_first = Expression::Constant(F::one()) - is_first;
+
+                vec![
+                    s_start * (next_rlc(not_first * rlc_carry) - input_rlc.clone()),
+                    s_link * (next_rlc(input_rlc_prev) - input_rlc),
+                ]
+            }
+        );
+
+        meta.create_gate(
+            "Table Gas",
+            |meta| {
+                let s_table = meta.query_selector(s_table);
+                let s_start = meta.query_selector(s_start);
+                let s_word = meta.query_selector(s_word);
+                let s_word_hold = meta.query_selector(s_word_hold);
+                let is_first = meta.query_advice(carry, Rotation::cur());
+                let prev_words = meta.query_advice(carry, Rotation(6));
+                let words_prev = meta.query_advice(words, Rotation::prev());
+                let words = meta.query_advice(words, Rotation::cur());
+                let is_padding = meta.query_advice(is_padding, Rotation::cur());
+                let gas = meta.query_advice(table.gas, Rotation::cur());
+
+                // A word is counted from its first row, unless the message
+                // ended before it.
+                let new_word = Expression::Constant(F::one()) - is_padding;
+                let not_first = Expression::Constant(F::one()) - is_first;
+
+                vec![
+                    s_table * (
+                        Expression::Constant(F::from(GAS_BASE)) +
+                        Expression::Constant(F::from(GAS_PER_WORD)) * words.clone() - gas
+                    ),
+                    s_start * (not_first * prev_words + new_word.clone() - words.clone()),
+                    s_word * (words_prev.clone() + new_word - words.clone()),
+                    s_word_hold * (words_prev - words),
+                ]
+            }
+        );
+
+        meta.create_gate(
+            "Table Padding Zero",
+            |meta| {
+                let s_body = meta.query_selector(s_body);
+                let pad_prev = meta.query_advice(pad_prev, Rotation::cur());
+                let input = meta.query_advice(table.input, Rotation::cur());
+
+                vec![
+                    s_body * pad_prev * input
+                ]
+            }
+        );
+
+        meta.create_gate(
+            "Table Length",
+            |meta| {
+                let s_length = meta.query_selector(s_length);
+                let is_final = meta.query_advice(table.is_final, Rotation::cur());
+                let input_len = meta.query_advice(table.input_len, Rotation::cur());
+                let is_padding = meta.query_advice(is_padding, Rotation(-8));
+
+                // The last 8 bytes of the final block hold the bit length of
+                // the message, and are zero padding in any other block.
+                let mut length = Expression::Constant(F::zero());
+                let mut constraints = vec![
+                    s_length.clone() * (is_padding - is_final.clone()),
+                ];
+                for i in 0..8 {
+                    let pad_prev = meta.query_advice(pad_prev, Rotation(i - 7));
+                    let input = meta.query_advice(table.input, Rotation(i - 7));
+                    constraints.push(
+                        s_length.clone() * (Expression::Constant(F::one()) - is_final.clone()) * pad_prev * input.clone()
+                    );
+                    length = length * Expression::Constant(F::from(1 << 8)) + input;
+                }
+                constraints.push(
+                    s_length * is_final * (length - input_len * Expression::Constant(F::from(8)))
+                );
+                constraints
+            }
+        );
+
+        let bytes = ByteDecomposeConfig::configure(meta, table.input, word_limb);
+        let digest_bytes = ByteDecomposeConfig::configure(meta, digest_byte, digest_byte_limb);
+
+        meta.create_gate(
+            "Table Output RLC",
+            |meta| {
+                let s_output_first = meta.query_selector(s_output_first);
+                let s_output_step = meta.query_selector(s_output_step);
+                let acc = meta.query_advice(output_acc, Rotation::cur());
+                let acc_prev = meta.query_advice(output_acc, Rotation::prev());
+                let byte = meta.query_advice(digest_byte, Rotation::cur());
+                let r = meta.query_challenge(table.challenge);
+
+                vec![
+                    s_output_first * (byte.clone() - acc.clone()),
+                    s_output_step * (acc_prev * r + byte - acc),
+                ]
+            }
+        );
+
+        meta.create_gate(
+            "Table Digest",
+            |meta| {
+                let s_digest = meta.query_selector(s_digest);
+                let digest_hi = meta.query_advice(table.digest_hi, Rotation::cur());
+                let digest_lo = meta.query_advice(table.digest_lo, Rotation::cur());
+                let is_final = meta.query_advice(table.is_final, Rotation::cur());
+                let output_rlc = meta.query_advice(table.output_rlc, Rotation::cur());
+                // The digest bytes start 32 rows before the end of the block.
+                let acc = meta.query_advice(output_acc, Rotation(4 * digest_words as i32 - 32));
+
+                // The 16 limbs of the state are laid out big-endian on the
+                // 16 rows ending at the current one, and the digest packs
+                // the limbs of its first `digest_words` words when the block
+                // is final.
+                let limbs: Vec<Expression<F>> = (0..16)
+                    .map(|i| meta.query_advice(digest_limb, Rotation(i - 15)))
+                    .collect();
+                let pack = |limbs: &[Expression<F>]| limbs.iter().fold(
+                    Expression::Constant(F::zero()),
+                    |acc, limb| acc * Expression::Constant(F::from(1 << 16)) + limb.clone()
+                );
+
+                vec![
+                    s_digest.clone() * (is_final.clone() * pack(&limbs[0..8]) - digest_hi),
+                    s_digest.clone() * (is_final.clone() * pack(&limbs[8..2 * digest_words]) - digest_lo),
+                    s_digest * (is_final * acc - output_rlc),
+                ]
+            }
+        );
+
+        Self {
+            table,
+            is_padding,
+            pad_prev,
+            carry,
+            digest_limb,
+            rlc_carry,
+            output_acc,
+            words,
+            s_table,
+            s_start,
+            s_link,
+            s_body,
+            s_length,
+            s_digest,
+            s_output_first,
+            s_output_step,
+            s_word,
+            s_word_hold,
+            bytes,
+            digest_bytes,
+            digest_words,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn load(
+        &self,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        self.bytes.load(layouter)?;
+        self.digest_bytes.load(layouter)
+    }
+
+    /// Assigns the rows of one padded block, of which the first `data_len`
+    /// bytes are message bytes, and constrains the 16-bit limbs it decomposes
+    /// into to the first 16 words of the message schedule `w`.
+    /// `prev` holds the cells returned for the previous block, if any, and
+    /// `is_first` whether this block starts a message.
+    ///
+    /// Returns the `is_final`, `index`, `input_len`, `is_padding`, `tag`,
+    /// `input_rlc`, `words` and `id` cells of the last row of the block, `r`
+    /// being the value of the challenge.
+    pub fn assign_block(
+        &self,
+        region: &mut Region<F>,
+        id: u64,
+        tag: u64,
+        input_len: u64,
+        block_index: usize,
+        block: &[u8],
+        data_len: usize,
+        is_final: bool,
+        is_first: AssignedCell<F, F>,
+        prev: Option<Vec<AssignedCell<F, F>>>,
+        w: &[AssignedWord<F>],
+        r: Value<F>,
+        offset: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        self.s_start.enable(region, offset)?;
+        self.s_length.enable(region, offset + TABLE_ROWS_PER_BLOCK - 1)?;
+
+        is_first.copy_advice(|| "table carry is_first", region, self.carry, offset)?;
+        let mut pad_prev = Value::known(F::zero());
+        for i in 0..4 {
+            match &prev {
+                Some(prev) => {
+                    let c = prev[i+1].copy_advice(|| "table carry", region, self.carry, offset+i+1)?;
+                    if i == 2 {
+                        pad_prev = is_first.value().zip(c.value()).map(|(first, pad)| (F::one() - *first) * *pad);
+                    }
+                }
+                None => {
+                    region.assign_advice(|| "table carry", self.carry, offset+i+1, || Value::known(F::zero()))?;
+                }
+            };
+        }
+        match &prev {
+            Some(prev) => prev[0].copy_advice(|| "table carry is_final", region, self.carry, offset+5)?,
+            None => region.assign_advice(|| "table carry is_final", self.carry, offset+5, || Value::known(F::zero()))?,
+        };
+        let rlc_carry = match &prev {
+            Some(prev) => prev[5].copy_advice(|| "table rlc_carry", region, self.rlc_carry, offset)?,
+            None => region.assign_advice(|| "table rlc_carry", self.rlc_carry, offset, || Value::known(F::zero()))?,
+        };
+        let mut input_rlc = is_first.value().zip(rlc_carry.value()).map(|(first, rlc)| (F::one() - *first) * *rlc);
+        let prev_words = match &prev {
+            Some(prev) => prev[6].copy_advice(|| "table carry words", region, self.carry, offset+6)?,
+            None => region.assign_advice(|| "table carry words", self.carry, offset+6, || Value::known(F::zero()))?,
+        };
+        let mut words = is_first.value().zip(prev_words.value()).map(|(first, words)| (F::one() - *first) * *words);
+        match &prev {
+            Some(prev) => prev[7].copy_advice(|| "table carry id", region, self.carry, offset+7)?,
+            None => region.assign_advice_from_constant(|| "table carry id", self.carry, offset+7, F::zero())?,
+        };
+
+        let bytes: Vec<Value<u8>> = block.iter().map(|byte| Value::known(*byte)).collect();
+        let (_, limbs) = self.bytes.assign(region, &bytes, offset)?;
+        for (limbs, w) in limbs.chunks(2).zip(w.iter()) {
+            region.constrain_equal(limbs[0].cell(), w.lo.cell())?;
+            region.constrain_equal(limbs[1].cell(), w.hi.cell())?;
+        }
+
+        let mut last = vec![];
+        for j in 0..block.len() {
+            let row = offset + j;
+            let row_is_final = is_final && j == TABLE_ROWS_PER_BLOCK - 1;
+            let row_is_padding = j >= data_len;
+            self.s_table.enable(region, row)?;
+            if j > 0 {
+                self.s_link.enable(region, row)?;
+            }
+            if j < LENGTH_FIELD_ROW {
+                self.s_body.enable(region, row)?;
+            }
+            let word_start = j % GAS_WORD_BYTES == 0;
+            if j > 0 {
+                if word_start {
+                    self.s_word.enable(region, row)?;
+                } else {
+                    self.s_word_hold.enable(region, row)?;
+                }
+            }
+            let id_c = region.assign_advice(|| "table id", self.table.id, row, || Value::known(F::from(id)))?;
+            let tag_c = region.assign_advice(|| "table tag", self.table.tag, row, || Value::known(F::from(tag)))?;
+            let is_final_c = region.assign_advice(|| "table is_final", self.table.is_final, row, || Value::known(F::from(row_is_final as u64)))?;
+            let input_len_c = region.assign_advice(|| "table input_len", self.table.input_len, row, || Value::known(F::from(input_len)))?;
+            let index_c = region.assign_advice(|| "table index", self.table.index, row, || Value::known(F::from((block_index * TABLE_ROWS_PER_BLOCK + j) as u64)))?;
+            region.assign_advice(|| "table pad_prev", self.pad_prev, row, || pad_prev)?;
+            let is_padding_c = region.assign_advice(|| "table is_padding", self.is_padding, row, || Value::known(F::from(row_is_padding as u64)))?;
+            pad_prev = Value::known(F::from(row_is_padding as u64));
+            if !row_is_padding {
+                input_rlc = input_rlc.zip(r).map(|(acc, r)| acc * r + F::from(block[j] as u64));
+            }
+            let input_rlc_c = region.assign_advice(|| "table input_rlc", self.table.input_rlc, row, || input_rlc)?;
+            if word_start && !row_is_padding {
+                words = words.map(|words| words + F::one());
+            }
+            let words_c = region.assign_advice(|| "table words", self.words, row, || words)?;
+            let gas = words.map(|words| F::from(GAS_BASE) + F::from(GAS_PER_WORD) * words);
+            region.assign_advice(|| "table gas", self.table.gas, row, || gas)?;
+
+            last = vec![is_final_c, index_c, input_len_c, is_padding_c, tag_c, input_rlc_c, words_c, id_c];
+        }
+        Ok(last)
+    }
+
+    /// Range checks the 64 bytes of a padded block outside of the table rows
+    /// of a message, and returns the byte cells along with the 16 message
+    /// words they form.
+    pub fn assign_bytes(
+        &self,
+        region: &mut Region<F>,
+        block: &[Value<u8>],
+        offset: usize,
+    ) -> Result<(Vec<AssignedCell<F, F>>, Vec<AssignedWord<F>>), Error> {
+        let (bytes, limbs) = self.bytes.assign(region, block, offset)?;
+        let mut words = vec![];
+        for (i, limbs) in limbs.chunks(2).enumerate() {
+            let word = block[4*i..4*i+4].iter().fold(
+                Value::known(0u32),
+                |acc, byte| acc.zip(*byte).map(|(acc, byte)| (acc << 8) + byte as u32)
+            );
+            words.push(AssignedWord::new(limbs[0].clone(), limbs[1].clone(), word.map(Word32::from)));
+        }
+        Ok((bytes, words))
+    }
+
+    /// Copies the limbs of the final state `h` into the limb column of a
+    /// block's table rows and packs the limbs of the digest into
+    /// `digest_hi`/`digest_lo` on the last row, or zeros when `is_final`, the
+    /// cell returned for that row by `assign_block`, is not set.
+    ///
+    /// The limbs of `h` are also decomposed into bytes on the last 32 rows,
+    /// whose combination with the challenge value `r` goes to `output_rlc`.
+    pub fn assign_digest(
+        &self,
+        region: &mut Region<F>,
+        is_final: &AssignedCell<F, F>,
+        h: &[AssignedWord<F>],
+        r: Value<F>,
+        offset: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let last = offset + TABLE_ROWS_PER_BLOCK - 1;
+        self.s_digest.enable(region, last)?;
+
+        let first = last - 31;
+        let bytes: Vec<Value<u8>> = h.iter()
+            .flat_map(|h| (0..4).map(move |k| h.value.map(|h| u32::from(h).to_be_bytes()[k])))
+            .collect();
+        let (_, byte_limbs) = self.digest_bytes.assign(region, &bytes, first)?;
+        for (limbs, h) in byte_limbs.chunks(2).zip(h.iter()) {
+            region.constrain_equal(limbs[0].cell(), h.lo.cell())?;
+            region.constrain_equal(limbs[1].cell(), h.hi.cell())?;
+        }
+        let digest_bytes = 4 * self.digest_words;
+        let mut acc = Value::known(F::zero());
+        for (j, byte) in bytes[..digest_bytes].iter().enumerate() {
+            if j == 0 {
+                self.s_output_first.enable(region, first)?;
+            } else {
+                self.s_output_step.enable(region, first + j)?;
+            }
+            acc = acc.zip(r).zip(*byte).map(|((acc, r), byte)| acc * r + F::from(byte as u64));
+            region.assign_advice(|| "table output_acc", self.output_acc, first + j, || acc)?;
+        }
+        let output_rlc = acc.zip(is_final.value()).map(|(acc, is_final)| acc * *is_final);
+        region.assign_advice(|| "table output_rlc", self.table.output_rlc, last, || output_rlc)?;
+
+        let mut limbs = vec![];
+        for h in h.iter() {
+            limbs.push(h.hi.clone());
+            limbs.push(h.lo.clone());
+        }
+        let mut packed = vec![];
+        for half in 0..2 {
+            let mut acc = Value::known(F::zero());
+            for j in 0..8 {
+                let row = last - 15 + 8 * half + j;
+                let limb = limbs[8 * half + j].copy_advice(|| "table digest limb", region, self.digest_limb, row)?;
+                if 8 * half + j < 2 * self.digest_words {
+                    acc = acc.zip(limb.value()).map(|(acc, limb)| acc * F::from(1 << 16) + *limb);
+                }
+            }
+            packed.push(acc.zip(is_final.value()).map(|(acc, is_final)| acc * *is_final));
+        }
+
+        let digest_hi = region.assign_advice(|| "table digest_hi", self.table.digest_hi, last, || packed[0])?;
+        let digest_lo = region.assign_advice(|| "table digest_lo", self.table.digest_lo, last, || packed[1])?;
+
+        Ok(vec![digest_hi, digest_lo])
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::utils::{pad_message, message_words};
+    use crate::word::Word32;
+    use halo2_proofs::circuit::{SimpleFloorPlanner, Layouter};
+    use halo2_proofs::plonk::Circuit;
+    use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr as F};
+
+    #[derive(Debug, Clone)]
+    struct TableTestConfig<F: FieldExt> {
+        table_chip: TableChip<F>,
+        a: Column<Advice>,
+    }
+
+    #[derive(Default, Clone)]
+    struct TableTestCircuit<F: FieldExt> {
+        input_len: usize,
+        padded: Vec<u8>,
+        words: Vec<u16>,
+        ids: Vec<u64>,
+        is_first: Vec<bool>,
+        is_final: Vec<bool>,
+        _marker: PhantomData<F>,
+    }
+
+    impl<F: FieldExt> TableTestCircuit<F> {
+        fn new(input: &[u8]) -> Self {
+            let padded = pad_message(input);
+            let num_blocks = padded.len() / TABLE_ROWS_PER_BLOCK;
+            Self {
+                input_len: input.len(),
+                words: message_words(&padded),
+                ids: vec![1; num_blocks],
+                is_first: (0..num_blocks).map(|i| i == 0).collect(),
+                is_final: (0..num_blocks).map(|i| i == num_blocks - 1).collect(),
+                padded,
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl<F: FieldExt> Circuit<F> for TableTestCircuit<F> {
+        type Config = TableTestConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = Sha2Table::construct(meta);
+            let table_chip = TableChip::configure(meta, table, Sha2Mode::Sha256);
+            let a = meta.advice_column();
+            meta.enable_equality(a);
+            let constants = meta.fixed_column();
+            meta.enable_constant(constants);
+
+            TableTestConfig { table_chip, a }
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            config.table_chip.load(&mut layouter)?;
+            let r = layouter.get_challenge(config.table_chip.table.challenge);
+            layouter.assign_region(
+                || "Table Blocks",
+                |mut region| {
+                    let mut prev = None;
+                    for i in 0..self.is_first.len() {
+                        let offset = TABLE_ROWS_PER_BLOCK * i;
+                        let block = &self.padded[offset..offset + TABLE_ROWS_PER_BLOCK];
+                        let is_first = region.assign_advice(
+                            || "is_first", config.a, offset, || Value::known(F::from(self.is_first[i] as u64))
+                        )?;
+                        let mut w = vec![];
+                        for (j, limbs) in self.words[32 * i..32 * (i + 1)].chunks(2).enumerate() {
+                            let lo = region.assign_advice(
+                                || "w lo", config.a, offset + 2 * j + 1, || Value::known(F::from(limbs[0] as u64))
+                            )?;
+                            let hi = region.assign_advice(
+                                || "w hi", config.a, offset + 2 * j + 2, || Value::known(F::from(limbs[1] as u64))
+                            )?;
+                            w.push(AssignedWord::new(lo, hi, Value::known(Word32::new(limbs[0], limbs[1]))));
+                        }
+                        let data_len = self.input_len.saturating_sub(offset).min(TABLE_ROWS_PER_BLOCK);
+                        prev = Some(config.table_chip.assign_block(
+                            &mut region, self.ids[i], 0, self.input_len as u64, i, block, data_len,
+                            self.is_final[i], is_first, prev, &w, r, offset
+                        )?);
+                    }
+                    Ok(())
+                }
+            )
+        }
+    }
+
+    #[test]
+    fn test_table_padding() {
+        for len in [0, 1, 55, 56, 63, 64, 119, 120] {
+            let circuit = TableTestCircuit::<F>::new(&vec![0x61; len]);
+            let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()), "input of {} bytes", len);
+        }
+    }
+
+    #[test]
+    fn test_table_wrong_word() {
+        let mut circuit = TableTestCircuit::<F>::new(&[0x61; 10]);
+        circuit.words[1] ^= 1;
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_table_padding_wrong_marker() {
+        let mut circuit = TableTestCircuit::<F>::new(&[0x61; 10]);
+        circuit.padded[10] = 0x81;
+        circuit.words = message_words(&circuit.padded);
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_table_padding_nonzero_fill() {
+        let mut circuit = TableTestCircuit::<F>::new(&[0x61; 10]);
+        circuit.padded[20] = 0x01;
+        circuit.words = message_words(&circuit.padded);
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_table_padding_wrong_length() {
+        let mut circuit = TableTestCircuit::<F>::new(&[0x61; 10]);
+        circuit.padded[63] += 8;
+        circuit.words = message_words(&circuit.padded);
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_table_padding_missing_length_block() {
+        // 56 bytes leave no room for the length field in the first block.
+        let mut circuit = TableTestCircuit::<F>::new(&[0x61; 56]);
+        circuit.is_first.truncate(1);
+        circuit.is_final = vec![true];
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_table_block_after_final() {
+        // A block of zeros ending with the same length field is a consistent
+        // continuation of the padding, but the message already ended.
+        let mut circuit = TableTestCircuit::<F>::new(&[0x61; 10]);
+        let length = circuit.padded[LENGTH_FIELD_ROW..].to_vec();
+        circuit.padded.extend(vec![0; LENGTH_FIELD_ROW]);
+        circuit.padded.extend(length);
+        circuit.words = message_words(&circuit.padded);
+        circuit.ids.push(1);
+        circuit.is_first.push(false);
+        circuit.is_final.push(true);
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_table_wrong_first_id() {
+        let mut circuit = TableTestCircuit::<F>::new(&[0x61; 10]);
+        circuit.ids[0] = 2;
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_table_id_changes_within_call() {
+        // The second block of the message claims the id of another call.
+        let mut circuit = TableTestCircuit::<F>::new(&[0x61; 100]);
+        circuit.ids[1] = 2;
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,135 @@
+use std::marker::PhantomData;
+
+use crate::utils::create_interleave_num;
+use crate::word::{AssignedWord64, Word64};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Expression, Fixed, Selector, Error},
+    poly::Rotation,
+};
+
+/// Maximum number of words added by `ModAddConfig`, besides a constant.
+pub const MAX_TERMS: usize = 5;
+
+/// Adds up to `MAX_TERMS` words and a constant modulo `2^64`.
+///
+/// The limbs of the terms are copied into `a4..a8` and the limbs of the
+/// constant are in the fixed column `k`, on four rows, low limb first. The
+/// limbs of the sum are laid out in `a0` with their spread in `a1`, so the sum
+/// comes out range checked, and the carry out of 64 bits in `a2` on the first
+/// row, with its spread in `a3`. The spread lookups are enabled on these rows
+/// by `s_spread_a0` and `s_spread_a2`.
+#[derive(Debug, Clone)]
+pub struct ModAddConfig<F: FieldExt> {
+    s_add: Selector,
+    a0: Column<Advice>,
+    a1: Column<Advice>,
+    a2: Column<Advice>,
+    a3: Column<Advice>,
+    terms: [Column<Advice>; MAX_TERMS],
+    k: Column<Fixed>,
+    s_spread_a0: Selector,
+    s_spread_a2: Selector,
+    _marker: PhantomData<F>
+}
+
+impl<F: FieldExt> ModAddConfig<F> {
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a0: Column<Advice>,
+        a1: Column<Advice>,
+        a2: Column<Advice>,
+        a3: Column<Advice>,
+        terms: [Column<Advice>; MAX_TERMS],
+        k: Column<Fixed>,
+        s_spread_a0: Selector,
+        s_spread_a2: Selector,
+    ) -> Self {
+        let s_add = meta.selector();
+
+        meta.create_gate(
+            "Add64",
+            |meta| {
+                let s_add = meta.query_selector(s_add);
+                let carry = meta.query_advice(a2, Rotation::cur());
+
+                let mut lhs = Expression::Constant(F::zero());
+                let mut rhs = carry * Expression::Constant(F::from_u128(1 << 64));
+                for i in 0..4 {
+                    let shift = Expression::Constant(F::from(1 << (16 * i)));
+                    let mut limb_sum = meta.query_fixed(k, Rotation(i as i32));
+                    for term in terms.iter() {
+                        limb_sum = limb_sum + meta.query_advice(*term, Rotation(i as i32));
+                    }
+                    lhs = lhs + shift.clone() * limb_sum;
+                    rhs = rhs + shift * meta.query_advice(a0, Rotation(i as i32));
+                }
+
+                vec![s_add * (lhs - rhs)]
+            }
+        );
+
+        Self {
+            s_add,
+            a0, a1, a2, a3,
+            terms,
+            k,
+            s_spread_a0,
+            s_spread_a2,
+            _marker: PhantomData
+        }
+    }
+
+    /// Number of rows used by `assign`.
+    pub fn rows() -> usize {
+        4
+    }
+
+    /// Returns `terms[0] + .. + constant` modulo `2^64`.
+    pub fn assign(
+        &self,
+        region: &mut Region<F>,
+        terms: &[&AssignedWord64<F>],
+        constant: u64,
+        offset: usize,
+    ) -> Result<AssignedWord64<F>, Error> {
+        if terms.len() > MAX_TERMS {
+            return Err(Error::Synthesis);
+        }
+        self.s_add.enable(region, offset)?;
+
+        let sum = terms.iter().fold(Value::known(constant as u128), |acc, term| {
+            acc.zip(term.value).map(|(acc, term)| acc + term.value() as u128)
+        });
+        let word = sum.map(|sum| Word64::from(sum as u64));
+        let carry = sum.map(|sum| (sum >> 64) as u32);
+
+        let constant = Word64::from(constant);
+        let mut limbs = vec![];
+        let mut spread = vec![];
+        for i in 0..4 {
+            for (j, column) in self.terms.iter().enumerate() {
+                match terms.get(j) {
+                    Some(term) => {
+                        term.limbs[i].copy_advice(|| "add term", region, *column, offset+i)?;
+                    }
+                    None => {
+                        region.assign_advice(|| "add term", *column, offset+i, || Value::known(F::zero()))?;
+                    }
+                };
+            }
+            region.assign_fixed(|| "add constant", self.k, offset+i, || Value::known(F::from(constant.limbs[i] as u64)))?;
+            self.s_spread_a0.enable(region, offset+i)?;
+            limbs.push(region.assign_advice(|| "add sum", self.a0, offset+i, || word.map(|word| F::from(word.limbs[i] as u64)))?);
+            spread.push(region.assign_advice(|| "add s_sum", self.a1, offset+i, || word.map(|word| F::from(word.spread_limbs()[i] as u64)))?);
+        }
+        self.s_spread_a2.enable(region, offset)?;
+        region.assign_advice(|| "add carry", self.a2, offset, || carry.map(|carry| F::from(carry as u64)))?;
+        region.assign_advice(|| "add s_carry", self.a3, offset, || carry.map(|carry| F::from(create_interleave_num(carry) as u64)))?;
+
+        Ok(AssignedWord64::new(limbs, spread, word))
+    }
+}
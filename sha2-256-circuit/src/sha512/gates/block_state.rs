@@ -0,0 +1,180 @@
+use std::marker::PhantomData;
+
+use crate::utils::{create_interleave_num, create_value_2_check};
+use crate::word::{AssignedWord64, Word64};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Expression, Selector, Error},
+    poly::Rotation,
+};
+
+/// Selects the initial state of a block and masks its digest, as
+/// `BlockStateConfig` does for SHA-256, on 64-bit words. The limbs of the
+/// initial state in `a0`, with their spread in `a1`, are looked up in the
+/// spread table through `s_spread_a0`.
+#[derive(Debug, Clone)]
+pub struct BlockStateConfig<F: FieldExt> {
+    pub s_init: Selector,
+    pub s_out: Selector,
+    a0: Column<Advice>,
+    a1: Column<Advice>,
+    a4: Column<Advice>,
+    a5: Column<Advice>,
+    a6: Column<Advice>,
+    a7: Column<Advice>,
+    a8: Column<Advice>,
+    s_spread_a0: Selector,
+    _marker: PhantomData<F>
+}
+
+impl<F: FieldExt> BlockStateConfig<F> {
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a0: Column<Advice>,
+        a1: Column<Advice>,
+        a4: Column<Advice>,
+        a5: Column<Advice>,
+        a6: Column<Advice>,
+        a7: Column<Advice>,
+        a8: Column<Advice>,
+        s_spread_a0: Selector,
+    ) -> Self {
+        let s_init = meta.selector();
+        let s_out = meta.selector();
+
+        meta.create_gate(
+            "Initial State64",
+            |meta| {
+                let s_init = meta.query_selector(s_init);
+                let h = meta.query_advice(a0, Rotation::cur());
+                let iv = meta.query_advice(a4, Rotation::cur());
+                let prev = meta.query_advice(a5, Rotation::cur());
+                let is_first = meta.query_advice(a6, Rotation::cur());
+
+                let select_constraint =
+                    is_first.clone() * iv +
+                    (Expression::Constant(F::one()) - is_first.clone()) * prev -
+                    h;
+
+                vec![
+                    s_init.clone() * select_constraint,
+                    s_init * create_value_2_check(is_first)
+                ]
+            }
+        );
+
+        meta.create_gate(
+            "Final Digest64",
+            |meta| {
+                let s_out = meta.query_selector(s_out);
+                let is_final = meta.query_advice(a8, Rotation::cur());
+                let out = meta.query_advice(a4, Rotation::next());
+
+                let mut h = Expression::Constant(F::zero());
+                for (i, column) in [a4, a5, a6, a7].iter().enumerate() {
+                    h = h + meta.query_advice(*column, Rotation::cur()) * Expression::Constant(F::from(1 << (16 * i)));
+                }
+
+                vec![
+                    s_out.clone() * (is_final.clone() * h - out),
+                    s_out * create_value_2_check(is_final)
+                ]
+            }
+        );
+
+        Self {
+            s_init,
+            s_out,
+            a0, a1, a4, a5, a6, a7, a8,
+            s_spread_a0,
+            _marker: PhantomData
+        }
+    }
+
+    /// Number of rows used by `assign_init`, one per limb of the state.
+    pub fn init_rows() -> usize {
+        32
+    }
+
+    /// Number of rows used by `assign_out`, two per word of the state.
+    pub fn out_rows() -> usize {
+        16
+    }
+
+    /// Selects the initial state of a block limb by limb: `iv` when `is_first`
+    /// is set, the output state of the previous block otherwise. The `iv`
+    /// limbs are constrained to constants.
+    pub fn assign_init(
+        &self,
+        region: &mut Region<F>,
+        is_first: Value<bool>,
+        iv: [u64; 8],
+        prev: Option<Vec<AssignedWord64<F>>>,
+        offset: usize,
+    ) -> Result<(AssignedCell<F, F>, Vec<AssignedWord64<F>>), Error> {
+        let mut is_first_c: Option<AssignedCell<F, F>> = None;
+        let mut h = vec![];
+        for (j, iv) in iv.iter().enumerate() {
+            let iv = Word64::from(*iv);
+            let prev_val = prev.as_ref().map_or(Value::known(iv), |prev| prev[j].value);
+            let h_val = is_first.zip(prev_val).map(|(is_first, prev)| if is_first { iv } else { prev });
+
+            let mut limbs = vec![];
+            let mut spread = vec![];
+            for i in 0..4 {
+                let row = offset + 4 * j + i;
+                self.s_init.enable(region, row)?;
+                self.s_spread_a0.enable(region, row)?;
+                let first_c = match &is_first_c {
+                    Some(first) => first.copy_advice(|| "s_init is_first", region, self.a6, row)?,
+                    None => region.assign_advice(|| "s_init is_first", self.a6, row, || is_first.map(|is_first| F::from(is_first as u64)))?,
+                };
+                is_first_c = Some(first_c);
+
+                let iv_limb = F::from(iv.limbs[i] as u64);
+                let iv_c = region.assign_advice(|| "s_init iv", self.a4, row, || Value::known(iv_limb))?;
+                region.constrain_constant(iv_c.cell(), iv_limb)?;
+                // The first block of the circuit has no predecessor, its `prev`
+                // limbs are free and must be ignored by forcing `is_first`.
+                match &prev {
+                    Some(prev) => {
+                        prev[j].limbs[i].copy_advice(|| "s_init prev", region, self.a5, row)?;
+                    }
+                    None => {
+                        region.assign_advice(|| "s_init prev", self.a5, row, || Value::known(iv_limb))?;
+                    }
+                };
+                limbs.push(region.assign_advice(|| "s_init h", self.a0, row, || h_val.map(|h| F::from(h.limbs[i] as u64)))?);
+                spread.push(region.assign_advice(|| "s_init s_h", self.a1, row, || h_val.map(|h| F::from(create_interleave_num(h.limbs[i] as u32) as u64)))?);
+            }
+            h.push(AssignedWord64::new(limbs, spread, h_val));
+        }
+        Ok((is_first_c.ok_or(Error::Synthesis)?, h))
+    }
+
+    /// Packs the digest words of a block into one cell each, masked with its
+    /// `is_final` flag so only the final block of a message exposes its state.
+    pub fn assign_out(
+        &self,
+        region: &mut Region<F>,
+        is_final: AssignedCell<F, F>,
+        words: &[AssignedWord64<F>],
+        offset: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let mut out = vec![];
+        for (i, word) in words.iter().enumerate() {
+            let row = offset + 2 * i;
+            self.s_out.enable(region, row)?;
+            for (limb, column) in word.limbs.iter().zip([self.a4, self.a5, self.a6, self.a7]) {
+                limb.copy_advice(|| "s_out h", region, column, row)?;
+            }
+            let final_c = is_final.copy_advice(|| "s_out is_final", region, self.a8, row)?;
+            let out_val = final_c.value().zip(word.value).map(|(f, h)| *f * F::from(h.value()));
+            out.push(region.assign_advice(|| "s_out out", self.a4, row+1, || out_val)?);
+        }
+        Ok(out)
+    }
+}
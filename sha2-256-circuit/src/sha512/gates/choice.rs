@@ -0,0 +1,150 @@
+use std::marker::PhantomData;
+
+use crate::utils::{create_interleave_num, even_bit, odd_bit, spread_not};
+use crate::word::{AssignedWord64, Word64};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Expression, Selector, Error},
+    poly::Rotation,
+};
+
+/// Computes `Ch(e, f, g) = (e & f) ^ (!e & g)` limb by limb as the two words
+/// `e & f` and `!e & g`, whose bits never overlap, so their sum is `Ch`.
+///
+/// The spread limbs of `e` and `f` are copied into `a4` and `a5`, and their sum
+/// is split into its even bits in `a0`/`a1` and its odd bits, the AND, in
+/// `a2`/`a3`, one limb per row. The four rows that follow do the same with the
+/// spread of `!e`, computed from the spread of `e`, and of `g`. Both pairs are
+/// looked up in the spread table on every row, through `s_spread_a0` and
+/// `s_spread_a2`.
+#[derive(Debug, Clone)]
+pub struct ChoiceConfig<F: FieldExt> {
+    s_ch_p: Selector,
+    s_ch_q: Selector,
+    a0: Column<Advice>,
+    a1: Column<Advice>,
+    a2: Column<Advice>,
+    a3: Column<Advice>,
+    a4: Column<Advice>,
+    a5: Column<Advice>,
+    s_spread_a0: Selector,
+    s_spread_a2: Selector,
+    _marker: PhantomData<F>
+}
+
+impl<F: FieldExt> ChoiceConfig<F> {
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a0: Column<Advice>,
+        a1: Column<Advice>,
+        a2: Column<Advice>,
+        a3: Column<Advice>,
+        a4: Column<Advice>,
+        a5: Column<Advice>,
+        s_spread_a0: Selector,
+        s_spread_a2: Selector,
+    ) -> Self {
+        let s_ch_p = meta.selector();
+        let s_ch_q = meta.selector();
+
+        meta.create_gate(
+            "Choice64 P",
+            |meta| {
+                let s_ch_p = meta.query_selector(s_ch_p);
+                let s_even = meta.query_advice(a1, Rotation::cur());
+                let s_odd = meta.query_advice(a3, Rotation::cur());
+                let s_e = meta.query_advice(a4, Rotation::cur());
+                let s_f = meta.query_advice(a5, Rotation::cur());
+
+                vec![
+                    s_ch_p * (s_e + s_f - s_even - s_odd * Expression::Constant(F::from(2)))
+                ]
+            }
+        );
+
+        meta.create_gate(
+            "Choice64 Q",
+            |meta| {
+                let s_ch_q = meta.query_selector(s_ch_q);
+                let s_even = meta.query_advice(a1, Rotation::cur());
+                let s_odd = meta.query_advice(a3, Rotation::cur());
+                let s_e = meta.query_advice(a4, Rotation::cur());
+                let s_g = meta.query_advice(a5, Rotation::cur());
+                let s_e_n = Expression::Constant(F::from(create_interleave_num(0xFFFF) as u64)) - s_e;
+
+                vec![
+                    s_ch_q * (s_e_n + s_g - s_even - s_odd * Expression::Constant(F::from(2)))
+                ]
+            }
+        );
+
+        Self {
+            s_ch_p,
+            s_ch_q,
+            a0, a1, a2, a3, a4, a5,
+            s_spread_a0,
+            s_spread_a2,
+            _marker: PhantomData
+        }
+    }
+
+    /// Number of rows used by `assign`.
+    pub fn rows() -> usize {
+        8
+    }
+
+    /// Returns `e & f` and `!e & g`.
+    pub fn assign(
+        &self,
+        region: &mut Region<F>,
+        e: &AssignedWord64<F>,
+        f: &AssignedWord64<F>,
+        g: &AssignedWord64<F>,
+        offset: usize,
+    ) -> Result<(AssignedWord64<F>, AssignedWord64<F>), Error> {
+        let p = self.assign_half(region, self.s_ch_p, e, f, false, offset)?;
+        let q = self.assign_half(region, self.s_ch_q, e, g, true, offset+4)?;
+        Ok((p, q))
+    }
+
+    fn assign_half(
+        &self,
+        region: &mut Region<F>,
+        selector: Selector,
+        e: &AssignedWord64<F>,
+        x: &AssignedWord64<F>,
+        negate: bool,
+        offset: usize,
+    ) -> Result<AssignedWord64<F>, Error> {
+        let mut limbs = vec![];
+        let mut spread = vec![];
+        for i in 0..4 {
+            let row = offset + i;
+            self.s_spread_a0.enable(region, row)?;
+            self.s_spread_a2.enable(region, row)?;
+            selector.enable(region, row)?;
+            e.spread[i].copy_advice(|| "ch s_e", region, self.a4, row)?;
+            x.spread[i].copy_advice(|| "ch s_x", region, self.a5, row)?;
+
+            let sum = e.value.zip(x.value).map(|(e, x)| {
+                let s_e = if negate { spread_not(e.limbs[i]) } else { e.spread_limbs()[i] };
+                s_e + x.spread_limbs()[i]
+            });
+            let even = sum.map(even_bit);
+            let odd = sum.map(odd_bit);
+            region.assign_advice(|| "ch even", self.a0, row, || even.map(|even| F::from(even as u64)))?;
+            region.assign_advice(|| "ch s_even", self.a1, row, || even.map(|even| F::from(create_interleave_num(even as u32) as u64)))?;
+            limbs.push(region.assign_advice(|| "ch odd", self.a2, row, || odd.map(|odd| F::from(odd as u64)))?);
+            spread.push(region.assign_advice(|| "ch s_odd", self.a3, row, || odd.map(|odd| F::from(create_interleave_num(odd as u32) as u64)))?);
+        }
+
+        let value = e.value.zip(x.value).map(|(e, x)| {
+            let e = if negate { !e.value() } else { e.value() };
+            Word64::from(e & x.value())
+        });
+        Ok(AssignedWord64::new(limbs, spread, value))
+    }
+}
@@ -0,0 +1,115 @@
+use std::marker::PhantomData;
+
+use crate::utils::{create_interleave_num, even_bit, odd_bit};
+use crate::word::{AssignedWord64, Word64};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::Region,
+    plonk::{Advice, Column, ConstraintSystem, Expression, Selector, Error},
+    poly::Rotation,
+};
+
+/// Computes `Maj(a, b, c)` limb by limb, as the odd bits of the sum of the
+/// spread limbs of `a`, `b` and `c` copied into `a4..a6`. The even bits of the
+/// sum are in `a0`/`a1` and the odd bits in `a2`/`a3`, one limb per row, both
+/// pairs looked up in the spread table through `s_spread_a0` and
+/// `s_spread_a2`.
+#[derive(Debug, Clone)]
+pub struct MajConfig<F: FieldExt> {
+    s_maj: Selector,
+    a0: Column<Advice>,
+    a1: Column<Advice>,
+    a2: Column<Advice>,
+    a3: Column<Advice>,
+    a4: Column<Advice>,
+    a5: Column<Advice>,
+    a6: Column<Advice>,
+    s_spread_a0: Selector,
+    s_spread_a2: Selector,
+    _marker: PhantomData<F>
+}
+
+impl<F: FieldExt> MajConfig<F> {
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a0: Column<Advice>,
+        a1: Column<Advice>,
+        a2: Column<Advice>,
+        a3: Column<Advice>,
+        a4: Column<Advice>,
+        a5: Column<Advice>,
+        a6: Column<Advice>,
+        s_spread_a0: Selector,
+        s_spread_a2: Selector,
+    ) -> Self {
+        let s_maj = meta.selector();
+
+        meta.create_gate(
+            "Maj64",
+            |meta| {
+                let s_maj = meta.query_selector(s_maj);
+                let s_even = meta.query_advice(a1, Rotation::cur());
+                let s_odd = meta.query_advice(a3, Rotation::cur());
+                let s_a = meta.query_advice(a4, Rotation::cur());
+                let s_b = meta.query_advice(a5, Rotation::cur());
+                let s_c = meta.query_advice(a6, Rotation::cur());
+
+                vec![
+                    s_maj * (s_a + s_b + s_c - s_even - s_odd * Expression::Constant(F::from(2)))
+                ]
+            }
+        );
+
+        Self {
+            s_maj,
+            a0, a1, a2, a3, a4, a5, a6,
+            s_spread_a0,
+            s_spread_a2,
+            _marker: PhantomData
+        }
+    }
+
+    /// Number of rows used by `assign`.
+    pub fn rows() -> usize {
+        4
+    }
+
+    pub fn assign(
+        &self,
+        region: &mut Region<F>,
+        a: &AssignedWord64<F>,
+        b: &AssignedWord64<F>,
+        c: &AssignedWord64<F>,
+        offset: usize,
+    ) -> Result<AssignedWord64<F>, Error> {
+        let mut limbs = vec![];
+        let mut spread = vec![];
+        for i in 0..4 {
+            let row = offset + i;
+            self.s_spread_a0.enable(region, row)?;
+            self.s_spread_a2.enable(region, row)?;
+            self.s_maj.enable(region, row)?;
+            a.spread[i].copy_advice(|| "maj s_a", region, self.a4, row)?;
+            b.spread[i].copy_advice(|| "maj s_b", region, self.a5, row)?;
+            c.spread[i].copy_advice(|| "maj s_c", region, self.a6, row)?;
+
+            let sum = a.value.zip(b.value).zip(c.value).map(|((a, b), c)| {
+                a.spread_limbs()[i] + b.spread_limbs()[i] + c.spread_limbs()[i]
+            });
+            let even = sum.map(even_bit);
+            let odd = sum.map(odd_bit);
+            region.assign_advice(|| "maj even", self.a0, row, || even.map(|even| F::from(even as u64)))?;
+            region.assign_advice(|| "maj s_even", self.a1, row, || even.map(|even| F::from(create_interleave_num(even as u32) as u64)))?;
+            limbs.push(region.assign_advice(|| "maj odd", self.a2, row, || odd.map(|odd| F::from(odd as u64)))?);
+            spread.push(region.assign_advice(|| "maj s_odd", self.a3, row, || odd.map(|odd| F::from(create_interleave_num(odd as u32) as u64)))?);
+        }
+
+        let value = a.value.zip(b.value).zip(c.value).map(|((a, b), c)| {
+            let (a, b, c) = (a.value(), b.value(), c.value());
+            Word64::from((a & b) ^ (a & c) ^ (b & c))
+        });
+        Ok(AssignedWord64::new(limbs, spread, value))
+    }
+}
@@ -0,0 +1,6 @@
+pub mod add;
+pub mod block_state;
+pub mod choice;
+pub mod maj;
+pub mod sigma;
+pub mod spread_word;
@@ -0,0 +1,201 @@
+use std::marker::PhantomData;
+
+use crate::utils::{create_interleave_num, even_bit, odd_bit};
+use crate::word::{AssignedWord64, Word64};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Expression, Selector, Error},
+    poly::Rotation,
+};
+
+/// A term of a sigma function, a right rotation or a right shift of the word.
+#[derive(Debug, Clone, Copy)]
+pub enum SigmaOp {
+    Rotate(usize),
+    Shift(usize),
+}
+
+impl SigmaOp {
+    fn amount(&self) -> usize {
+        match self {
+            Self::Rotate(n) | Self::Shift(n) => *n,
+        }
+    }
+
+    /// Bit position a bit of the word moves to, if it is kept.
+    fn position(&self, bit: usize) -> Option<usize> {
+        match self {
+            Self::Rotate(n) => Some((bit + 64 - n) % 64),
+            Self::Shift(n) => bit.checked_sub(*n),
+        }
+    }
+}
+
+/// XORs the three rotations or shifts of a word given by `ops`, as done by the
+/// `Σ0`, `Σ1`, `σ0` and `σ1` functions of SHA-512.
+///
+/// The word is cut into pieces at its limb boundaries and at the amounts of
+/// `ops`, so that no piece crosses a boundary. Piece `j` of `w` bits sits on
+/// row `j`, in `a0` with its spread in `a1`, and shifted left to 16 bits in
+/// `a2` with its spread in `a3`: the lookups of both pairs, enabled by
+/// `s_spread_a0` and `s_spread_a2` on every row of the gate, range check the
+/// piece to `w` bits. The limbs of the word are copied into `a4` on the first
+/// four rows.
+///
+/// The spread forms of the pieces are moved to their rotated positions and
+/// summed, and the sum is split in the four rows following the pieces into
+/// the spread of the XOR on the even bits, in `a0`/`a1`, and the spread of the
+/// majority on the odd bits, in `a2`/`a3`.
+#[derive(Debug, Clone)]
+pub struct SigmaConfig<F: FieldExt> {
+    s_sigma: Selector,
+    ops: [SigmaOp; 3],
+    /// `(start, width)` of every piece, from the lowest bit up.
+    pieces: Vec<(usize, usize)>,
+    a0: Column<Advice>,
+    a1: Column<Advice>,
+    a2: Column<Advice>,
+    a3: Column<Advice>,
+    a4: Column<Advice>,
+    s_spread_a0: Selector,
+    s_spread_a2: Selector,
+    _marker: PhantomData<F>
+}
+
+impl<F: FieldExt> SigmaConfig<F> {
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a0: Column<Advice>,
+        a1: Column<Advice>,
+        a2: Column<Advice>,
+        a3: Column<Advice>,
+        a4: Column<Advice>,
+        ops: [SigmaOp; 3],
+        s_spread_a0: Selector,
+        s_spread_a2: Selector,
+    ) -> Self {
+        let s_sigma = meta.selector();
+
+        let mut bounds = vec![0, 16, 32, 48, 64];
+        bounds.extend(ops.iter().map(|op| op.amount()));
+        bounds.sort();
+        bounds.dedup();
+        let pieces: Vec<(usize, usize)> = bounds.windows(2).map(|b| (b[0], b[1] - b[0])).collect();
+        let n = pieces.len();
+
+        let pow = |bits: usize| Expression::Constant(F::from_u128(1 << bits));
+
+        meta.create_gate(
+            "Sigma64",
+            |meta| {
+                let s_sigma = meta.query_selector(s_sigma);
+                let mut constraints = vec![];
+
+                let mut limbs = vec![Expression::Constant(F::zero()); 4];
+                let mut rotated = Expression::Constant(F::zero());
+                for (j, (start, width)) in pieces.iter().enumerate() {
+                    let piece = meta.query_advice(a0, Rotation(j as i32));
+                    let s_piece = meta.query_advice(a1, Rotation(j as i32));
+                    let piece_shifted = meta.query_advice(a2, Rotation(j as i32));
+                    let s_piece_shifted = meta.query_advice(a3, Rotation(j as i32));
+
+                    constraints.push(piece_shifted - piece.clone() * pow(16 - width));
+                    constraints.push(s_piece_shifted - s_piece.clone() * pow(2 * (16 - width)));
+
+                    let limb = start / 16;
+                    limbs[limb] = limbs[limb].clone() + piece * pow(start - 16 * limb);
+                    for op in ops.iter() {
+                        if let Some(position) = op.position(*start) {
+                            rotated = rotated + s_piece.clone() * pow(2 * position);
+                        }
+                    }
+                }
+                for (i, limb) in limbs.into_iter().enumerate() {
+                    constraints.push(meta.query_advice(a4, Rotation(i as i32)) - limb);
+                }
+
+                let mut split = Expression::Constant(F::zero());
+                for i in 0..4 {
+                    let row = Rotation((n + i) as i32);
+                    let s_even = meta.query_advice(a1, row);
+                    let s_odd = meta.query_advice(a3, row);
+                    split = split + (s_even + s_odd * Expression::Constant(F::from(2))) * pow(32 * i);
+                }
+                constraints.push(rotated - split);
+
+                constraints.into_iter().map(|constraint| s_sigma.clone() * constraint).collect::<Vec<_>>()
+            }
+        );
+
+        Self {
+            s_sigma,
+            ops,
+            pieces,
+            a0, a1, a2, a3, a4,
+            s_spread_a0,
+            s_spread_a2,
+            _marker: PhantomData
+        }
+    }
+
+    /// Number of rows used by `assign`.
+    pub fn rows(&self) -> usize {
+        self.pieces.len() + 4
+    }
+
+    /// Returns the XOR of the rotations and shifts of `word`.
+    pub fn assign(
+        &self,
+        region: &mut Region<F>,
+        word: &AssignedWord64<F>,
+        offset: usize,
+    ) -> Result<AssignedWord64<F>, Error> {
+        self.s_sigma.enable(region, offset)?;
+        let n = self.pieces.len();
+        for row in offset..offset + self.rows() {
+            self.s_spread_a0.enable(region, row)?;
+            self.s_spread_a2.enable(region, row)?;
+        }
+
+        for (i, limb) in word.limbs.iter().enumerate() {
+            limb.copy_advice(|| "sigma limb", region, self.a4, offset+i)?;
+        }
+
+        let value = word.value.map(|word| word.value());
+        let mut rotated = Value::known(0u128);
+        for (j, (start, width)) in self.pieces.iter().enumerate() {
+            let piece = value.map(|value| ((value >> start) & ((1 << width) - 1)) as u32);
+            let s_piece = piece.map(create_interleave_num);
+            let shifted = piece.map(|piece| piece << (16 - width));
+            region.assign_advice(|| "sigma piece", self.a0, offset+j, || piece.map(|piece| F::from(piece as u64)))?;
+            region.assign_advice(|| "sigma s_piece", self.a1, offset+j, || s_piece.map(|s_piece| F::from(s_piece as u64)))?;
+            region.assign_advice(|| "sigma piece shifted", self.a2, offset+j, || shifted.map(|shifted| F::from(shifted as u64)))?;
+            region.assign_advice(|| "sigma s_piece shifted", self.a3, offset+j, || shifted.map(|shifted| F::from(create_interleave_num(shifted) as u64)))?;
+
+            for op in self.ops.iter() {
+                if let Some(position) = op.position(*start) {
+                    rotated = rotated.zip(s_piece).map(|(rotated, s_piece)| rotated + ((s_piece as u128) << (2 * position)));
+                }
+            }
+        }
+
+        let mut limbs = vec![];
+        let mut spread = vec![];
+        for i in 0..4 {
+            let row = offset + n + i;
+            let digit = rotated.map(|rotated| (rotated >> (32 * i)) as u32);
+            let even = digit.map(even_bit);
+            let odd = digit.map(odd_bit);
+            limbs.push(region.assign_advice(|| "sigma even", self.a0, row, || even.map(|even| F::from(even as u64)))?);
+            spread.push(region.assign_advice(|| "sigma s_even", self.a1, row, || even.map(|even| F::from(create_interleave_num(even as u32) as u64)))?);
+            region.assign_advice(|| "sigma odd", self.a2, row, || odd.map(|odd| F::from(odd as u64)))?;
+            region.assign_advice(|| "sigma s_odd", self.a3, row, || odd.map(|odd| F::from(create_interleave_num(odd as u32) as u64)))?;
+        }
+        let xor = rotated.map(|rotated| Word64::new([0, 1, 2, 3].map(|i| even_bit((rotated >> (32 * i)) as u32))));
+
+        Ok(AssignedWord64::new(limbs, spread, xor))
+    }
+}
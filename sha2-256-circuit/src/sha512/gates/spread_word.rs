@@ -0,0 +1,109 @@
+use std::marker::PhantomData;
+
+use crate::spread_table::{SpreadTableConfig, SPREAD_TABLE_BITS};
+use crate::word::{AssignedWord64, Word64};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Selector, Error},
+    poly::Rotation,
+};
+
+/// Looks up the pairs `(a0, a1)` and `(a2, a3)` in the 16-bit spread table, on
+/// the rows where `s_spread_a0` and `s_spread_a2` are enabled respectively, so
+/// a value in `a0` or `a2` is a 16-bit limb and the cell next to it is its
+/// spread. The gates laying out limbs in these columns enable the selectors
+/// on their rows.
+///
+/// Words are assigned as their four limbs on four rows of `(a0, a1)`, low limb
+/// first, which both range checks the limbs and gives their spread form.
+#[derive(Debug, Clone)]
+pub struct SpreadWordConfig<F: FieldExt> {
+    pub s_spread_a0: Selector,
+    pub s_spread_a2: Selector,
+    a0: Column<Advice>,
+    a1: Column<Advice>,
+    _marker: PhantomData<F>
+}
+
+impl<F: FieldExt> SpreadWordConfig<F> {
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a0: Column<Advice>,
+        a1: Column<Advice>,
+        a2: Column<Advice>,
+        a3: Column<Advice>,
+        spread_table: &SpreadTableConfig<F, SPREAD_TABLE_BITS>,
+    ) -> Self {
+        let s_spread_a0 = meta.complex_selector();
+        let s_spread_a2 = meta.complex_selector();
+
+        meta.lookup("Spread Lookup 1", |meta| {
+            let s_spread_a0 = meta.query_selector(s_spread_a0);
+            let a0 = meta.query_advice(a0, Rotation::cur());
+            let a1 = meta.query_advice(a1, Rotation::cur());
+
+            vec![
+                (s_spread_a0.clone() * a0, spread_table.table),
+                (s_spread_a0 * a1, spread_table.spread),
+            ]
+        });
+
+        meta.lookup("Spread Lookup 2", |meta| {
+            let s_spread_a2 = meta.query_selector(s_spread_a2);
+            let a2 = meta.query_advice(a2, Rotation::cur());
+            let a3 = meta.query_advice(a3, Rotation::cur());
+
+            vec![
+                (s_spread_a2.clone() * a2, spread_table.table),
+                (s_spread_a2 * a3, spread_table.spread),
+            ]
+        });
+
+        Self {
+            s_spread_a0,
+            s_spread_a2,
+            a0, a1,
+            _marker: PhantomData
+        }
+    }
+
+    /// Number of rows used by `assign`.
+    pub fn rows() -> usize {
+        4
+    }
+
+    pub fn assign(
+        &self,
+        region: &mut Region<F>,
+        word: Value<Word64>,
+        offset: usize,
+    ) -> Result<AssignedWord64<F>, Error> {
+        let mut limbs = vec![];
+        let mut spread = vec![];
+        for i in 0..4 {
+            let limb = word.map(|word| word.limbs[i]);
+            self.s_spread_a0.enable(region, offset+i)?;
+            limbs.push(region.assign_advice(|| "word limb", self.a0, offset+i, || limb.map(|limb| F::from(limb as u64)))?);
+            spread.push(region.assign_advice(|| "word spread", self.a1, offset+i, || word.map(|word| F::from(word.spread_limbs()[i] as u64)))?);
+        }
+        Ok(AssignedWord64::new(limbs, spread, word))
+    }
+
+    /// Assigns `word` with its limbs constrained to their constant values.
+    pub fn assign_constant(
+        &self,
+        region: &mut Region<F>,
+        word: u64,
+        offset: usize,
+    ) -> Result<AssignedWord64<F>, Error> {
+        let word = Word64::from(word);
+        let assigned = self.assign(region, Value::known(word), offset)?;
+        for (limb_c, limb) in assigned.limbs.iter().zip(word.limbs.iter()) {
+            region.constrain_constant(limb_c.cell(), F::from(*limb as u64))?;
+        }
+        Ok(assigned)
+    }
+}
@@ -0,0 +1,500 @@
+//! SHA-512, SHA-384 and SHA-512/256 on 64-bit words.
+//!
+//! Words are assigned as four 16-bit limbs, which are range checked and
+//! spread through the same 16-bit `SpreadTableConfig` as the SHA-256 circuit.
+//! The sigma functions cut a word into pieces of at most 16 bits at its limb
+//! boundaries and rotation amounts, and the bitwise functions and modular
+//! additions work limb by limb, so no word ever needs a 64-bit spread.
+//!
+//! `Sha512Config` mirrors `Sha2Config`: it has a fixed capacity of
+//! `max_blocks` 1024-bit blocks, every block slot is laid out whether it is
+//! used or not, and the digests are exposed through its `instance` column,
+//! block slot `i` occupying the rows `n * i..n * i + n` for the `n` 64-bit
+//! digest words of the mode.
+//!
+//! `Sha512Table` carries one row per byte of every padded message block, as
+//! `Sha2Table` does: the rows of a call share its `id` and `input_len`, the
+//! bytes are range checked and recomposed into the message words of the
+//! message schedule, and their padding is checked up to the 128-bit length
+//! at the end of the final block. The last row of the final block has
+//! `is_final` set together with the digest, two 64-bit words per `digest`
+//! column, so a consumer circuit can look up
+//! `(id, is_final, input_len, digest)`. Slots past the last input hold dummy
+//! blocks, unterminated blocks of zero bytes numbered as calls of their own.
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Any, Column, ConstraintSystem, Error, Instance},
+};
+
+mod gates;
+mod regions;
+
+use crate::spread_table::{SpreadTableConfig, SPREAD_TABLE_BITS};
+use crate::utils::{pad_message_sha512, IV_384, IV_512, IV_512_256};
+use crate::word::{AssignedWord64, Word64};
+use gates::{
+    add::{ModAddConfig, MAX_TERMS},
+    block_state::BlockStateConfig,
+    spread_word::SpreadWordConfig,
+};
+use regions::{
+    compression::CompressionChip,
+    message_schedule::MessageScheduleChip,
+    table::{TableChip, TABLE_ROWS_PER_BLOCK},
+};
+
+/// Number of bytes in a SHA-512 block.
+pub const SHA512_BLOCK_BYTES: usize = TABLE_ROWS_PER_BLOCK;
+
+#[derive(Clone, Debug)]
+pub struct Sha512Table {
+    pub id: Column<Advice>,
+    pub is_final: Column<Advice>,
+    pub input_len: Column<Advice>,
+    pub index: Column<Advice>,
+    pub input: Column<Advice>,
+    /// The digest on the last row of the final block, two 64-bit words per
+    /// column, big-endian, and zero past the digest words of the mode.
+    pub digest: [Column<Advice>; 4],
+}
+
+impl Sha512Table {
+    pub fn construct<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> Self {
+        let is_final = meta.advice_column();
+        meta.enable_equality(is_final);
+        let digest = [(); 4].map(|_| meta.advice_column());
+        for column in digest.iter() {
+            meta.enable_equality(*column);
+        }
+        let id = meta.advice_column();
+        let input_len = meta.advice_column();
+        let index = meta.advice_column();
+        let input = meta.advice_column();
+
+        Self {
+            id,
+            is_final,
+            input_len,
+            index,
+            input,
+            digest,
+        }
+    }
+
+    pub fn columns(&self) -> Vec<Column<Any>> {
+        let mut columns: Vec<Column<Any>> = vec![
+            self.id.into(),
+            self.is_final.into(),
+            self.input_len.into(),
+            self.index.into(),
+            self.input.into(),
+        ];
+        columns.extend(self.digest.iter().map(|column| Column::<Any>::from(*column)));
+        columns
+    }
+
+    pub fn annotations(&self) -> Vec<String> {
+        let mut annotations = vec![
+            String::from("id"),
+            String::from("is_final"),
+            String::from("input_len"),
+            String::from("index"),
+            String::from("input"),
+        ];
+        annotations.extend((0..4).map(|i| format!("digest_{}", i)));
+        annotations
+    }
+}
+
+/// Variant of SHA-512 hashed by a `Sha512Config`. SHA-384 and SHA-512/256 are
+/// SHA-512 started from another IV, with their digest truncated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sha512Mode {
+    Sha512,
+    Sha384,
+    Sha512Trunc256,
+}
+
+impl Sha512Mode {
+    pub fn iv(&self) -> [u64; 8] {
+        match self {
+            Self::Sha512 => IV_512,
+            Self::Sha384 => IV_384,
+            Self::Sha512Trunc256 => IV_512_256,
+        }
+    }
+
+    /// Number of 64-bit words of the digest.
+    pub fn digest_words(&self) -> usize {
+        match self {
+            Self::Sha512 => 8,
+            Self::Sha384 => 6,
+            Self::Sha512Trunc256 => 4,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Sha512Config<F: FieldExt> {
+    table: Sha512Table,
+    instance: Column<Instance>,
+    spread_table: SpreadTableConfig<F, SPREAD_TABLE_BITS>,
+    message_schedule_chip: MessageScheduleChip<F>,
+    compression_chip: CompressionChip<F>,
+    table_chip: TableChip<F>,
+    block_state: BlockStateConfig<F>,
+    max_blocks: usize,
+    mode: Sha512Mode,
+    /// Rows halo2 reserves at the end of every column for blinding.
+    unusable_rows: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Sha512Config<F> {
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        table: Sha512Table,
+        max_blocks: usize,
+        mode: Sha512Mode,
+    ) -> Self {
+        let mut cols: Vec<Column<Advice>> = vec![];
+        for i in 0..4 + MAX_TERMS {
+            cols.push(meta.advice_column());
+            meta.enable_equality(cols[i]);
+        }
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+        let k = meta.fixed_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let spread_table = SpreadTableConfig::configure(meta);
+        let spread = SpreadWordConfig::configure(meta, cols[0], cols[1], cols[2], cols[3], &spread_table);
+        let add = ModAddConfig::configure(
+            meta, cols[0], cols[1], cols[2], cols[3],
+            [cols[4], cols[5], cols[6], cols[7], cols[8]], k,
+            spread.s_spread_a0, spread.s_spread_a2
+        );
+        let message_schedule_chip = MessageScheduleChip::configure(
+            meta, cols[0], cols[1], cols[2], cols[3], cols[4], spread.clone(), add.clone()
+        );
+        let compression_chip = CompressionChip::configure(
+            meta, cols[0], cols[1], cols[2], cols[3], cols[4], cols[5], cols[6], add,
+            spread.s_spread_a0, spread.s_spread_a2
+        );
+        let table_chip = TableChip::configure(meta, table.clone(), mode);
+        let block_state = BlockStateConfig::configure(
+            meta, cols[0], cols[1], cols[4], cols[5], cols[6], cols[7], cols[8], spread.s_spread_a0
+        );
+        let unusable_rows = meta.blinding_factors() + 1;
+
+        Self {
+            table,
+            instance,
+            spread_table,
+            message_schedule_chip,
+            compression_chip,
+            table_chip,
+            block_state,
+            max_blocks,
+            mode,
+            unusable_rows,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn mode(&self) -> Sha512Mode {
+        self.mode
+    }
+
+    /// Offsets of the message schedule, the compression, the selection of
+    /// the initial state and the digest words within the region of a block,
+    /// followed by the number of rows of the block. The table rows of the
+    /// block live in their own columns and start at the first row of the
+    /// region.
+    fn block_layout(&self) -> (usize, usize, usize, usize, usize) {
+        let schedule = 0;
+        let compression = schedule + self.message_schedule_chip.rows();
+        let init = compression + self.compression_chip.rows();
+        let words = init + BlockStateConfig::<F>::init_rows();
+        let rows = words + BlockStateConfig::<F>::out_rows();
+        debug_assert!(TABLE_ROWS_PER_BLOCK <= rows);
+        (schedule, compression, init, words, rows)
+    }
+
+    /// Number of rows used by the block slots of the circuit.
+    pub fn rows(&self) -> usize {
+        self.block_layout().4 * self.max_blocks
+    }
+
+    /// Smallest `k` the circuit fits in, the spread table included.
+    pub fn min_k(&self) -> u32 {
+        let rows = self.rows().max(1 << SPREAD_TABLE_BITS);
+        let mut k = 0;
+        while (1 << k) < rows + self.unusable_rows {
+            k += 1;
+        }
+        k
+    }
+
+    /// Number of blocks needed to hash `inputs`.
+    pub fn num_blocks(inputs: &[Vec<u8>]) -> usize {
+        inputs.iter().map(|input| pad_message_sha512(input).len() / SHA512_BLOCK_BYTES).sum()
+    }
+
+    /// Checks that `inputs` fit in the capacity of the circuit and that the
+    /// circuit fits in `2^k` rows.
+    pub fn check(&self, inputs: &[Vec<u8>], k: u32) -> Result<(), Error> {
+        if Self::num_blocks(inputs) > self.max_blocks || k < self.min_k() {
+            return Err(Error::NotEnoughRowsAvailable { current_k: k });
+        }
+        Ok(())
+    }
+
+    /// Loads the lookup tables used by the circuit.
+    pub fn load_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.spread_table.load(layouter)?;
+        self.table_chip.load(layouter)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Sha512Witness<F> {
+    pub inputs: Vec<Vec<u8>>,
+    pub _marker: PhantomData<F>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Sha512Chip<F: FieldExt> {
+    config: Sha512Config<F>,
+    data: Sha512Witness<F>,
+}
+
+impl<F: FieldExt> Sha512Chip<F> {
+    pub fn construct(config: Sha512Config<F>, data: Sha512Witness<F>) -> Self {
+        Self { data, config }
+    }
+
+    /// Hashes every input and returns, for every block slot, the 64-bit words
+    /// of its digest masked by its `is_final` flag.
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<Vec<Vec<AssignedCell<F, F>>>, Error> {
+        let iv = self.config.mode.iv();
+
+        // (id, input_len, block_index, is_first, is_final, block, data_len)
+        // of every block slot, followed by dummy blocks up to the capacity of
+        // the circuit. A dummy block is an unterminated block of zero bytes,
+        // so it carries no padding and never outputs a digest. Every dummy
+        // block starts a call of its own, numbered after the inputs.
+        let mut slots = vec![];
+        for (idx, input) in self.data.inputs.iter().enumerate() {
+            let padded = pad_message_sha512(input);
+            let num_blocks = padded.len() / SHA512_BLOCK_BYTES;
+            for (i, block) in padded.chunks(SHA512_BLOCK_BYTES).enumerate() {
+                slots.push((
+                    idx as u64 + 1, input.len() as u64, i, i == 0, i == num_blocks - 1, block.to_vec(),
+                    input.len().saturating_sub(SHA512_BLOCK_BYTES*i).min(SHA512_BLOCK_BYTES)
+                ));
+            }
+        }
+        if slots.len() > self.config.max_blocks {
+            return Err(Error::Synthesis);
+        }
+        let mut next_id = self.data.inputs.len() as u64 + 1;
+        while slots.len() < self.config.max_blocks {
+            slots.push((next_id, 0, 0, true, false, vec![0; SHA512_BLOCK_BYTES], SHA512_BLOCK_BYTES));
+            next_id += 1;
+        }
+
+        let (schedule, compression, init, out, _) = self.config.block_layout();
+        let digest_words = self.config.mode.digest_words();
+        let mut digests = vec![];
+        let mut prev: Option<Vec<AssignedWord64<F>>> = None;
+        let mut prev_row: Option<Vec<AssignedCell<F, F>>> = None;
+        for (s, (id, l, i, is_first, is_final, block, data_len)) in slots.into_iter().enumerate() {
+            let w: Vec<Value<Word64>> = block
+                .chunks(8)
+                .map(|word| Value::known(Word64::from(u64::from_be_bytes(word.try_into().expect("SHA-512 word is 8-bytes")))))
+                .collect();
+
+            let (h_out, last_row, digest) = layouter.assign_region(
+                || format!("SHA512 block {}", s),
+                |mut region| {
+                    let (is_first_c, h_in) = self.config.block_state.assign_init(
+                        &mut region, Value::known(is_first), iv, prev.clone(), init
+                    )?;
+                    if s == 0 {
+                        region.constrain_constant(is_first_c.cell(), F::one())?;
+                    }
+                    let w = self.config.message_schedule_chip.load(&mut region, w.clone(), schedule)?;
+                    let last_row = self.config.table_chip.assign_block(
+                        &mut region, id, l, i, &block, data_len, is_final,
+                        is_first_c, prev_row.clone(), &w[..16], 0
+                    )?;
+                    let h_out = self.config.compression_chip.load(&mut region, &w, h_in, compression)?;
+                    self.config.table_chip.assign_digest(&mut region, &last_row[0], &h_out, 0)?;
+                    let digest = self.config.block_state.assign_out(
+                        &mut region, last_row[0].clone(), &h_out[..digest_words], out
+                    )?;
+                    Ok((h_out, last_row, digest))
+                }
+            )?;
+            digests.push(digest);
+            prev = Some(h_out);
+            prev_row = Some(last_row);
+        }
+
+        Ok(digests)
+    }
+
+    /// Constrains the digest words returned by `load` to the instance column,
+    /// one row per digest word of every block slot.
+    pub fn expose_digests(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        digests: &Vec<Vec<AssignedCell<F, F>>>,
+    ) -> Result<(), Error> {
+        for (i, digest) in digests.iter().enumerate() {
+            for (j, word) in digest.iter().enumerate() {
+                layouter.constrain_instance(word.cell(), self.config.instance, digest.len() * i + j)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use ethers_core::utils::hex;
+    use halo2_proofs::circuit::SimpleFloorPlanner;
+    use halo2_proofs::plonk::Circuit;
+    use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+
+    const MAX_BLOCKS: usize = 3;
+
+    const TWO_BLOCKS: &str = "abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu";
+
+    macro_rules! sha512_test_circuit {
+        ($name:ident, $mode:expr) => {
+            #[derive(Default)]
+            struct $name<F> {
+                inputs: Vec<Vec<u8>>,
+                _marker: PhantomData<F>,
+            }
+
+            impl<F: FieldExt> Circuit<F> for $name<F> {
+                type Config = Sha512Config<F>;
+                type FloorPlanner = SimpleFloorPlanner;
+
+                fn without_witnesses(&self) -> Self {
+                    Self::default()
+                }
+
+                fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                    let table = Sha512Table::construct(meta);
+                    Sha512Config::configure(meta, table, MAX_BLOCKS, $mode)
+                }
+
+                fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+                    config.load_tables(&mut layouter)?;
+                    let chip = Sha512Chip::construct(
+                        config,
+                        Sha512Witness { inputs: self.inputs.clone(), _marker: PhantomData },
+                    );
+                    let digests = chip.load(&mut layouter)?;
+                    chip.expose_digests(&mut layouter, &digests)
+                }
+            }
+        };
+    }
+
+    sha512_test_circuit!(Sha512TestCircuit, Sha512Mode::Sha512);
+    sha512_test_circuit!(Sha384TestCircuit, Sha512Mode::Sha384);
+    sha512_test_circuit!(Sha512Trunc256TestCircuit, Sha512Mode::Sha512Trunc256);
+
+    /// Instance column values exposing the hex `outputs` of `inputs`.
+    fn digest_instances(mode: Sha512Mode, inputs: &[&str], outputs: &[&str]) -> Vec<Fr> {
+        let digest_words = mode.digest_words();
+        let mut instances = vec![];
+        for (input, output) in inputs.iter().zip(outputs.iter()) {
+            let num_blocks = pad_message_sha512(input.as_bytes()).len() / SHA512_BLOCK_BYTES;
+            instances.extend(vec![Fr::zero(); digest_words * (num_blocks - 1)]);
+            instances.extend(
+                hex::decode(output)
+                    .expect("digest is hex")
+                    .chunks(8)
+                    .map(|word| Fr::from(u64::from_be_bytes(word.try_into().expect("SHA-512 word is 8-bytes"))))
+            );
+        }
+        instances.resize(digest_words * MAX_BLOCKS, Fr::zero());
+        instances
+    }
+
+    fn inputs(inputs: &[&str]) -> Vec<Vec<u8>> {
+        inputs.iter().map(|input| input.as_bytes().to_vec()).collect()
+    }
+
+    #[test]
+    fn test_sha512_circuit() {
+        let outputs = [
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+            "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909",
+        ];
+        let circuit = Sha512TestCircuit::<Fr> { inputs: inputs(&["abc", TWO_BLOCKS]), _marker: PhantomData };
+        let instances = digest_instances(Sha512Mode::Sha512, &["abc", TWO_BLOCKS], &outputs);
+        let prover = MockProver::run(17, &circuit, vec![instances]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_sha384_circuit() {
+        let outputs = [
+            "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7",
+            "09330c33f71147e83d192fc782cd1b4753111b173b3b05d22fa08086e3b0f712fcc7c71a557e2db966c3e9fa91746039",
+        ];
+        let circuit = Sha384TestCircuit::<Fr> { inputs: inputs(&["abc", TWO_BLOCKS]), _marker: PhantomData };
+        let instances = digest_instances(Sha512Mode::Sha384, &["abc", TWO_BLOCKS], &outputs);
+        let prover = MockProver::run(17, &circuit, vec![instances]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_sha512_256_circuit() {
+        let outputs = [
+            "53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23",
+            "c672b8d1ef56ed28ab87c3622c5114069bdd3ad7b8f9737498d0c01ecef0967a",
+        ];
+        let circuit = Sha512Trunc256TestCircuit::<Fr> { inputs: inputs(&["abc", ""]), _marker: PhantomData };
+        let instances = digest_instances(Sha512Mode::Sha512Trunc256, &["abc", ""], &outputs);
+        let prover = MockProver::run(17, &circuit, vec![instances]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_sha512_circuit_wrong_digest() {
+        let outputs = [
+            "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e",
+        ];
+        let circuit = Sha512TestCircuit::<Fr> { inputs: inputs(&["abc"]), _marker: PhantomData };
+        let instances = digest_instances(Sha512Mode::Sha512, &["abc"], &outputs);
+        let prover = MockProver::run(17, &circuit, vec![instances]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_sha512_config_check() {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let table = Sha512Table::construct(&mut meta);
+        let config = Sha512Config::configure(&mut meta, table, MAX_BLOCKS, Sha512Mode::Sha512);
+
+        assert_eq!(config.min_k(), 17);
+        assert!(config.check(&inputs(&["abc", TWO_BLOCKS]), 17).is_ok());
+        assert!(config.check(&inputs(&["abc", TWO_BLOCKS]), 16).is_err());
+        assert!(config.check(&inputs(&[TWO_BLOCKS, TWO_BLOCKS]), 17).is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::Region,
+    plonk::{Advice, Column, ConstraintSystem, Selector, Error},
+};
+
+use crate::sha512::gates::{
+    add::ModAddConfig,
+    choice::ChoiceConfig,
+    maj::MajConfig,
+    sigma::{SigmaConfig, SigmaOp},
+};
+use crate::utils::ROUND_CONSTANTS_512;
+use crate::word::AssignedWord64;
+
+/// Number of rounds of the SHA-512 compression function.
+pub const ROUNDS: usize = 80;
+
+#[derive(Debug, Clone)]
+pub struct CompressionChip<F: FieldExt> {
+    add: ModAddConfig<F>,
+    sum0: SigmaConfig<F>,
+    sum1: SigmaConfig<F>,
+    choice: ChoiceConfig<F>,
+    maj: MajConfig<F>,
+}
+
+impl<F: FieldExt> CompressionChip<F> {
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a0: Column<Advice>,
+        a1: Column<Advice>,
+        a2: Column<Advice>,
+        a3: Column<Advice>,
+        a4: Column<Advice>,
+        a5: Column<Advice>,
+        a6: Column<Advice>,
+        add: ModAddConfig<F>,
+        s_spread_a0: Selector,
+        s_spread_a2: Selector,
+    ) -> Self {
+        let sum0 = SigmaConfig::configure(
+            meta, a0, a1, a2, a3, a4,
+            [SigmaOp::Rotate(28), SigmaOp::Rotate(34), SigmaOp::Rotate(39)],
+            s_spread_a0, s_spread_a2
+        );
+        let sum1 = SigmaConfig::configure(
+            meta, a0, a1, a2, a3, a4,
+            [SigmaOp::Rotate(14), SigmaOp::Rotate(18), SigmaOp::Rotate(41)],
+            s_spread_a0, s_spread_a2
+        );
+        let choice = ChoiceConfig::configure(meta, a0, a1, a2, a3, a4, a5, s_spread_a0, s_spread_a2);
+        let maj = MajConfig::configure(meta, a0, a1, a2, a3, a4, a5, a6, s_spread_a0, s_spread_a2);
+
+        Self {
+            add,
+            sum0,
+            sum1,
+            choice,
+            maj,
+        }
+    }
+
+    fn round_rows(&self) -> usize {
+        self.sum0.rows() + self.sum1.rows() + ChoiceConfig::<F>::rows() + MajConfig::<F>::rows() +
+            3 * ModAddConfig::<F>::rows()
+    }
+
+    /// Number of rows used by `load`.
+    pub fn rows(&self) -> usize {
+        ROUNDS * self.round_rows() + 8 * ModAddConfig::<F>::rows()
+    }
+
+    /// Compresses the message schedule `w` of a block into the state `h` and
+    /// returns the new state.
+    pub fn load(
+        &self,
+        region: &mut Region<F>,
+        w: &[AssignedWord64<F>],
+        h: Vec<AssignedWord64<F>>,
+        offset: usize,
+    ) -> Result<Vec<AssignedWord64<F>>, Error> {
+        let mut state = h.clone();
+        let mut row = offset;
+        for (t, k) in ROUND_CONSTANTS_512.iter().enumerate() {
+            let (a, b, c, d) = (&state[0], &state[1], &state[2], &state[3]);
+            let (e, f, g, h) = (&state[4], &state[5], &state[6], &state[7]);
+
+            let s1 = self.sum1.assign(region, e, row)?;
+            row += self.sum1.rows();
+            let (p, q) = self.choice.assign(region, e, f, g, row)?;
+            row += ChoiceConfig::<F>::rows();
+            let h_prime = self.add.assign(region, &[h, &s1, &p, &q, &w[t]], *k, row)?;
+            row += ModAddConfig::<F>::rows();
+
+            let s0 = self.sum0.assign(region, a, row)?;
+            row += self.sum0.rows();
+            let m = self.maj.assign(region, a, b, c, row)?;
+            row += MajConfig::<F>::rows();
+            let a_new = self.add.assign(region, &[&h_prime, &s0, &m], 0, row)?;
+            row += ModAddConfig::<F>::rows();
+            let e_new = self.add.assign(region, &[d, &h_prime], 0, row)?;
+            row += ModAddConfig::<F>::rows();
+
+            state = vec![
+                a_new, a.clone(), b.clone(), c.clone(),
+                e_new, e.clone(), f.clone(), g.clone(),
+            ];
+        }
+
+        let mut digest = vec![];
+        for (h, v) in h.iter().zip(state.iter()) {
+            digest.push(self.add.assign(region, &[h, v], 0, row)?);
+            row += ModAddConfig::<F>::rows();
+        }
+        Ok(digest)
+    }
+}
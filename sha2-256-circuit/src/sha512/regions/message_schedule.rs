@@ -0,0 +1,88 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error},
+};
+
+use crate::sha512::gates::{
+    add::ModAddConfig,
+    sigma::{SigmaConfig, SigmaOp},
+    spread_word::SpreadWordConfig,
+};
+use crate::word::{AssignedWord64, Word64};
+
+/// Number of words in the message schedule of a SHA-512 block.
+pub const SCHEDULE_WORDS: usize = 80;
+
+#[derive(Debug, Clone)]
+pub struct MessageScheduleChip<F: FieldExt> {
+    spread: SpreadWordConfig<F>,
+    add: ModAddConfig<F>,
+    sigma0: SigmaConfig<F>,
+    sigma1: SigmaConfig<F>,
+}
+
+impl<F: FieldExt> MessageScheduleChip<F> {
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a0: Column<Advice>,
+        a1: Column<Advice>,
+        a2: Column<Advice>,
+        a3: Column<Advice>,
+        a4: Column<Advice>,
+        spread: SpreadWordConfig<F>,
+        add: ModAddConfig<F>,
+    ) -> Self {
+        let sigma0 = SigmaConfig::configure(
+            meta, a0, a1, a2, a3, a4,
+            [SigmaOp::Rotate(1), SigmaOp::Rotate(8), SigmaOp::Shift(7)],
+            spread.s_spread_a0, spread.s_spread_a2
+        );
+        let sigma1 = SigmaConfig::configure(
+            meta, a0, a1, a2, a3, a4,
+            [SigmaOp::Rotate(19), SigmaOp::Rotate(61), SigmaOp::Shift(6)],
+            spread.s_spread_a0, spread.s_spread_a2
+        );
+
+        Self {
+            spread,
+            add,
+            sigma0,
+            sigma1,
+        }
+    }
+
+    /// Number of rows used by `load`.
+    pub fn rows(&self) -> usize {
+        16 * SpreadWordConfig::<F>::rows() +
+            (SCHEDULE_WORDS - 16) * (self.sigma0.rows() + self.sigma1.rows() + ModAddConfig::<F>::rows())
+    }
+
+    /// Expands the 16 words of a block into the 80 words `W0..W79` of its
+    /// message schedule.
+    pub fn load(
+        &self,
+        region: &mut Region<F>,
+        w: Vec<Value<Word64>>,
+        offset: usize,
+    ) -> Result<Vec<AssignedWord64<F>>, Error> {
+        let mut w_all = vec![];
+        let mut row = offset;
+        for word in w.into_iter() {
+            w_all.push(self.spread.assign(region, word, row)?);
+            row += SpreadWordConfig::<F>::rows();
+        }
+
+        for i in 16..SCHEDULE_WORDS {
+            let s0 = self.sigma0.assign(region, &w_all[i-15], row)?;
+            row += self.sigma0.rows();
+            let s1 = self.sigma1.assign(region, &w_all[i-2], row)?;
+            row += self.sigma1.rows();
+            let w_new = self.add.assign(region, &[&s1, &w_all[i-7], &s0, &w_all[i-16]], 0, row)?;
+            row += ModAddConfig::<F>::rows();
+            w_all.push(w_new);
+        }
+        Ok(w_all)
+    }
+}
@@ -0,0 +1,3 @@
+pub mod compression;
+pub mod message_schedule;
+pub mod table;
@@ -0,0 +1,552 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Expression, Selector, Error},
+    poly::Rotation,
+};
+
+use crate::gates::byte_decompose::ByteDecomposeConfig;
+use crate::sha512::{Sha512Mode, Sha512Table};
+use crate::utils::create_value_2_check;
+use crate::word::AssignedWord64;
+
+/// Number of table rows reserved for every 1024-bit block, one per message
+/// byte.
+pub const TABLE_ROWS_PER_BLOCK: usize = 128;
+
+/// Row of a block holding the last byte before the length field.
+const LENGTH_FIELD_ROW: usize = TABLE_ROWS_PER_BLOCK - 16;
+
+/// Assigns the table rows of the padded message blocks and binds them to the
+/// message words of the message schedule, as `TableChip` does for SHA-256.
+///
+/// Every row carries `is_padding`, set from the first padding byte on, and
+/// `pad_prev`, the flag of the previous byte of the message. The first rows of
+/// a block also hold in `carry` whether the block starts a message, followed by
+/// `index`, `input_len`, `is_padding`, `is_final` and `id` of the last row of
+/// the previous block, which chains the rows of a message across blocks: a
+/// block continuing a message follows a block that is not final.
+///
+/// The `id` of a call is held on all of its rows, and a block starting a call
+/// increments the `id` of the previous block, from 0 before the first block,
+/// so the calls are numbered `1, 2, ...` and no two calls share an `id`.
+///
+/// The final block ends with the 128-bit big-endian bit length of the message.
+/// Only the final block of a message publishes its digest: the `digest`
+/// columns are zero on the last row of any other block.
+#[derive(Debug, Clone)]
+pub struct TableChip<F: FieldExt> {
+    table: Sha512Table,
+    is_padding: Column<Advice>,
+    pad_prev: Column<Advice>,
+    carry: Column<Advice>,
+    digest_limb: Column<Advice>,
+    s_table: Selector,
+    s_start: Selector,
+    s_link: Selector,
+    s_body: Selector,
+    s_length: Selector,
+    s_digest: Selector,
+    bytes: ByteDecomposeConfig<F>,
+    digest_words: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> TableChip<F> {
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        table: Sha512Table,
+        mode: Sha512Mode,
+    ) -> Self {
+        let digest_words = mode.digest_words();
+        let is_padding = meta.advice_column();
+        let pad_prev = meta.advice_column();
+        let carry = meta.advice_column();
+        let word_limb = meta.advice_column();
+        let digest_limb = meta.advice_column();
+        meta.enable_equality(table.id);
+        meta.enable_equality(table.index);
+        meta.enable_equality(table.input_len);
+        meta.enable_equality(is_padding);
+        meta.enable_equality(carry);
+        meta.enable_equality(word_limb);
+        meta.enable_equality(digest_limb);
+
+        let s_table = meta.selector();
+        let s_start = meta.selector();
+        let s_link = meta.selector();
+        let s_body = meta.selector();
+        let s_length = meta.selector();
+        let s_digest = meta.selector();
+
+        meta.create_gate(
+            "SHA512 Table Row",
+            |meta| {
+                let s_table = meta.query_selector(s_table);
+                let is_final = meta.query_advice(table.is_final, Rotation::cur());
+                let is_padding = meta.query_advice(is_padding, Rotation::cur());
+                let pad_prev = meta.query_advice(pad_prev, Rotation::cur());
+                let index = meta.query_advice(table.index, Rotation::cur());
+                let input_len = meta.query_advice(table.input_len, Rotation::cur());
+                let input = meta.query_advice(table.input, Rotation::cur());
+
+                // Padding starts at most once, at byte `input_len`, with 0x80.
+                let pad_start = is_padding.clone() - pad_prev;
+
+                vec![
+                    s_table.clone() * create_value_2_check(is_final),
+                    s_table.clone() * create_value_2_check(is_padding),
+                    s_table.clone() * create_value_2_check(pad_start.clone()),
+                    s_table.clone() * pad_start.clone() * (input - Expression::Constant(F::from(0x80))),
+                    s_table * pad_start * (index - input_len),
+                ]
+            }
+        );
+
+        meta.create_gate(
+            "SHA512 Table Block Start",
+            |meta| {
+                let s_start = meta.query_selector(s_start);
+                let is_first = meta.query_advice(carry, Rotation::cur());
+                let prev_index = meta.query_advice(carry, Rotation(1));
+                let prev_input_len = meta.query_advice(carry, Rotation(2));
+                let prev_is_padding = meta.query_advice(carry, Rotation(3));
+                let prev_is_final = meta.query_advice(carry, Rotation(4));
+                let prev_id = meta.query_advice(carry, Rotation(5));
+                let pad_prev = meta.query_advice(pad_prev, Rotation::cur());
+                let index = meta.query_advice(table.index, Rotation::cur());
+                let input_len = meta.query_advice(table.input_len, Rotation::cur());
+                let id = meta.query_advice(table.id, Rotation::cur());
+
+                let not_first = Expression::Constant(F::one()) - is_first.clone();
+
+                vec![
+                    s_start.clone() * (id - prev_id - is_first),
+                    s_start.clone() * (pad_prev - not_first.clone() * prev_is_padding),
+                    s_start.clone() * (index - not_first.clone() * (prev_index + Expression::Constant(F::one()))),
+                    s_start.clone() * not_first.clone() * (input_len - prev_input_len),
+                    s_start * not_first * prev_is_final,
+                ]
+            }
+        );
+
+        meta.create_gate(
+            "SHA512 Table Row Link",
+            |meta| {
+                let s_link = meta.query_selector(s_link);
+                let pad_prev = meta.query_advice(pad_prev, Rotation::cur());
+                let is_padding_prev = meta.query_advice(is_padding, Rotation::prev());
+                let index = meta.query_advice(table.index, Rotation::cur());
+                let index_prev = meta.query_advice(table.index, Rotation::prev());
+                let input_len = meta.query_advice(table.input_len, Rotation::cur());
+                let input_len_prev = meta.query_advice(table.input_len, Rotation::prev());
+                let is_final_prev = meta.query_advice(table.is_final, Rotation::prev());
+                let id = meta.query_advice(table.id, Rotation::cur());
+                let id_prev = meta.query_advice(table.id, Rotation::prev());
+
+                vec![
+                    s_link.clone() * (id - id_prev),
+                    s_link.clone() * (pad_prev - is_padding_prev),
+                    s_link.clone() * (index - index_prev - Expression::Constant(F::one())),
+                    s_link.clone() * (input_len - input_len_prev),
+                    s_link * is_final_prev,
+                ]
+            }
+        );
+
+        meta.create_gate(
+            "SHA512 Table Padding Zero",
+            |meta| {
+                let s_body = meta.query_selector(s_body);
+                let pad_prev = meta.query_advice(pad_prev, Rotation::cur());
+                let input = meta.query_advice(table.input, Rotation::cur());
+
+                vec![
+                    s_body * pad_prev * input
+                ]
+            }
+        );
+
+        meta.create_gate(
+            "SHA512 Table Length",
+            |meta| {
+                let s_length = meta.query_selector(s_length);
+                let is_final = meta.query_advice(table.is_final, Rotation::cur());
+                let input_len = meta.query_advice(table.input_len, Rotation::cur());
+                let is_padding = meta.query_advice(is_padding, Rotation(-16));
+
+                // The last 16 bytes of the final block hold the bit length of
+                // the message, and are zero padding in any other block.
+                let mut length = Expression::Constant(F::zero());
+                let mut constraints = vec![
+                    s_length.clone() * (is_padding - is_final.clone()),
+                ];
+                for i in 0..16 {
+                    let pad_prev = meta.query_advice(pad_prev, Rotation(i - 15));
+                    let input = meta.query_advice(table.input, Rotation(i - 15));
+                    constraints.push(
+                        s_length.clone() * (Expression::Constant(F::one()) - is_final.clone()) * pad_prev * input.clone()
+                    );
+                    length = length * Expression::Constant(F::from(1 << 8)) + input;
+                }
+                constraints.push(
+                    s_length * is_final * (length - input_len * Expression::Constant(F::from(8)))
+                );
+                constraints
+            }
+        );
+
+        let bytes = ByteDecomposeConfig::configure(meta, table.input, word_limb);
+
+        meta.create_gate(
+            "SHA512 Table Digest",
+            |meta| {
+                let s_digest = meta.query_selector(s_digest);
+                let is_final = meta.query_advice(table.is_final, Rotation::cur());
+
+                // The 32 limbs of the state are laid out big-endian on the 32
+                // rows ending at the current one, and every digest column
+                // packs the limbs of two words when the block is final, up to
+                // the `digest_words` words of the mode.
+                let limbs: Vec<Expression<F>> = (0..32)
+                    .map(|i| meta.query_advice(digest_limb, Rotation(i - 31)))
+                    .collect();
+                let pack = |limbs: &[Expression<F>]| limbs.iter().fold(
+                    Expression::Constant(F::zero()),
+                    |acc, limb| acc * Expression::Constant(F::from(1 << 16)) + limb.clone()
+                );
+
+                table.digest.iter().enumerate().map(|(k, digest)| {
+                    let digest = meta.query_advice(*digest, Rotation::cur());
+                    if 2 * k < digest_words {
+                        s_digest.clone() * (is_final.clone() * pack(&limbs[8 * k..8 * k + 8]) - digest)
+                    } else {
+                        s_digest.clone() * digest
+                    }
+                }).collect::<Vec<_>>()
+            }
+        );
+
+        Self {
+            table,
+            is_padding,
+            pad_prev,
+            carry,
+            digest_limb,
+            s_table,
+            s_start,
+            s_link,
+            s_body,
+            s_length,
+            s_digest,
+            bytes,
+            digest_words,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn load(
+        &self,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        self.bytes.load(layouter)
+    }
+
+    /// Assigns the rows of one padded block, of which the first `data_len`
+    /// bytes are message bytes, and constrains the 16-bit limbs it decomposes
+    /// into to the 16 words `w` the message schedule starts from.
+    /// `prev` holds the cells returned for the previous block, if any, and
+    /// `is_first` whether this block starts a message.
+    ///
+    /// Returns the `is_final`, `index`, `input_len`, `is_padding` and `id`
+    /// cells of the last row of the block.
+    pub fn assign_block(
+        &self,
+        region: &mut Region<F>,
+        id: u64,
+        input_len: u64,
+        block_index: usize,
+        block: &[u8],
+        data_len: usize,
+        is_final: bool,
+        is_first: AssignedCell<F, F>,
+        prev: Option<Vec<AssignedCell<F, F>>>,
+        w: &[AssignedWord64<F>],
+        offset: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        self.s_start.enable(region, offset)?;
+        self.s_length.enable(region, offset + TABLE_ROWS_PER_BLOCK - 1)?;
+
+        is_first.copy_advice(|| "table carry is_first", region, self.carry, offset)?;
+        let mut pad_prev = Value::known(F::zero());
+        // The carry rows hold `index`, `input_len`, `is_padding` and
+        // `is_final` of the previous block, picked from the cells returned
+        // for it, followed by its `id`.
+        for (i, j) in [1, 2, 3, 0].into_iter().enumerate() {
+            match &prev {
+                Some(prev) => {
+                    let c = prev[j].copy_advice(|| "table carry", region, self.carry, offset+i+1)?;
+                    if j == 3 {
+                        pad_prev = is_first.value().zip(c.value()).map(|(first, pad)| (F::one() - *first) * *pad);
+                    }
+                }
+                None => {
+                    region.assign_advice(|| "table carry", self.carry, offset+i+1, || Value::known(F::zero()))?;
+                }
+            };
+        }
+        match &prev {
+            Some(prev) => prev[4].copy_advice(|| "table carry id", region, self.carry, offset+5)?,
+            None => region.assign_advice_from_constant(|| "table carry id", self.carry, offset+5, F::zero())?,
+        };
+
+        let bytes: Vec<Value<u8>> = block.iter().map(|byte| Value::known(*byte)).collect();
+        let (_, limbs) = self.bytes.assign(region, &bytes, offset)?;
+        // The limbs come out as (lo, hi) halves of 32-bit words, so a 64-bit
+        // word is made of the limbs 2, 3, 0, 1 of its chunk, low limb first.
+        for (limbs, w) in limbs.chunks(4).zip(w.iter()) {
+            for (limb, w) in [&limbs[2], &limbs[3], &limbs[0], &limbs[1]].into_iter().zip(w.limbs.iter()) {
+                region.constrain_equal(limb.cell(), w.cell())?;
+            }
+        }
+
+        let mut last = vec![];
+        for j in 0..block.len() {
+            let row = offset + j;
+            let row_is_final = is_final && j == TABLE_ROWS_PER_BLOCK - 1;
+            let row_is_padding = j >= data_len;
+            self.s_table.enable(region, row)?;
+            if j > 0 {
+                self.s_link.enable(region, row)?;
+            }
+            if j < LENGTH_FIELD_ROW {
+                self.s_body.enable(region, row)?;
+            }
+            let id_c = region.assign_advice(|| "table id", self.table.id, row, || Value::known(F::from(id)))?;
+            let is_final_c = region.assign_advice(|| "table is_final", self.table.is_final, row, || Value::known(F::from(row_is_final as u64)))?;
+            let input_len_c = region.assign_advice(|| "table input_len", self.table.input_len, row, || Value::known(F::from(input_len)))?;
+            let index_c = region.assign_advice(|| "table index", self.table.index, row, || Value::known(F::from((block_index * TABLE_ROWS_PER_BLOCK + j) as u64)))?;
+            region.assign_advice(|| "table pad_prev", self.pad_prev, row, || pad_prev)?;
+            let is_padding_c = region.assign_advice(|| "table is_padding", self.is_padding, row, || Value::known(F::from(row_is_padding as u64)))?;
+            pad_prev = Value::known(F::from(row_is_padding as u64));
+
+            last = vec![is_final_c, index_c, input_len_c, is_padding_c, id_c];
+        }
+        Ok(last)
+    }
+
+    /// Copies the limbs of the final state `h` into the limb column of a
+    /// block's table rows and packs the limbs of the digest into the `digest`
+    /// columns of the last row, two words per column, or zeros when
+    /// `is_final`, the cell returned for that row by `assign_block`, is not
+    /// set.
+    pub fn assign_digest(
+        &self,
+        region: &mut Region<F>,
+        is_final: &AssignedCell<F, F>,
+        h: &[AssignedWord64<F>],
+        offset: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let last = offset + TABLE_ROWS_PER_BLOCK - 1;
+        self.s_digest.enable(region, last)?;
+
+        let limbs: Vec<&AssignedCell<F, F>> = h.iter().flat_map(|h| h.limbs.iter().rev()).collect();
+        let mut acc = Value::known(F::zero());
+        let mut digest = vec![];
+        for (j, limb) in limbs.iter().enumerate() {
+            let limb = limb.copy_advice(|| "table digest limb", region, self.digest_limb, last - 31 + j)?;
+            acc = acc.zip(limb.value()).map(|(acc, limb)| acc * F::from(1 << 16) + *limb);
+            if j % 8 == 7 {
+                let k = j / 8;
+                let packed = if 2 * k < self.digest_words {
+                    acc.zip(is_final.value()).map(|(acc, is_final)| acc * *is_final)
+                } else {
+                    Value::known(F::zero())
+                };
+                digest.push(region.assign_advice(|| "table digest", self.table.digest[k], last, || packed)?);
+                acc = Value::known(F::zero());
+            }
+        }
+        Ok(digest)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::utils::pad_message_sha512;
+    use crate::word::Word64;
+    use halo2_proofs::circuit::{SimpleFloorPlanner, Layouter};
+    use halo2_proofs::plonk::Circuit;
+    use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr as F};
+
+    #[derive(Debug, Clone)]
+    struct TableTestConfig<F: FieldExt> {
+        table_chip: TableChip<F>,
+        a: Column<Advice>,
+    }
+
+    #[derive(Default, Clone)]
+    struct TableTestCircuit<F: FieldExt> {
+        input_len: usize,
+        padded: Vec<u8>,
+        words: Vec<u64>,
+        ids: Vec<u64>,
+        is_first: Vec<bool>,
+        is_final: Vec<bool>,
+        _marker: PhantomData<F>,
+    }
+
+    fn message_words(padded: &[u8]) -> Vec<u64> {
+        padded
+            .chunks(8)
+            .map(|word| u64::from_be_bytes(word.try_into().expect("SHA-512 word is 8-bytes")))
+            .collect()
+    }
+
+    impl<F: FieldExt> TableTestCircuit<F> {
+        fn new(input: &[u8]) -> Self {
+            let padded = pad_message_sha512(input);
+            let num_blocks = padded.len() / TABLE_ROWS_PER_BLOCK;
+            Self {
+                input_len: input.len(),
+                words: message_words(&padded),
+                ids: vec![1; num_blocks],
+                is_first: (0..num_blocks).map(|i| i == 0).collect(),
+                is_final: (0..num_blocks).map(|i| i == num_blocks - 1).collect(),
+                padded,
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl<F: FieldExt> Circuit<F> for TableTestCircuit<F> {
+        type Config = TableTestConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let table = Sha512Table::construct(meta);
+            let table_chip = TableChip::configure(meta, table, Sha512Mode::Sha512);
+            let a = meta.advice_column();
+            meta.enable_equality(a);
+            let constants = meta.fixed_column();
+            meta.enable_constant(constants);
+
+            TableTestConfig { table_chip, a }
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            config.table_chip.load(&mut layouter)?;
+            layouter.assign_region(
+                || "Table Blocks",
+                |mut region| {
+                    let mut prev = None;
+                    for i in 0..self.is_first.len() {
+                        let offset = TABLE_ROWS_PER_BLOCK * i;
+                        let block = &self.padded[offset..offset + TABLE_ROWS_PER_BLOCK];
+                        let is_first = region.assign_advice(
+                            || "is_first", config.a, offset, || Value::known(F::from(self.is_first[i] as u64))
+                        )?;
+                        let mut w = vec![];
+                        for (j, word) in self.words[16 * i..16 * (i + 1)].iter().enumerate() {
+                            let word = Word64::from(*word);
+                            let mut limbs = vec![];
+                            for (l, limb) in word.limbs.iter().enumerate() {
+                                limbs.push(region.assign_advice(
+                                    || "w limb", config.a, offset + 4 * j + l + 1, || Value::known(F::from(*limb as u64))
+                                )?);
+                            }
+                            w.push(AssignedWord64::new(limbs, vec![], Value::known(word)));
+                        }
+                        let data_len = self.input_len.saturating_sub(offset).min(TABLE_ROWS_PER_BLOCK);
+                        prev = Some(config.table_chip.assign_block(
+                            &mut region, self.ids[i], self.input_len as u64, i, block, data_len,
+                            self.is_final[i], is_first, prev, &w, offset
+                        )?);
+                    }
+                    Ok(())
+                }
+            )
+        }
+    }
+
+    #[test]
+    fn test_table_padding() {
+        for len in [0, 1, 111, 112, 127, 128, 239, 240] {
+            let circuit = TableTestCircuit::<F>::new(&vec![0x61; len]);
+            let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()), "input of {} bytes", len);
+        }
+    }
+
+    #[test]
+    fn test_table_wrong_word() {
+        let mut circuit = TableTestCircuit::<F>::new(&[0x61; 10]);
+        circuit.words[1] ^= 1;
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_table_padding_wrong_marker() {
+        let mut circuit = TableTestCircuit::<F>::new(&[0x61; 10]);
+        circuit.padded[10] = 0x81;
+        circuit.words = message_words(&circuit.padded);
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_table_padding_nonzero_fill() {
+        let mut circuit = TableTestCircuit::<F>::new(&[0x61; 10]);
+        circuit.padded[20] = 0x01;
+        circuit.words = message_words(&circuit.padded);
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_table_padding_wrong_length() {
+        let mut circuit = TableTestCircuit::<F>::new(&[0x61; 10]);
+        circuit.padded[127] += 8;
+        circuit.words = message_words(&circuit.padded);
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_table_padding_missing_length_block() {
+        // 112 bytes leave no room for the length field in the first block.
+        let mut circuit = TableTestCircuit::<F>::new(&[0x61; 112]);
+        circuit.is_first.truncate(1);
+        circuit.is_final = vec![true];
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_table_wrong_first_id() {
+        let mut circuit = TableTestCircuit::<F>::new(&[0x61; 10]);
+        circuit.ids[0] = 2;
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_table_id_changes_within_call() {
+        // The second block of the message claims the id of another call.
+        let mut circuit = TableTestCircuit::<F>::new(&[0x61; 200]);
+        circuit.ids[1] = 2;
+        let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,337 @@
+use std::marker::PhantomData;
+
+use crate::utils::{create_interleave_num, create_tag};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector, TableColumn, VirtualCells},
+    poly::Rotation,
+};
+
+use super::{SpreadTableBits, SpreadTableConfig, SPREAD_TABLE_BITS};
+
+/// Lookup table of the bytes, on `2^8` rows. Every byte comes with its spread,
+/// its tag, the tag of the 16-bit values whose high byte it is, and whether it
+/// is nonzero, so the first row is all zeros.
+#[derive(Debug, Clone, Copy)]
+struct ByteSpreadTableConfig {
+    byte: TableColumn,
+    spread: TableColumn,
+    tag: TableColumn,
+    tag_hi: TableColumn,
+    nonzero: TableColumn,
+}
+
+impl ByteSpreadTableConfig {
+    fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            byte: meta.lookup_table_column(),
+            spread: meta.lookup_table_column(),
+            tag: meta.lookup_table_column(),
+            tag_hi: meta.lookup_table_column(),
+            nonzero: meta.lookup_table_column(),
+        }
+    }
+
+    fn load<F: FieldExt>(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "load byte spread table",
+            |mut table| {
+                for byte in 0..(1u16 << 8) {
+                    let offset = byte as usize;
+                    let cells = [
+                        (self.byte, byte as u64),
+                        (self.spread, create_interleave_num(byte as u32) as u64),
+                        (self.tag, create_tag(byte)),
+                        (self.tag_hi, create_tag(byte << 8)),
+                        (self.nonzero, (byte != 0) as u64),
+                    ];
+                    for (column, value) in cells {
+                        table.assign_cell(|| "byte spread", column, offset, || Value::known(F::from(value)))?;
+                    }
+                }
+                Ok(())
+            }
+        )
+    }
+}
+
+/// Cells of the two bytes of a 16-bit value and of their spreads.
+#[derive(Debug, Clone, Copy)]
+struct ByteColumns {
+    lo: Column<Advice>,
+    hi: Column<Advice>,
+    spread_lo: Column<Advice>,
+    spread_hi: Column<Advice>,
+}
+
+impl ByteColumns {
+    fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            lo: meta.advice_column(),
+            hi: meta.advice_column(),
+            spread_lo: meta.advice_column(),
+            spread_hi: meta.advice_column(),
+        }
+    }
+
+    /// Constraints recomposing `value` and its `spread` from the bytes.
+    fn recompose<F: FieldExt>(
+        &self,
+        meta: &mut VirtualCells<'_, F>,
+        value: Expression<F>,
+        spread: Expression<F>,
+    ) -> [Expression<F>; 2] {
+        let lo = meta.query_advice(self.lo, Rotation::cur());
+        let hi = meta.query_advice(self.hi, Rotation::cur());
+        let spread_lo = meta.query_advice(self.spread_lo, Rotation::cur());
+        let spread_hi = meta.query_advice(self.spread_hi, Rotation::cur());
+
+        [
+            lo + Expression::Constant(F::from(1 << 8)) * hi - value,
+            spread_lo + Expression::Constant(F::from(1 << 16)) * spread_hi - spread,
+        ]
+    }
+
+    fn assign<F: FieldExt>(&self, region: &mut Region<F>, offset: usize, value: Value<u16>) -> Result<(), Error> {
+        let lo = value.map(|value| value & 0xFF);
+        let hi = value.map(|value| value >> 8);
+        region.assign_advice(|| "byte lo", self.lo, offset, || lo.map(|lo| F::from(lo as u64)))?;
+        region.assign_advice(|| "byte hi", self.hi, offset, || hi.map(|hi| F::from(hi as u64)))?;
+        region.assign_advice(
+            || "byte spread lo", self.spread_lo, offset,
+            || lo.map(|lo| F::from(create_interleave_num(lo as u32) as u64))
+        )?;
+        region.assign_advice(
+            || "byte spread hi", self.spread_hi, offset,
+            || hi.map(|hi| F::from(create_interleave_num(hi as u32) as u64))
+        )?;
+        Ok(())
+    }
+}
+
+/// Columns splitting the looked up values into bytes with
+/// `SpreadTableBits::Eight`.
+#[derive(Debug, Clone, Copy)]
+struct SplitConfig {
+    table: ByteSpreadTableConfig,
+    /// Bytes of the `(tag, value, spread)` rows, with the tags of the bytes.
+    bytes: ByteColumns,
+    tag_lo: Column<Advice>,
+    tag_hi: Column<Advice>,
+    nonzero: Column<Advice>,
+    /// Bytes of the `(value, spread)` rows of `configure_limb`.
+    limb: ByteColumns,
+}
+
+/// Lookup of the 16-bit values of the SHA-256 gates into the spread table.
+///
+/// The gates enable the lookup on the rows whose `a0`, `a1` and `a2` cells hold
+/// the tag, value and spread of a value of up to 16 bits. With
+/// `SpreadTableBits::Sixteen`, the triple is looked up as a whole in a table of
+/// `2^16` rows. With `SpreadTableBits::Eight`, the value is split into its
+/// bytes `lo` and `hi` on the same row, both looked up in a table of `2^8`
+/// rows, and a gate recomposes the value and its spread from them. The tag of
+/// the value is the tag of `hi << 8` when `hi` is nonzero, and the tag of `lo`
+/// otherwise.
+#[derive(Debug, Clone, Copy)]
+pub struct SpreadLookupConfig<F: FieldExt> {
+    pub s_spread: Selector,
+    table: Option<SpreadTableConfig<F, SPREAD_TABLE_BITS>>,
+    split: Option<SplitConfig>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> SpreadLookupConfig<F> {
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        a0: Column<Advice>,
+        a1: Column<Advice>,
+        a2: Column<Advice>,
+        bits: SpreadTableBits,
+    ) -> Self {
+        let s_spread = meta.complex_selector();
+        let mut table = None;
+        let mut split = None;
+
+        match bits {
+            SpreadTableBits::Sixteen => {
+                let config = SpreadTableConfig::<F, SPREAD_TABLE_BITS>::configure(meta);
+                meta.lookup(
+                    "Consistent Lookup 1",
+                    |meta| {
+                        let s_spread = meta.query_selector(s_spread);
+                        let a0 = meta.query_advice(a0, Rotation::cur());
+                        let a1 = meta.query_advice(a1, Rotation::cur());
+                        let a2 = meta.query_advice(a2, Rotation::cur());
+
+                        vec![
+                            (s_spread.clone() * a0, config.tag),
+                            (s_spread.clone() * a1, config.table),
+                            (s_spread * a2,         config.spread)
+                        ]
+                    }
+                );
+                table = Some(config);
+            }
+            SpreadTableBits::Eight => {
+                let config = SplitConfig {
+                    table: ByteSpreadTableConfig::configure(meta),
+                    bytes: ByteColumns::configure(meta),
+                    tag_lo: meta.advice_column(),
+                    tag_hi: meta.advice_column(),
+                    nonzero: meta.advice_column(),
+                    limb: ByteColumns::configure(meta),
+                };
+
+                meta.create_gate(
+                    "Spread Split",
+                    |meta| {
+                        let s_spread = meta.query_selector(s_spread);
+                        let a0 = meta.query_advice(a0, Rotation::cur());
+                        let a1 = meta.query_advice(a1, Rotation::cur());
+                        let a2 = meta.query_advice(a2, Rotation::cur());
+                        let tag_lo = meta.query_advice(config.tag_lo, Rotation::cur());
+                        let tag_hi = meta.query_advice(config.tag_hi, Rotation::cur());
+                        let nonzero = meta.query_advice(config.nonzero, Rotation::cur());
+
+                        let [value, spread] = config.bytes.recompose(meta, a1, a2);
+                        let tag = tag_hi + (Expression::Constant(F::one()) - nonzero) * tag_lo - a0;
+
+                        vec![
+                            s_spread.clone() * value,
+                            s_spread.clone() * spread,
+                            s_spread * tag,
+                        ]
+                    }
+                );
+
+                meta.lookup(
+                    "Spread Split Lo",
+                    |meta| {
+                        let s_spread = meta.query_selector(s_spread);
+                        let tag = meta.query_advice(config.tag_lo, Rotation::cur());
+                        let lo = meta.query_advice(config.bytes.lo, Rotation::cur());
+                        let spread = meta.query_advice(config.bytes.spread_lo, Rotation::cur());
+
+                        vec![
+                            (s_spread.clone() * tag, config.table.tag),
+                            (s_spread.clone() * lo,  config.table.byte),
+                            (s_spread * spread,      config.table.spread),
+                        ]
+                    }
+                );
+
+                meta.lookup(
+                    "Spread Split Hi",
+                    |meta| {
+                        let s_spread = meta.query_selector(s_spread);
+                        let tag = meta.query_advice(config.tag_hi, Rotation::cur());
+                        let nonzero = meta.query_advice(config.nonzero, Rotation::cur());
+                        let hi = meta.query_advice(config.bytes.hi, Rotation::cur());
+                        let spread = meta.query_advice(config.bytes.spread_hi, Rotation::cur());
+
+                        vec![
+                            (s_spread.clone() * tag,     config.table.tag_hi),
+                            (s_spread.clone() * nonzero, config.table.nonzero),
+                            (s_spread.clone() * hi,      config.table.byte),
+                            (s_spread * spread,          config.table.spread),
+                        ]
+                    }
+                );
+                split = Some(config);
+            }
+        }
+
+        Self {
+            s_spread,
+            table,
+            split,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Looks up the `(value, spread)` pairs of the 16-bit values in `value` and
+    /// `spread` on the rows where `flag` is nonzero. With a split table, the
+    /// bytes of these values are assigned by `assign_limb`.
+    pub fn configure_limb(
+        &self,
+        meta: &mut ConstraintSystem<F>,
+        flag: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
+        value: Column<Advice>,
+        spread: Column<Advice>,
+    ) {
+        if let Some(table) = self.table {
+            meta.lookup("Consistent Lookup 2", |meta| {
+                let flag = flag(meta);
+                let value = meta.query_advice(value, Rotation::cur());
+                let spread = meta.query_advice(spread, Rotation::cur());
+
+                vec![
+                    (flag.clone() * value, table.table),
+                    (flag * spread,        table.spread),
+                ]
+            });
+        }
+
+        if let Some(config) = self.split {
+            meta.create_gate("Spread Split Limb", |meta| {
+                let flag = flag(meta);
+                let value = meta.query_advice(value, Rotation::cur());
+                let spread = meta.query_advice(spread, Rotation::cur());
+
+                config.limb.recompose(meta, value, spread).map(|constraint| flag.clone() * constraint)
+            });
+
+            for (name, byte, spread) in [
+                ("Spread Split Limb Lo", config.limb.lo, config.limb.spread_lo),
+                ("Spread Split Limb Hi", config.limb.hi, config.limb.spread_hi),
+            ] {
+                meta.lookup(name, |meta| {
+                    let flag = flag(meta);
+                    let byte = meta.query_advice(byte, Rotation::cur());
+                    let spread = meta.query_advice(spread, Rotation::cur());
+
+                    vec![
+                        (flag.clone() * byte, config.table.byte),
+                        (flag * spread,       config.table.spread),
+                    ]
+                });
+            }
+        }
+    }
+
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        if let Some(table) = &self.table {
+            table.load(layouter)?;
+        }
+        if let Some(config) = &self.split {
+            config.table.load(layouter)?;
+        }
+        Ok(())
+    }
+
+    /// Enables the lookup of the `(tag, value, spread)` cells of `offset`,
+    /// which hold `value`, and assigns the bytes of `value` with a split table.
+    pub fn enable(&self, region: &mut Region<F>, offset: usize, value: Value<u16>) -> Result<(), Error> {
+        self.s_spread.enable(region, offset)?;
+        if let Some(config) = &self.split {
+            config.bytes.assign(region, offset, value)?;
+            let lo = value.map(|value| value & 0xFF);
+            let hi = value.map(|value| value >> 8);
+            region.assign_advice(|| "spread tag lo", config.tag_lo, offset, || lo.map(|lo| F::from(create_tag(lo))))?;
+            region.assign_advice(|| "spread tag hi", config.tag_hi, offset, || hi.map(|hi| F::from(create_tag(hi << 8))))?;
+            region.assign_advice(|| "spread nonzero", config.nonzero, offset, || hi.map(|hi| F::from((hi != 0) as u64)))?;
+        }
+        Ok(())
+    }
+
+    /// Assigns the bytes of the value looked up by `configure_limb` on
+    /// `offset` with a split table.
+    pub fn assign_limb(&self, region: &mut Region<F>, offset: usize, value: Value<u16>) -> Result<(), Error> {
+        if let Some(config) = &self.split {
+            config.limb.assign(region, offset, value)?;
+        }
+        Ok(())
+    }
+}
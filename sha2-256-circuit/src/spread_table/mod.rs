@@ -1,12 +1,45 @@
 use std::marker::PhantomData;
-use crate::utils::create_interleave_num;
+use crate::utils::{create_interleave_num, create_tag};
 use halo2_proofs::{
     arithmetic::FieldExt,
     circuit::{Layouter, Value},
     plonk::{ConstraintSystem, Error, TableColumn},
 };
 
-#[derive(Debug, Clone)]
+mod lookup;
+
+pub(crate) use lookup::SpreadLookupConfig;
+
+/// Bit size of the spread table of the SHA-2 circuits, whose gates split words
+/// into 16-bit limbs.
+pub(crate) const SPREAD_TABLE_BITS: usize = 16;
+
+/// Bit size of the spread table the SHA-256 gates look their 16-bit values up
+/// in. A table of `2^8` rows lets a circuit fit in a smaller `k`, at the cost
+/// of looking every value up as two bytes in extra columns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpreadTableBits {
+    Sixteen,
+    Eight,
+}
+
+impl SpreadTableBits {
+    pub fn bits(&self) -> usize {
+        match self {
+            Self::Sixteen => SPREAD_TABLE_BITS,
+            Self::Eight => 8,
+        }
+    }
+
+    /// Number of rows of the table.
+    pub fn rows(&self) -> usize {
+        1 << self.bits()
+    }
+}
+
+/// Lookup table of the `(tag, value, spread)` triples of the values of up to
+/// `NUM_BITS` bits, on `2^NUM_BITS` rows.
+#[derive(Debug, Clone, Copy)]
 pub(super) struct SpreadTableConfig<F: FieldExt, const NUM_BITS: usize> {
     pub(super) table: TableColumn,
     pub(super) tag: TableColumn,
@@ -99,7 +132,7 @@ impl <F: FieldExt, const NUM_BITS: usize> SpreadTableConfig<F, NUM_BITS> {
                     || "Number Tag",
                     self.tag, 
                     offset, 
-                    || Value::known(F::from(5 as u64))
+                    || Value::known(F::from(create_tag(((1<<NUM_BITS)-1) as u16)))
                 )?;
                 table.assign_cell(
                     || "Number Table", 
@@ -304,6 +337,28 @@ mod tests {
 
     }
 
+    fn spread_circuit_8_bits(values: &[u16]) -> SpreadCircuit<F, 8> {
+        SpreadCircuit {
+            tags: values.iter().map(|v| Value::known(F::from(create_tag(*v)))).collect(),
+            tables: values.iter().map(|v| Value::known(F::from(*v as u64))).collect(),
+            spreads: values.iter().map(|v| Value::known(F::from(create_interleave_num(*v as u32) as u64))).collect(),
+        }
+    }
+
+    #[test]
+    fn test_spread_table_circuit_8_bits() {
+        // A smaller table fits in a smaller circuit, with the tags of its
+        // values up to its last row.
+        let circuit = spread_circuit_8_bits(&[0, 5, 0b1111111, 0b10000000, 0b11111111]);
+        let k = 9;
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        let circuit = spread_circuit_8_bits(&[0b100000000]);
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
 
 
 }
\ No newline at end of file
@@ -0,0 +1,304 @@
+use crate::utils::{
+    e_and_f,
+    maj_r,
+    ne_and_g_r,
+    pad_message,
+    reduce2,
+    reduce3,
+    reduce4,
+    reduce5,
+    sum0_r,
+    sum1_r,
+    word_limbs,
+    IV,
+    ROUND_CONSTANTS,
+};
+
+fn lo(w: u32) -> u16 {
+    w as u16
+}
+
+fn hi(w: u32) -> u16 {
+    (w >> 16) as u16
+}
+
+fn join(lo: u16, hi: u16) -> u32 {
+    lo as u32 + (hi as u32) * (1 << 16)
+}
+
+/// Values of one compression round, in the representations used by the
+/// gates: spread values are split in low and high limbs, and additions keep
+/// the carry out of their 32 bits.
+#[derive(Debug, Clone, Default)]
+pub struct RoundTrace {
+    /// Working variables `a..h` entering the round.
+    pub state: [u32; 8],
+    pub k: u32,
+    pub w: u32,
+    /// Sums of the spread rotations of `a` and `e`.
+    pub sum0_r: (u32, u32),
+    pub sum1_r: (u32, u32),
+    pub sum0: u32,
+    pub sum1: u32,
+    /// Sums of the spread `e` and `f`, of the spread `!e` and `g`, and of the
+    /// spread `a`, `b` and `c`.
+    pub ch_p_r: (u32, u32),
+    pub ch_q_r: (u32, u32),
+    pub maj_r: (u32, u32),
+    pub ch: u32,
+    pub maj: u32,
+    /// `h + sum1 + ch + k + w`.
+    pub h_prime: u32,
+    pub h_prime_carry: u64,
+    /// `d + h_prime`.
+    pub e_new: u32,
+    pub e_new_carry: u64,
+    /// `sum0 + maj + h_prime`.
+    pub a_new: u32,
+    pub a_new_carry: u64,
+}
+
+impl RoundTrace {
+    fn new(state: [u32; 8], k: u32, w: u32) -> Self {
+        let [a, b, c, d, e, f, g, h] = state;
+
+        let sum0_r = sum0_r(lo(a), hi(a));
+        let sum1_r = sum1_r(lo(e), hi(e));
+        let sum0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let sum1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch_p_r = e_and_f(lo(e), hi(e), lo(f), hi(f));
+        let ch_q_r = ne_and_g_r(lo(e), hi(e), lo(g), hi(g));
+        let maj_r = maj_r(lo(a), hi(a), lo(b), hi(b), lo(c), hi(c));
+        let ch = (e & f) ^ (!e & g);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+
+        let (h_prime_lo, h_prime_hi, h_prime_carry) = reduce5(h, ch, sum1, k, w);
+        let h_prime = join(h_prime_lo, h_prime_hi);
+        let (e_new_lo, e_new_hi, e_new_carry) = reduce2(d, h_prime);
+        let (a_new_lo, a_new_hi, a_new_carry) = reduce3(sum0, maj, h_prime);
+
+        Self {
+            state,
+            k,
+            w,
+            sum0_r,
+            sum1_r,
+            sum0,
+            sum1,
+            ch_p_r,
+            ch_q_r,
+            maj_r,
+            ch,
+            maj,
+            h_prime,
+            h_prime_carry,
+            e_new: join(e_new_lo, e_new_hi),
+            e_new_carry,
+            a_new: join(a_new_lo, a_new_hi),
+            a_new_carry,
+        }
+    }
+
+    /// Working variables leaving the round.
+    pub fn next_state(&self) -> [u32; 8] {
+        let [a, b, c, _, e, f, g, _] = self.state;
+        [self.a_new, a, b, c, self.e_new, e, f, g]
+    }
+}
+
+/// Trace of the compression of one 512-bit block.
+#[derive(Debug, Clone, Default)]
+pub struct BlockTrace {
+    pub block: Vec<u8>,
+    /// Message schedule, with the `sigma0(W[i-15])`, `sigma1(W[i-2])` and
+    /// carries of the words computed from `i = 16` on.
+    pub w: Vec<u32>,
+    pub sigma0: Vec<u32>,
+    pub sigma1: Vec<u32>,
+    pub w_carry: Vec<u64>,
+    pub h_in: [u32; 8],
+    pub rounds: Vec<RoundTrace>,
+    /// `h_in` plus the final working variables, and the carries of the sums.
+    pub h_out: [u32; 8],
+    pub h_carry: [u64; 8],
+}
+
+impl BlockTrace {
+    pub fn new(block: &[u8], h_in: [u32; 8]) -> Self {
+        let mut w = vec![];
+        let mut sigma0 = vec![0; 16];
+        let mut sigma1 = vec![0; 16];
+        let mut w_carry = vec![0; 16];
+        for word in block.chunks(4) {
+            w.push(u32::from_be_bytes(word.try_into().expect("SHA-256 word is 4-bytes")));
+        }
+        for i in 16..64 {
+            let s0 = w[i-15].rotate_right(7) ^ w[i-15].rotate_right(18) ^ (w[i-15] >> 3);
+            let s1 = w[i-2].rotate_right(17) ^ w[i-2].rotate_right(19) ^ (w[i-2] >> 10);
+            let (w_n, carry) = reduce4(s0, s1, w[i-16], w[i-7]);
+            w.push(w_n);
+            sigma0.push(s0);
+            sigma1.push(s1);
+            w_carry.push(carry);
+        }
+
+        let mut rounds: Vec<RoundTrace> = vec![];
+        let mut state = h_in;
+        for i in 0..64 {
+            let round = RoundTrace::new(state, ROUND_CONSTANTS[i], w[i]);
+            state = round.next_state();
+            rounds.push(round);
+        }
+
+        let mut h_out = [0; 8];
+        let mut h_carry = [0; 8];
+        for i in 0..8 {
+            let (h_lo, h_hi, carry) = reduce2(h_in[i], state[i]);
+            h_out[i] = join(h_lo, h_hi);
+            h_carry[i] = carry;
+        }
+
+        Self {
+            block: block.to_vec(),
+            w,
+            sigma0,
+            sigma1,
+            w_carry,
+            h_in,
+            rounds,
+            h_out,
+            h_carry,
+        }
+    }
+
+    /// Limbs of the 16 message words, low limb first.
+    pub fn w_limbs(&self) -> Vec<u16> {
+        word_limbs(&self.w[0..16])
+    }
+
+    pub fn h_in_limbs(&self) -> Vec<u16> {
+        word_limbs(&self.h_in)
+    }
+
+    pub fn h_out_limbs(&self) -> Vec<u16> {
+        word_limbs(&self.h_out)
+    }
+}
+
+/// Trace of the SHA-256 hash of a message, one `BlockTrace` per block of the
+/// padded message.
+#[derive(Debug, Clone, Default)]
+pub struct Sha256Trace {
+    pub input: Vec<u8>,
+    pub padded: Vec<u8>,
+    pub blocks: Vec<BlockTrace>,
+}
+
+impl Sha256Trace {
+    pub fn new(input: &[u8]) -> Self {
+        Self::with_iv(input, IV)
+    }
+
+    /// Trace of the compression function started from `iv` instead of the
+    /// SHA-256 IV, as done by SHA-224.
+    pub fn with_iv(input: &[u8], iv: [u32; 8]) -> Self {
+        let padded = pad_message(input);
+        let mut blocks: Vec<BlockTrace> = vec![];
+        let mut h = iv;
+        for block in padded.chunks(64) {
+            let trace = BlockTrace::new(block, h);
+            h = trace.h_out;
+            blocks.push(trace);
+        }
+
+        Self {
+            input: input.to_vec(),
+            padded,
+            blocks,
+        }
+    }
+
+    pub fn digest(&self) -> [u8; 32] {
+        let h = self.blocks.last().expect("padded message has a block").h_out;
+        let mut digest = [0u8; 32];
+        for (i, word) in h.iter().enumerate() {
+            digest[4*i..4*i+4].copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::utils::{even_bit, odd_bit, message_words, IV_224};
+
+    fn hex(digest: [u8; 32]) -> String {
+        digest.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_trace_digest() {
+        let vectors = [
+            (
+                "",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                "abc",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+            (
+                "abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+                "cf5b16a778af8380036ce59e7b0492370b249b11e8f07a51afac45037afee9d1",
+            ),
+        ];
+        for (input, output) in vectors {
+            assert_eq!(hex(Sha256Trace::new(input.as_bytes()).digest()), output);
+        }
+        assert_eq!(
+            hex(Sha256Trace::new(&vec![b'a'; 1_000]).digest()),
+            "41edece42d63e8d9bf515a9ba6932e1c20cbc9f5a5d134645adb5db1b9737ea3"
+        );
+    }
+
+    #[test]
+    fn test_trace_digest_224() {
+        let vectors = [
+            ("", "d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f"),
+            ("abc", "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7"),
+        ];
+        for (input, output) in vectors {
+            assert_eq!(hex(Sha256Trace::with_iv(input.as_bytes(), IV_224).digest())[..56], *output);
+        }
+    }
+
+    #[test]
+    fn test_trace_intermediates() {
+        let trace = Sha256Trace::new(b"abc");
+        assert_eq!(trace.blocks.len(), 1);
+
+        let block = &trace.blocks[0];
+        assert_eq!(block.w_limbs(), message_words(&trace.padded));
+        assert_eq!(block.h_in_limbs(), word_limbs(&IV));
+        for round in block.rounds.iter() {
+            let [a, b, c, _, e, f, g, _] = round.state;
+            // Even bits of the spread sums are the XOR, odd bits the majority.
+            assert_eq!(join(even_bit(round.sum0_r.0), even_bit(round.sum0_r.1)), round.sum0);
+            assert_eq!(join(even_bit(round.sum1_r.0), even_bit(round.sum1_r.1)), round.sum1);
+            assert_eq!(join(odd_bit(round.maj_r.0), odd_bit(round.maj_r.1)), round.maj);
+            assert_eq!(join(odd_bit(round.ch_p_r.0), odd_bit(round.ch_p_r.1)), e & f);
+            assert_eq!(join(odd_bit(round.ch_q_r.0), odd_bit(round.ch_q_r.1)), !e & g);
+            assert_eq!(round.maj, (a & b) ^ (a & c) ^ (b & c));
+            assert_eq!(round.ch, (e & f) ^ (!e & g));
+        }
+        for i in 0..63 {
+            assert_eq!(block.rounds[i].next_state(), block.rounds[i + 1].state);
+        }
+    }
+}
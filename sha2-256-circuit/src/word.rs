@@ -0,0 +1,161 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Value},
+};
+
+use crate::utils::create_interleave_num;
+
+/// A 32-bit word split in the two 16-bit limbs it is assigned as.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Word32 {
+    pub lo: u16,
+    pub hi: u16,
+}
+
+impl Word32 {
+    pub fn new(lo: u16, hi: u16) -> Self {
+        Self { lo, hi }
+    }
+
+    pub fn value(&self) -> u32 {
+        self.lo as u32 + self.hi as u32 * ( 1<<16 )
+    }
+
+    pub fn spread_lo(&self) -> u32 {
+        create_interleave_num(self.lo as u32)
+    }
+
+    pub fn spread_hi(&self) -> u32 {
+        create_interleave_num(self.hi as u32)
+    }
+}
+
+impl From<u32> for Word32 {
+    fn from(word: u32) -> Self {
+        Self::new(word as u16, (word >> 16) as u16)
+    }
+}
+
+impl From<Word32> for u32 {
+    fn from(word: Word32) -> Self {
+        word.value()
+    }
+}
+
+/// A 32-bit word assigned as its two 16-bit limbs.
+#[derive(Debug, Clone)]
+pub struct AssignedWord<F: FieldExt> {
+    pub lo: AssignedCell<F, F>,
+    pub hi: AssignedCell<F, F>,
+    pub value: Value<Word32>,
+}
+
+impl<F: FieldExt> AssignedWord<F> {
+    pub fn new(lo: AssignedCell<F, F>, hi: AssignedCell<F, F>, value: Value<Word32>) -> Self {
+        Self { lo, hi, value }
+    }
+}
+
+/// A 32-bit word decomposed by `DecomposeABCConfig` or `DecomposeEFGConfig`,
+/// which also carries the spread of its limbs as looked up in the spread
+/// table. The gates combining the spread forms of several words, `MajConfig`
+/// and `ChoiceConfig`, take this type so they cannot be fed a word that has
+/// not been decomposed.
+#[derive(Debug, Clone)]
+pub struct AssignedWord32<F: FieldExt> {
+    pub lo: AssignedCell<F, F>,
+    pub hi: AssignedCell<F, F>,
+    pub spread_lo: AssignedCell<F, F>,
+    pub spread_hi: AssignedCell<F, F>,
+    pub value: Value<Word32>,
+}
+
+impl<F: FieldExt> AssignedWord32<F> {
+    /// The limbs of the word, without their spread.
+    pub fn word(&self) -> AssignedWord<F> {
+        AssignedWord::new(self.lo.clone(), self.hi.clone(), self.value)
+    }
+
+    /// The same word with its spread limbs replaced by copies of them, as
+    /// returned by the gates that copy the spread limbs in their rows.
+    pub fn with_spread(&self, spread_lo: AssignedCell<F, F>, spread_hi: AssignedCell<F, F>) -> Self {
+        Self {
+            lo: self.lo.clone(),
+            hi: self.hi.clone(),
+            spread_lo,
+            spread_hi,
+            value: self.value,
+        }
+    }
+}
+
+/// A 64-bit word split in the four 16-bit limbs it is assigned as, low limb
+/// first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Word64 {
+    pub limbs: [u16; 4],
+}
+
+impl Word64 {
+    pub fn new(limbs: [u16; 4]) -> Self {
+        Self { limbs }
+    }
+
+    pub fn value(&self) -> u64 {
+        self.limbs.iter().rev().fold(0, |acc, limb| (acc << 16) + *limb as u64)
+    }
+
+    pub fn spread_limbs(&self) -> [u32; 4] {
+        self.limbs.map(|limb| create_interleave_num(limb as u32))
+    }
+}
+
+impl From<u64> for Word64 {
+    fn from(word: u64) -> Self {
+        Self::new([word as u16, (word >> 16) as u16, (word >> 32) as u16, (word >> 48) as u16])
+    }
+}
+
+impl From<Word64> for u64 {
+    fn from(word: Word64) -> Self {
+        word.value()
+    }
+}
+
+/// A 64-bit word assigned as its four 16-bit limbs on the lookup columns of a
+/// `Sha512Config`, next to the spread of every limb.
+#[derive(Debug, Clone)]
+pub struct AssignedWord64<F: FieldExt> {
+    pub limbs: Vec<AssignedCell<F, F>>,
+    pub spread: Vec<AssignedCell<F, F>>,
+    pub value: Value<Word64>,
+}
+
+impl<F: FieldExt> AssignedWord64<F> {
+    pub fn new(limbs: Vec<AssignedCell<F, F>>, spread: Vec<AssignedCell<F, F>>, value: Value<Word64>) -> Self {
+        Self { limbs, spread, value }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_word32_limbs() {
+        let word = Word32::from(0x6a09e667);
+        assert_eq!(word, Word32::new(0xe667, 0x6a09));
+        assert_eq!(u32::from(word), 0x6a09e667);
+        assert_eq!(word.spread_lo(), create_interleave_num(0xe667));
+        assert_eq!(word.spread_hi(), create_interleave_num(0x6a09));
+    }
+
+    #[test]
+    fn test_word64_limbs() {
+        let word = Word64::from(0x6a09e667f3bcc908);
+        assert_eq!(word, Word64::new([0xc908, 0xf3bc, 0xe667, 0x6a09]));
+        assert_eq!(u64::from(word), 0x6a09e667f3bcc908);
+        assert_eq!(word.spread_limbs()[3], create_interleave_num(0x6a09));
+    }
+}